@@ -0,0 +1,212 @@
+//! A normalized text form of a playlist, which is used to compare playlists
+//! semantically and to compute a stable fingerprint.
+use std::borrow::Cow;
+
+use crate::attribute::AttributePairs;
+use crate::tags::ExtXVersion;
+
+/// Returns the canonical form of a serialized playlist, that does not contain
+/// the `unknown` tags, followed by the sorted `unknown` tags.
+///
+/// In the canonical form
+///
+/// - the `EXT-X-VERSION` tag is removed, because it is derived from the
+///   other tags,
+/// - the attributes of an attribute list are sorted by name,
+/// - decimal numbers do not have trailing zeros and
+/// - hexadecimal numbers are lowercase.
+///
+/// Numbers are only normalized outside of quoted strings, which are kept
+/// byte for byte including their quotes (for example `URI="0XAB.ts"` or
+/// `NAME="1.0"`), so a quoted string can not be confused with other
+/// attributes (`X-A="1,X-B=2"` is a single attribute).
+pub(crate) fn canonical_form(playlist: &str, unknown: &[Cow<'_, str>]) -> String {
+    let mut result = String::new();
+
+    for line in playlist
+        .lines()
+        .filter(|line| !line.starts_with(ExtXVersion::PREFIX))
+    {
+        result.push_str(&canonical_line(line));
+        result.push('\n');
+    }
+
+    let mut unknown = unknown
+        .iter()
+        .map(|line| canonical_line(line))
+        .collect::<Vec<_>>();
+
+    unknown.sort();
+
+    for line in unknown {
+        result.push_str(&line);
+        result.push('\n');
+    }
+
+    result
+}
+
+/// Returns the 64 bit FNV-1a hash of the input, which (unlike the hashers of
+/// the standard library) does not depend on the platform or the compiler.
+pub(crate) fn fingerprint(input: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    input.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+fn canonical_line(line: &str) -> String {
+    let line = line.trim();
+
+    let (tag, value) = {
+        if !line.starts_with('#') {
+            return line.to_string();
+        } else if let Some(index) = line.find(':') {
+            (&line[..=index], &line[index + 1..])
+        } else {
+            return line.to_string();
+        }
+    };
+
+    let mut attributes = AttributePairs::new(value).collect::<Vec<_>>();
+
+    let is_attribute_list = !attributes.is_empty()
+        && attributes.iter().all(|(key, _)| {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+        });
+
+    if is_attribute_list {
+        attributes.sort_by_key(|(key, _)| *key);
+
+        let attributes = attributes
+            .into_iter()
+            .map(|(key, value)| {
+                if value.starts_with('"') {
+                    format!("{}={}", key, value)
+                } else {
+                    format!("{}={}", key, canonical_value(value))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        format!("{}{}", tag, attributes.join(","))
+    } else {
+        // for example `#EXTINF:<duration>,[<title>]`:
+        let mut parts = value.splitn(2, ',');
+        let first = canonical_value(parts.next().unwrap_or_default());
+
+        match parts.next() {
+            Some(rest) => format!("{}{},{}", tag, first, rest),
+            None => format!("{}{}", tag, first),
+        }
+    }
+}
+
+/// Normalizes decimal and hexadecimal numbers, other values are returned
+/// unchanged.
+fn canonical_value(value: &str) -> String {
+    if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return format!("0x{}", hex.to_ascii_lowercase());
+        }
+    }
+
+    let (sign, number) = {
+        if let Some(number) = value.strip_prefix('-') {
+            ("-", number)
+        } else {
+            ("", value)
+        }
+    };
+
+    let mut parts = number.splitn(2, '.');
+    let integer = parts.next().unwrap_or_default();
+    let fraction = parts.next().unwrap_or_default();
+
+    let is_decimal = !integer.is_empty()
+        && integer.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit());
+
+    if !is_decimal {
+        return value.to_string();
+    }
+
+    let integer = integer.trim_start_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+    let fraction = fraction.trim_end_matches('0');
+
+    let sign = if integer == "0" && fraction.is_empty() {
+        ""
+    } else {
+        sign
+    };
+
+    if fraction.is_empty() {
+        format!("{}{}", sign, integer)
+    } else {
+        format!("{}{}.{}", sign, integer, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_canonical_value() {
+        assert_eq!(canonical_value("10.000"), "10");
+        assert_eq!(canonical_value("010.500"), "10.5");
+        assert_eq!(canonical_value("-0.0"), "0");
+        assert_eq!(canonical_value("-2.50"), "-2.5");
+        assert_eq!(canonical_value("0XAB12"), "0xab12");
+        assert_eq!(canonical_value("avc1.4d401f"), "avc1.4d401f");
+        assert_eq!(canonical_value("1280x720"), "1280x720");
+        assert_eq!(canonical_value(""), "");
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        assert_eq!(
+            canonical_form(
+                concat!(
+                    "#EXTM3U\n",
+                    "#EXT-X-VERSION:3\n",
+                    "#EXT-X-TARGETDURATION:10\n",
+                    "#EXTINF:9.500,title\n",
+                    "a.ts\n",
+                ),
+                &[
+                    "#X-B:B=\"1.0\",A=2.0".into(),
+                    "#X-A".into(),
+                    "#X-C:B=0XAB,A=\"0XAB\"".into()
+                ]
+            ),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXTINF:9.5,title\n",
+                "a.ts\n",
+                "#X-A\n",
+                "#X-B:A=2,B=\"1.0\"\n",
+                "#X-C:A=\"0XAB\",B=0xab\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        // test vectors of the reference implementation
+        assert_eq!(fingerprint(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fingerprint("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fingerprint("foobar"), 0x8594_4171_f739_67e8);
+    }
+}
@@ -0,0 +1,699 @@
+//! Structural differences between two versions of a playlist.
+//!
+//! A [`MasterPlaylistDiff`] lists the variant streams and renditions, that
+//! have been added, removed or changed (by attribute), and a
+//! [`MediaPlaylistDiff`] lists the segments, that have been added, removed or
+//! changed (by tag), together with the changed playlist tags.
+//!
+//! Both implement [`Display`], which prints one line per change.
+//!
+//! # Example
+//!
+//! ```
+//! use core::convert::TryFrom;
+//! use hls_m3u8::MasterPlaylist;
+//!
+//! let old = MasterPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-STREAM-INF:BANDWIDTH=1000000\n",
+//!     "low.m3u8\n",
+//!     "#EXT-X-STREAM-INF:BANDWIDTH=2000000\n",
+//!     "mid.m3u8\n",
+//! ))?;
+//!
+//! let new = MasterPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-STREAM-INF:BANDWIDTH=2500000\n",
+//!     "mid.m3u8\n",
+//!     "#EXT-X-STREAM-INF:BANDWIDTH=5000000\n",
+//!     "high.m3u8\n",
+//! ))?;
+//!
+//! assert_eq!(
+//!     old.diff(&new).to_string(),
+//!     concat!(
+//!         "- EXT-X-STREAM-INF URI=\"low.m3u8\"\n",
+//!         "~ EXT-X-STREAM-INF URI=\"mid.m3u8\"\n",
+//!         "    BANDWIDTH: 2000000 -> 2500000\n",
+//!         "+ EXT-X-STREAM-INF URI=\"high.m3u8\"\n",
+//!     )
+//! );
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+//!
+//! [`Display`]: std::fmt::Display
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+use crate::attribute::AttributePairs;
+use crate::tags::{
+    ExtXDiscontinuitySequence, ExtXEndList, ExtXIFramesOnly, ExtXIndependentSegments,
+    ExtXMediaSequence, ExtXTargetDuration, ExtXVersion,
+};
+use crate::types::ProtocolVersion;
+use crate::utils::quote;
+use crate::{MasterPlaylist, MediaPlaylist, MediaSegment, RequiredVersion};
+
+/// The named values of a tag or an entry, in the order in which they are
+/// written.
+type Fields = Vec<(String, String)>;
+
+/// The change of a single attribute or tag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttributeChange {
+    /// The name of the attribute (for example `BANDWIDTH`) or the tag (for
+    /// example `EXT-X-TARGETDURATION`).
+    pub name: String,
+    /// The old value or `None`, if the attribute has been added.
+    pub old: Option<String>,
+    /// The new value or `None`, if the attribute has been removed.
+    pub new: Option<String>,
+}
+
+impl fmt::Display for AttributeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "(none)".into());
+
+        write!(f, "{}: {} -> {}", self.name, value(&self.old), value(&self.new))
+    }
+}
+
+/// The change of an entry, which is identified by a key (for example a
+/// variant stream by its URI or a segment by its number).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change<K> {
+    /// The entry only exists in the new playlist.
+    Added(K),
+    /// The entry only exists in the old playlist.
+    Removed(K),
+    /// The entry exists in both playlists, but some of its attributes have
+    /// been changed.
+    Changed {
+        /// The key of the entry.
+        key: K,
+        /// The changed attributes.
+        attributes: Vec<AttributeChange>,
+    },
+}
+
+impl<K> Change<K> {
+    /// Returns the key of the changed entry.
+    #[must_use]
+    pub const fn key(&self) -> &K {
+        match self {
+            Self::Added(key) | Self::Removed(key) | Self::Changed { key, .. } => key,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, key: impl fmt::Display) -> fmt::Result {
+        match self {
+            Self::Added(_) => writeln!(f, "+ {}", key),
+            Self::Removed(_) => writeln!(f, "- {}", key),
+            Self::Changed { attributes, .. } => {
+                writeln!(f, "~ {}", key)?;
+
+                for attribute in attributes {
+                    writeln!(f, "    {}", attribute)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The structural difference between two [`MasterPlaylist`]s, which is
+/// returned by [`MasterPlaylist::diff`].
+///
+/// Variant streams are identified by their tag and URI, renditions by their
+/// `TYPE`, `GROUP-ID` and `NAME`, session data by its `DATA-ID` and
+/// `LANGUAGE` and session keys by their `URI` and `KEYFORMAT`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MasterPlaylistDiff {
+    /// The changed tags, that apply to the entire playlist.
+    pub tags: Vec<AttributeChange>,
+    /// The changed [`ExtXMedia`] tags.
+    ///
+    /// [`ExtXMedia`]: crate::tags::ExtXMedia
+    pub media: Vec<Change<String>>,
+    /// The changed [`VariantStream`]s.
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    pub variant_streams: Vec<Change<String>>,
+    /// The changed [`ExtXSessionData`] tags.
+    ///
+    /// [`ExtXSessionData`]: crate::tags::ExtXSessionData
+    pub session_data: Vec<Change<String>>,
+    /// The changed [`ExtXSessionKey`] tags.
+    ///
+    /// [`ExtXSessionKey`]: crate::tags::ExtXSessionKey
+    pub session_keys: Vec<Change<String>>,
+}
+
+impl MasterPlaylistDiff {
+    pub(crate) fn new(old: &MasterPlaylist<'_>, new: &MasterPlaylist<'_>) -> Self {
+        let entries = |playlist: &MasterPlaylist<'_>| {
+            (
+                playlist
+                    .media
+                    .iter()
+                    .map(|value| entry(value, &["TYPE", "GROUP-ID", "NAME"]))
+                    .collect::<Vec<_>>(),
+                playlist
+                    .variant_streams
+                    .iter()
+                    .map(|value| entry(value, &["URI"]))
+                    .collect::<Vec<_>>(),
+                playlist
+                    .session_data
+                    .iter()
+                    .map(|value| entry(value, &["DATA-ID", "LANGUAGE"]))
+                    .collect::<Vec<_>>(),
+                playlist
+                    .session_keys
+                    .iter()
+                    .map(|value| entry(value, &["URI", "KEYFORMAT"]))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let (old_media, old_variants, old_session_data, old_session_keys) = entries(old);
+        let (new_media, new_variants, new_session_data, new_session_keys) = entries(new);
+
+        Self {
+            tags: diff_fields(&master_tags(old), &master_tags(new)),
+            media: diff_entries(old_media, new_media),
+            variant_streams: diff_entries(old_variants, new_variants),
+            session_data: diff_entries(old_session_data, new_session_data),
+            session_keys: diff_entries(old_session_keys, new_session_keys),
+        }
+    }
+
+    /// Returns `true`, if the playlists are structurally equal.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.media.is_empty()
+            && self.variant_streams.is_empty()
+            && self.session_data.is_empty()
+            && self.session_keys.is_empty()
+    }
+}
+
+impl fmt::Display for MasterPlaylistDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for tag in &self.tags {
+            writeln!(f, "{}", tag)?;
+        }
+
+        for change in self
+            .media
+            .iter()
+            .chain(&self.variant_streams)
+            .chain(&self.session_data)
+            .chain(&self.session_keys)
+        {
+            change.write(f, change.key())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The structural difference between two [`MediaPlaylist`]s, which is
+/// returned by [`MediaPlaylist::diff`].
+///
+/// The segments are identified by their [`MediaSegment::number`] and the tags
+/// of a segment include the [`ExtXKey`]s, that apply to the segment.
+///
+/// # Example
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use hls_m3u8::MediaPlaylist;
+///
+/// let old = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXTINF:10,\n",
+///     "a.ts\n",
+///     "#EXTINF:10,\n",
+///     "b.ts\n",
+/// ))?;
+///
+/// let new = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-MEDIA-SEQUENCE:1\n",
+///     "#EXTINF:9.5,\n",
+///     "b.ts\n",
+///     "#EXTINF:10,\n",
+///     "c.ts\n",
+///     "#EXT-X-ENDLIST\n",
+/// ))?;
+///
+/// let diff = old.diff(&new);
+///
+/// assert_eq!(
+///     diff.to_string(),
+///     concat!(
+///         "EXT-X-VERSION: (none) -> 3\n",
+///         "EXT-X-MEDIA-SEQUENCE: (none) -> 1\n",
+///         "EXT-X-ENDLIST: (none) -> YES\n",
+///         "- segment 0\n",
+///         "~ segment 1\n",
+///         "    EXTINF: 10 -> 9.5\n",
+///         "+ segment 2\n",
+///     )
+/// );
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`ExtXKey`]: crate::tags::ExtXKey
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MediaPlaylistDiff {
+    /// The changed tags, that apply to the entire playlist.
+    pub tags: Vec<AttributeChange>,
+    /// The changed segments.
+    pub segments: Vec<Change<usize>>,
+}
+
+impl MediaPlaylistDiff {
+    pub(crate) fn new(old: &MediaPlaylist<'_>, new: &MediaPlaylist<'_>) -> Self {
+        let segments = |playlist: &MediaPlaylist<'_>| {
+            playlist
+                .segments
+                .values()
+                .map(|segment| (segment.number(), segment_tags(segment)))
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            tags: diff_fields(&media_tags(old), &media_tags(new)),
+            segments: diff_entries(segments(old), segments(new)),
+        }
+    }
+
+    /// Returns `true`, if the playlists are structurally equal.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.tags.is_empty() && self.segments.is_empty() }
+}
+
+impl fmt::Display for MediaPlaylistDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for tag in &self.tags {
+            writeln!(f, "{}", tag)?;
+        }
+
+        for change in &self.segments {
+            change.write(f, format_args!("segment {}", change.key()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a line into the name of the tag and its value. A tag without a
+/// value has the value `YES` and a line, that is not a tag, is an `URI`.
+fn split_tag(line: &str) -> (String, String) {
+    if let Some(tag) = line.strip_prefix('#') {
+        let mut parts = tag.splitn(2, ':');
+        let name = parts.next().unwrap_or_default();
+
+        (name.to_string(), parts.next().unwrap_or("YES").to_string())
+    } else {
+        ("URI".to_string(), quote(line))
+    }
+}
+
+/// Returns the attributes of an attribute list or `None`, if the value is not
+/// an attribute list.
+fn attributes(value: &str) -> Option<Fields> {
+    let result = AttributePairs::new(value)
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect::<Fields>();
+
+    let is_attribute_list = !result.is_empty()
+        && result.iter().all(|(key, _)| {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+        });
+
+    if is_attribute_list {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Returns the key and the attributes of a tag, which is followed by an
+/// optional URI line (like an `EXT-X-STREAM-INF` tag).
+fn entry<T: fmt::Display>(value: &T, key_attributes: &[&str]) -> (String, Fields) {
+    let value = value.to_string();
+    let mut lines = value.lines();
+
+    let (tag, value) = split_tag(lines.next().unwrap_or_default());
+    let mut fields = attributes(&value).unwrap_or_else(|| vec![("VALUE".to_string(), value)]);
+    fields.extend(lines.map(split_tag));
+
+    let key = key_attributes
+        .iter()
+        .filter_map(|name| {
+            fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(key, value)| format!("{}={}", key, value))
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    (format!("{} {}", tag, key), fields)
+}
+
+/// Appends the tag to the fields, if it is present.
+fn push_tag<T: fmt::Display>(fields: &mut Fields, tag: Option<T>) {
+    if let Some(tag) = tag {
+        fields.push(split_tag(&tag.to_string()));
+    }
+}
+
+fn master_tags(playlist: &MasterPlaylist<'_>) -> Fields {
+    let mut result = vec![];
+    let version = playlist.required_version();
+
+    push_tag(
+        &mut result,
+        Some(ExtXVersion::new(version)).filter(|_| version != ProtocolVersion::V1),
+    );
+    push_tag(
+        &mut result,
+        Some(ExtXIndependentSegments).filter(|_| playlist.has_independent_segments),
+    );
+    push_tag(&mut result, playlist.start);
+
+    result
+}
+
+fn media_tags(playlist: &MediaPlaylist<'_>) -> Fields {
+    let mut result = vec![];
+    let version = playlist.required_version();
+
+    push_tag(
+        &mut result,
+        Some(ExtXVersion::new(version)).filter(|_| version != ProtocolVersion::V1),
+    );
+    push_tag(&mut result, Some(ExtXTargetDuration(playlist.target_duration)));
+    push_tag(
+        &mut result,
+        Some(ExtXMediaSequence(playlist.media_sequence)).filter(|tag| tag.0 != 0),
+    );
+    push_tag(
+        &mut result,
+        Some(ExtXDiscontinuitySequence(playlist.discontinuity_sequence)).filter(|tag| tag.0 != 0),
+    );
+    push_tag(&mut result, playlist.playlist_type);
+    push_tag(
+        &mut result,
+        Some(ExtXIFramesOnly).filter(|_| playlist.has_i_frames_only),
+    );
+    push_tag(
+        &mut result,
+        Some(ExtXIndependentSegments).filter(|_| playlist.has_independent_segments),
+    );
+    push_tag(&mut result, playlist.start);
+    push_tag(&mut result, Some(ExtXEndList).filter(|_| playlist.has_end_list));
+
+    result
+}
+
+/// Returns the tags of a segment. Tags, that appear multiple times, are
+/// numbered, except for the `EXT-X-DATERANGE` tags, which are identified by
+/// their `ID`.
+fn segment_tags(segment: &MediaSegment<'_>) -> Fields {
+    let mut result: Fields = vec![];
+    let segment_lines = segment.to_string();
+
+    let lines = segment
+        .keys
+        .iter()
+        .map(ToString::to_string)
+        .chain(segment_lines.lines().map(String::from));
+
+    for line in lines {
+        let (mut name, mut value) = split_tag(&line);
+
+        // the comma of an `#EXTINF` without a title is not part of the value:
+        if name == "EXTINF" {
+            if let Some(duration) = value.strip_suffix(',') {
+                value = duration.to_string();
+            }
+        }
+
+        if name == "EXT-X-DATERANGE" {
+            if let Some((_, id)) = attributes(&value)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|(key, _)| key == "ID")
+            {
+                name = format!("{} ID={}", name, id);
+            }
+        }
+
+        let count = result
+            .iter()
+            .filter(|(key, _)| key == &name || key.starts_with(&format!("{} #", name)))
+            .count();
+
+        if count > 0 {
+            name = format!("{} #{}", name, count + 1);
+        }
+
+        result.push((name, value));
+    }
+
+    result
+}
+
+fn diff_fields(old: &[(String, String)], new: &[(String, String)]) -> Vec<AttributeChange> {
+    let find = |fields: &[(String, String)], name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    let mut result = vec![];
+
+    for (name, _) in old.iter().chain(new.iter().filter(|(name, _)| find(old, name).is_none())) {
+        let (old, new) = (find(old, name), find(new, name));
+
+        if old != new {
+            result.push(AttributeChange {
+                name: name.clone(),
+                old,
+                new,
+            });
+        }
+    }
+
+    result
+}
+
+fn diff_entries<K: Eq + Hash + Clone>(
+    old: Vec<(K, Fields)>,
+    new: Vec<(K, Fields)>,
+) -> Vec<Change<K>> {
+    let mut result = vec![];
+    let mut new = new.into_iter().map(Some).collect::<Vec<_>>();
+
+    // the positions of the new entries by key, so entries with the same key
+    // are matched in order:
+    let mut positions = HashMap::<K, VecDeque<usize>>::new();
+
+    for (i, entry) in new.iter().enumerate() {
+        if let Some((key, _)) = entry {
+            positions.entry(key.clone()).or_default().push_back(i);
+        }
+    }
+
+    for (key, fields) in old {
+        let matched = positions
+            .get_mut(&key)
+            .and_then(VecDeque::pop_front)
+            .and_then(|i| new[i].take());
+
+        if let Some((_, new_fields)) = matched {
+            let attributes = diff_fields(&fields, &new_fields);
+
+            if !attributes.is_empty() {
+                result.push(Change::Changed { key, attributes });
+            }
+        } else {
+            result.push(Change::Removed(key));
+        }
+    }
+
+    result.extend(new.into_iter().flatten().map(|(key, _)| Change::Added(key)));
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_master_playlist_diff() {
+        let old = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",LANGUAGE=\"en\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000,CODECS=\"avc1.4d401f,mp4a.40.2\",AUDIO=\"aac\"\n",
+            "low.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:URI=\"low-iframe.m3u8\",BANDWIDTH=100000\n",
+        ))
+        .unwrap();
+
+        let new = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-INDEPENDENT-SEGMENTS\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",LANGUAGE=\"en-US\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000,CODECS=\"avc1.4d401f,mp4a.40.2\",AUDIO=\"aac\"\n",
+            "low.m3u8\n",
+        ))
+        .unwrap();
+
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff,
+            MasterPlaylistDiff {
+                tags: vec![AttributeChange {
+                    name: "EXT-X-INDEPENDENT-SEGMENTS".into(),
+                    old: None,
+                    new: Some("YES".into()),
+                }],
+                media: vec![Change::Changed {
+                    key: "EXT-X-MEDIA TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\"".into(),
+                    attributes: vec![AttributeChange {
+                        name: "LANGUAGE".into(),
+                        old: Some("\"en\"".into()),
+                        new: Some("\"en-US\"".into()),
+                    }],
+                }],
+                variant_streams: vec![Change::Removed(
+                    "EXT-X-I-FRAME-STREAM-INF URI=\"low-iframe.m3u8\"".into()
+                )],
+                session_data: vec![],
+                session_keys: vec![],
+            }
+        );
+
+        assert!(!diff.is_empty());
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(old.diff(&old).to_string(), "");
+    }
+
+    #[test]
+    fn test_media_playlist_diff() {
+        let old = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://example.com/key\"\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2020-01-02T21:55:40Z\"\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+        ))
+        .unwrap();
+
+        let new = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://example.com/other\"\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2020-01-02T21:55:40Z\",DURATION=10\n",
+            "#EXT-X-DATERANGE:ID=\"other\",START-DATE=\"2020-01-02T21:55:40Z\"\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+        ))
+        .unwrap();
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.tags, vec![]);
+        assert_eq!(
+            diff.segments,
+            vec![Change::Changed {
+                key: 0,
+                attributes: vec![
+                    AttributeChange {
+                        name: "EXT-X-KEY".into(),
+                        old: Some("METHOD=AES-128,URI=\"https://example.com/key\"".into()),
+                        new: Some("METHOD=AES-128,URI=\"https://example.com/other\"".into()),
+                    },
+                    AttributeChange {
+                        name: "EXT-X-DATERANGE ID=\"ad\"".into(),
+                        old: Some("ID=\"ad\",START-DATE=\"2020-01-02T21:55:40Z\"".into()),
+                        new: Some(
+                            "ID=\"ad\",START-DATE=\"2020-01-02T21:55:40Z\",DURATION=10".into()
+                        ),
+                    },
+                    AttributeChange {
+                        name: "EXT-X-DATERANGE ID=\"other\"".into(),
+                        old: None,
+                        new: Some("ID=\"other\",START-DATE=\"2020-01-02T21:55:40Z\"".into()),
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_entries() {
+        let fields = |value: &str| vec![("A".to_string(), value.to_string())];
+
+        // entries with the same key are matched in order:
+        assert_eq!(
+            diff_entries(
+                vec![(1, fields("1")), (2, fields("2")), (1, fields("3"))],
+                vec![(1, fields("1")), (1, fields("4")), (3, fields("5"))],
+            ),
+            vec![
+                Change::Removed(2),
+                Change::Changed {
+                    key: 1,
+                    attributes: vec![AttributeChange {
+                        name: "A".into(),
+                        old: Some("3".into()),
+                        new: Some("4".into()),
+                    }],
+                },
+                Change::Added(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_segment_tags() {
+        let segment = |input| MediaPlaylist::try_from(input).unwrap().segments[0].clone();
+
+        assert_eq!(
+            segment_tags(&segment("#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:9.5,\na.ts\n")),
+            vec![
+                ("EXTINF".to_string(), "9.5".to_string()),
+                ("URI".to_string(), "\"a.ts\"".to_string()),
+            ]
+        );
+        assert_eq!(
+            segment_tags(&segment(
+                "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:9.5,title\na.ts\n"
+            ))[0],
+            ("EXTINF".to_string(), "9.5,title".to_string())
+        );
+    }
+}
@@ -67,6 +67,12 @@ enum ErrorKind {
 
     #[error("{source}")]
     Hex { source: hex::FromHexError },
+
+    #[error("invalid splice_info_section: {0}")]
+    Scte35(String),
+
+    #[error("crc32 mismatch: expected {expected:#010X}, but found {found:#010X}")]
+    Crc32 { expected: u32, found: u32 },
 }
 
 /// The Error type of this library.
@@ -187,6 +193,14 @@ impl Error {
         Self::new(ErrorKind::Hex { source })
     }
 
+    pub(crate) fn scte35<T: fmt::Display>(value: T) -> Self {
+        Self::new(ErrorKind::Scte35(value.to_string()))
+    }
+
+    pub(crate) fn crc32(expected: u32, found: u32) -> Self {
+        Self::new(ErrorKind::Crc32 { expected, found })
+    }
+
     pub(crate) fn strum(value: strum::ParseError) -> Self {
         Self::new(ErrorKind::Custom(value.to_string()))
     }
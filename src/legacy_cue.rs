@@ -0,0 +1,1052 @@
+//! Non-standard ad cue tags, which are emitted by many encoders.
+//!
+//! The tags `#EXT-X-CUE-OUT`, `#EXT-X-CUE-OUT-CONT`, `#EXT-X-CUE-IN` and
+//! `#EXT-OATCLS-SCTE35` are not part of [RFC8216] and therefore end up in
+//! [`MediaPlaylist::unknown`], which does not keep their position in the
+//! playlist. The [`parse`] function of this module extracts them together
+//! with the index of the [`MediaSegment`] they precede, and
+//! [`to_date_ranges`] / [`normalize`] convert them into equivalent
+//! [`ExtXDateRange`] tags with `SCTE35-OUT` and `SCTE35-IN` attributes.
+//!
+//! # Example
+//!
+//! ```
+//! use core::convert::TryFrom;
+//! use hls_m3u8::legacy_cue::{self, LegacyCue};
+//! use hls_m3u8::MediaPlaylist;
+//!
+//! let input = concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-TARGETDURATION:10\n",
+//!     "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:44Z\n",
+//!     "#EXTINF:10,\n",
+//!     "a.ts\n",
+//!     "#EXT-X-CUE-OUT:DURATION=20\n",
+//!     "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:54Z\n",
+//!     "#EXTINF:10,\n",
+//!     "b.ts\n",
+//!     "#EXT-X-CUE-OUT-CONT:ElapsedTime=10,Duration=20\n",
+//!     "#EXTINF:10,\n",
+//!     "c.ts\n",
+//!     "#EXT-X-CUE-IN\n",
+//!     "#EXTINF:10,\n",
+//!     "d.ts\n",
+//! );
+//!
+//! let markers = legacy_cue::parse(input)?;
+//!
+//! assert_eq!(markers.len(), 3);
+//! assert_eq!(markers[0].segment, 1);
+//! assert_eq!(markers[2].cue, LegacyCue::CueIn);
+//!
+//! let mut playlist = MediaPlaylist::try_from(input)?;
+//! legacy_cue::normalize(&mut playlist, &markers)?;
+//!
+//! assert!(playlist.unknown.is_empty());
+//! assert_eq!(playlist.segments[1].date_ranges[0].id(), "cue-1");
+//! assert!(playlist.segments[1].date_ranges[0].scte35_out().is_some());
+//! assert!(playlist.segments[3].date_ranges[0].scte35_in().is_some());
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+//!
+//! [RFC8216]: https://tools.ietf.org/html/rfc8216
+//! [`MediaPlaylist::unknown`]: crate::MediaPlaylist::unknown
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset};
+
+use crate::attribute::AttributePairs;
+use crate::scte35::{BreakDuration, SpliceCommand, SpliceInfoSection, SpliceInsert};
+use crate::tags::{ExtXDateRange, ExtXProgramDateTime};
+use crate::types::Decimal;
+use crate::utils::tag;
+use crate::{Error, MediaPlaylist, MediaSegment};
+
+/// The `#EXT-X-CUE-OUT` tag marks the start of an ad break, for example
+/// `#EXT-X-CUE-OUT:DURATION=30` or `#EXT-X-CUE-OUT:30`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CueOut {
+    /// The expected duration of the ad break.
+    pub duration: Option<Duration>,
+}
+
+impl CueOut {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-CUE-OUT";
+
+    /// Makes a new [`CueOut`] tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::legacy_cue::CueOut;
+    /// use std::time::Duration;
+    ///
+    /// let cue_out = CueOut::new(Some(Duration::from_secs(30)));
+    ///
+    /// assert_eq!(cue_out.to_string(), "#EXT-X-CUE-OUT:DURATION=30");
+    /// ```
+    #[must_use]
+    pub const fn new(duration: Option<Duration>) -> Self { Self { duration } }
+}
+
+impl fmt::Display for CueOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+
+        if let Some(value) = &self.duration {
+            write!(f, ":DURATION={}", value.as_secs_f64())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for CueOut {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let input = tag(input, ":")?;
+
+        if !input.contains('=') {
+            return Ok(Self::new(Some(parse_duration(input)?)));
+        }
+
+        let mut duration = None;
+
+        for (key, value) in AttributePairs::new(input) {
+            if key.eq_ignore_ascii_case("DURATION") {
+                duration = Some(parse_duration(value)?);
+            }
+        }
+
+        Ok(Self::new(duration))
+    }
+}
+
+/// The `#EXT-X-CUE-OUT-CONT` tag is repeated in front of every segment of an
+/// ad break, for example
+/// `#EXT-X-CUE-OUT-CONT:ElapsedTime=10,Duration=30,SCTE35=/DAl...` or
+/// `#EXT-X-CUE-OUT-CONT:10/30`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CueOutCont<'a> {
+    /// The time, that elapsed since the start of the ad break.
+    pub elapsed_time: Option<Duration>,
+    /// The expected duration of the ad break.
+    pub duration: Option<Duration>,
+    /// The base64 encoded `splice_info_section` of the ad break.
+    pub scte35: Option<Cow<'a, str>>,
+}
+
+impl<'a> CueOutCont<'a> {
+    pub(crate) const PREFIX: &'static str = "#EXT-X-CUE-OUT-CONT";
+
+    /// Decodes the [`CueOutCont::scte35`] attribute.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the attribute is not a valid
+    /// base64 encoded `splice_info_section`.
+    pub fn section(&self) -> crate::Result<Option<SpliceInfoSection>> {
+        self.scte35.as_deref().map(decode_section).transpose()
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of
+    /// all internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> CueOutCont<'static> {
+        CueOutCont {
+            elapsed_time: self.elapsed_time,
+            duration: self.duration,
+            scte35: self.scte35.map(|v| Cow::Owned(v.into_owned())),
+        }
+    }
+}
+
+impl<'a> fmt::Display for CueOutCont<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::PREFIX)?;
+
+        let mut separator = ":";
+
+        if let Some(value) = &self.elapsed_time {
+            write!(f, "{}ElapsedTime={}", separator, value.as_secs_f64())?;
+            separator = ",";
+        }
+
+        if let Some(value) = &self.duration {
+            write!(f, "{}Duration={}", separator, value.as_secs_f64())?;
+            separator = ",";
+        }
+
+        if let Some(value) = &self.scte35 {
+            write!(f, "{}SCTE35={}", separator, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for CueOutCont<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let input = tag(input, Self::PREFIX)?;
+
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let input = tag(input, ":")?;
+
+        if !input.contains('=') {
+            let mut parts = input.splitn(2, '/');
+
+            return Ok(Self {
+                elapsed_time: parts.next().map(parse_duration).transpose()?,
+                duration: parts.next().map(parse_duration).transpose()?,
+                scte35: None,
+            });
+        }
+
+        let mut result = Self::default();
+
+        for (key, value) in AttributePairs::new(input) {
+            // the keys are matched case-insensitively like the ones of `CueOut`:
+            if key.eq_ignore_ascii_case("ElapsedTime") {
+                result.elapsed_time = Some(parse_duration(value)?);
+            } else if key.eq_ignore_ascii_case("Duration") {
+                result.duration = Some(parse_duration(value)?);
+            } else if key.eq_ignore_ascii_case("SCTE35") {
+                result.scte35 = Some(Cow::Borrowed(value));
+            }
+            // unknown attributes are ignored
+        }
+
+        Ok(result)
+    }
+}
+
+/// The `#EXT-OATCLS-SCTE35` tag contains a base64 encoded
+/// `splice_info_section`, which usually accompanies an `#EXT-X-CUE-OUT` or
+/// `#EXT-X-CUE-IN` tag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OatclsScte35<'a>(pub Cow<'a, str>);
+
+impl<'a> OatclsScte35<'a> {
+    pub(crate) const PREFIX: &'static str = "#EXT-OATCLS-SCTE35:";
+
+    /// Decodes the base64 encoded `splice_info_section`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::legacy_cue::OatclsScte35;
+    /// let tag = OatclsScte35(
+    ///     "/DAvAAAAAAAA///wFAVIAACPf+/+c2nALv4AUsz1AAAAAAAKAAhDVUVJAAABNWLbowo=".into(),
+    /// );
+    ///
+    /// assert_eq!(tag.section()?.splice_event_id(), Some(0x4800_008F));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the value is not a valid base64
+    /// encoded `splice_info_section`.
+    pub fn section(&self) -> crate::Result<SpliceInfoSection> { decode_section(&self.0) }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of
+    /// all internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> OatclsScte35<'static> {
+        OatclsScte35(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl<'a> fmt::Display for OatclsScte35<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", Self::PREFIX, self.0)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for OatclsScte35<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        Ok(Self(Cow::Borrowed(tag(input, Self::PREFIX)?)))
+    }
+}
+
+/// One of the non-standard ad cue tags.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LegacyCue<'a> {
+    /// An `#EXT-X-CUE-OUT` tag.
+    CueOut(CueOut),
+    /// An `#EXT-X-CUE-OUT-CONT` tag.
+    CueOutCont(CueOutCont<'a>),
+    /// An `#EXT-X-CUE-IN` tag, which marks the end of an ad break.
+    CueIn,
+    /// An `#EXT-OATCLS-SCTE35` tag.
+    OatclsScte35(OatclsScte35<'a>),
+}
+
+impl<'a> LegacyCue<'a> {
+    pub(crate) const PREFIX_CUE_IN: &'static str = "#EXT-X-CUE-IN";
+
+    /// Returns `true`, if the line is one of the non-standard ad cue tags.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::legacy_cue::LegacyCue;
+    /// assert!(LegacyCue::matches("#EXT-X-CUE-OUT-CONT:10/30"));
+    /// assert!(!LegacyCue::matches("#EXT-X-CUE:ID=1"));
+    /// ```
+    #[must_use]
+    pub fn matches(line: &str) -> bool {
+        let line = line.trim();
+
+        [
+            CueOut::PREFIX,
+            CueOutCont::PREFIX,
+            Self::PREFIX_CUE_IN,
+            OatclsScte35::PREFIX,
+        ]
+        .iter()
+        .any(|prefix| {
+            line.starts_with(prefix)
+                && (prefix.ends_with(':') || line.len() == prefix.len() || {
+                    line.as_bytes()[prefix.len()] == b':'
+                })
+        })
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of
+    /// all internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> LegacyCue<'static> {
+        match self {
+            Self::CueOut(value) => LegacyCue::CueOut(value),
+            Self::CueOutCont(value) => LegacyCue::CueOutCont(value.into_owned()),
+            Self::CueIn => LegacyCue::CueIn,
+            Self::OatclsScte35(value) => LegacyCue::OatclsScte35(value.into_owned()),
+        }
+    }
+}
+
+impl<'a> fmt::Display for LegacyCue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CueOut(value) => value.fmt(f),
+            Self::CueOutCont(value) => value.fmt(f),
+            Self::CueIn => Self::PREFIX_CUE_IN.fmt(f),
+            Self::OatclsScte35(value) => value.fmt(f),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for LegacyCue<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let input = input.trim();
+
+        if !Self::matches(input) {
+            return Err(Error::unexpected_tag(input));
+        }
+
+        // `#EXT-X-CUE-OUT-CONT` has to be checked before `#EXT-X-CUE-OUT`:
+        if input.starts_with(CueOutCont::PREFIX) {
+            Ok(Self::CueOutCont(CueOutCont::try_from(input)?))
+        } else if input.starts_with(CueOut::PREFIX) {
+            Ok(Self::CueOut(CueOut::try_from(input)?))
+        } else if input.starts_with(OatclsScte35::PREFIX) {
+            Ok(Self::OatclsScte35(OatclsScte35::try_from(input)?))
+        } else {
+            Ok(Self::CueIn)
+        }
+    }
+}
+
+/// A [`LegacyCue`] together with its position in the playlist.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CueMarker<'a> {
+    /// The index of the [`MediaSegment`] in [`MediaPlaylist::segments`], that
+    /// follows the tag.
+    ///
+    /// A tag after the last segment has an index equal to the number of
+    /// segments.
+    ///
+    /// [`MediaPlaylist::segments`]: crate::MediaPlaylist::segments
+    pub segment: usize,
+    /// The parsed tag.
+    pub cue: LegacyCue<'a>,
+}
+
+/// Extracts all non-standard ad cue tags of a media playlist together with
+/// the index of the [`MediaSegment`] they precede.
+///
+/// # Errors
+///
+/// This function returns an error, if the input does not start with
+/// `#EXTM3U` or if one of the tags is invalid.
+pub fn parse(input: &str) -> crate::Result<Vec<CueMarker<'_>>> {
+    let input = tag(input, "#EXTM3U")?;
+
+    let mut segment = 0;
+    let mut result = vec![];
+
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if !line.starts_with('#') {
+            segment += 1;
+        } else if LegacyCue::matches(line) {
+            result.push(CueMarker {
+                segment,
+                cue: LegacyCue::try_from(line)?,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Converts the markers into [`ExtXDateRange`]s, which are returned together
+/// with the index of the [`MediaSegment`] they should precede.
+///
+/// - An `#EXT-X-CUE-OUT` becomes an [`ExtXDateRange`] with the `ID`
+///   `cue-{number}` (where `number` is the [`MediaSegment::number`] of the
+///   first segment of the ad break), a `PLANNED-DURATION` and a `SCTE35-OUT`
+///   attribute.
+/// - An `#EXT-X-CUE-IN` becomes an [`ExtXDateRange`] with the same `ID` and
+///   `START-DATE`, the actual `DURATION` of the ad break and a `SCTE35-IN`
+///   attribute.
+///
+/// The `SCTE35-OUT` attribute contains the `splice_info_section` of an
+/// `#EXT-OATCLS-SCTE35` tag in front of the same segment or otherwise a
+/// generated `splice_insert`, which uses the segment number as its
+/// `splice_event_id`.
+///
+/// `#EXT-X-CUE-OUT-CONT` tags are not converted, because the ad break is
+/// already fully described by the other two tags. Markers after the last
+/// segment are ignored.
+///
+/// ## Note
+///
+/// The `START-DATE` is derived from the [`ExtXProgramDateTime`] tags of the
+/// playlist. Without the `chrono` feature, the segment, that starts the ad
+/// break, must have an [`ExtXProgramDateTime`] tag.
+///
+/// # Errors
+///
+/// This function returns an error, if the `START-DATE` can not be determined,
+/// if a `splice_info_section` is invalid or can not be encoded (for example a
+/// `DURATION`, that does not fit in a `break_duration`, or a segment number,
+/// that does not fit in a `splice_event_id`) or if an `#EXT-X-CUE-IN` precedes
+/// the `#EXT-X-CUE-OUT` of its ad break (which can only happen, if the
+/// markers are not in playlist order).
+pub fn to_date_ranges(
+    playlist: &MediaPlaylist<'_>,
+    markers: &[CueMarker<'_>],
+) -> crate::Result<Vec<(usize, ExtXDateRange<'static>)>> {
+    let segments = playlist.segments.values().collect::<Vec<_>>();
+    let mut result = vec![];
+
+    // the id, start date and segment index of the current ad break
+    let mut open: Option<(String, StartDate, usize)> = None;
+    let mut section: Option<(usize, SpliceInfoSection)> = None;
+
+    for marker in markers {
+        let index = marker.segment;
+
+        let segment = {
+            if let Some(segment) = segments.get(index) {
+                segment
+            } else {
+                continue;
+            }
+        };
+
+        match &marker.cue {
+            LegacyCue::OatclsScte35(value) => {
+                section = Some((index, value.section()?));
+            }
+            LegacyCue::CueOut(cue_out) => {
+                let id = format!("cue-{}", segment.number());
+                let start_date = start_date(&segments, index)?;
+
+                let scte35_out = {
+                    match section.take() {
+                        Some((i, section)) if i == index => section,
+                        _ => {
+                            let mut splice_insert =
+                                SpliceInsert::new(event_id(segment)?, true);
+                            splice_insert.break_duration = cue_out
+                                .duration
+                                .map(|duration| BreakDuration::new(true, duration));
+
+                            SpliceInfoSection::new(SpliceCommand::SpliceInsert(splice_insert))
+                        }
+                    }
+                };
+
+                let mut builder = ExtXDateRange::builder();
+                builder
+                    .id(id.clone())
+                    .start_date(start_date.clone())
+                    .scte35_out(scte35_out.to_hex()?);
+
+                if let Some(duration) = cue_out.duration {
+                    builder.planned_duration(duration);
+                }
+
+                result.push((index, builder.build().map_err(Error::builder)?));
+                open = Some((id, start_date, index));
+            }
+            LegacyCue::CueIn => {
+                let (id, start_date, start) = {
+                    if let Some(value) = open.take() {
+                        value
+                    } else {
+                        (
+                            format!("cue-{}", segment.number()),
+                            start_date(&segments, index)?,
+                            index,
+                        )
+                    }
+                };
+
+                let scte35_in = {
+                    match section.take() {
+                        Some((i, section)) if i == index => section,
+                        _ => {
+                            let event_id = segments.get(start).map_or(Ok(0), |s| event_id(s))?;
+
+                            SpliceInfoSection::new(SpliceCommand::SpliceInsert(
+                                SpliceInsert::new(event_id, false),
+                            ))
+                        }
+                    }
+                };
+
+                if start > index {
+                    return Err(Error::custom(format!(
+                        "the #EXT-X-CUE-IN in front of segment {} precedes its #EXT-X-CUE-OUT \
+                         in front of segment {}",
+                        index, start
+                    )));
+                }
+
+                let duration = segments[start..index]
+                    .iter()
+                    .map(|s| s.duration.exact_duration())
+                    .sum::<Decimal>()
+                    .as_duration();
+
+                result.push((
+                    index,
+                    ExtXDateRange::builder()
+                        .id(id)
+                        .start_date(start_date)
+                        .duration(duration)
+                        .scte35_in(scte35_in.to_hex()?)
+                        .build()
+                        .map_err(Error::builder)?,
+                ));
+            }
+            LegacyCue::CueOutCont(_) => {}
+        }
+    }
+
+    Ok(result)
+}
+
+/// Converts the markers with [`to_date_ranges`], adds the resulting
+/// [`ExtXDateRange`]s to the [`MediaSegment`]s and removes the converted tags
+/// from [`MediaPlaylist::unknown`].
+///
+/// Tags after the last segment are kept in [`MediaPlaylist::unknown`],
+/// because they apply to a segment, that is not yet in the playlist.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`to_date_ranges`].
+///
+/// [`MediaPlaylist::unknown`]: crate::MediaPlaylist::unknown
+pub fn normalize(playlist: &mut MediaPlaylist<'_>, markers: &[CueMarker<'_>]) -> crate::Result<()> {
+    let date_ranges = to_date_ranges(playlist, markers)?;
+    let segments = playlist.segments.num_elements();
+
+    for (index, date_range) in date_ranges {
+        if let Some(segment) = playlist.segments.values_mut().nth(index) {
+            segment.date_ranges.push(date_range);
+        }
+    }
+
+    // the markers after the last segment are the last legacy cue tags in
+    // `unknown`:
+    let mut keep = markers.iter().filter(|m| m.segment >= segments).count();
+    let mut unknown = vec![];
+
+    for value in playlist.unknown.drain(..).rev() {
+        if !LegacyCue::matches(&value) {
+            unknown.push(value);
+        } else if keep > 0 {
+            keep -= 1;
+            unknown.push(value);
+        }
+    }
+
+    unknown.reverse();
+    playlist.unknown = unknown;
+
+    Ok(())
+}
+
+#[cfg(feature = "chrono")]
+type StartDate = DateTime<FixedOffset>;
+
+#[cfg(not(feature = "chrono"))]
+type StartDate = Cow<'static, str>;
+
+/// Returns the `splice_event_id` of an ad break, that starts with `segment`.
+fn event_id(segment: &MediaSegment<'_>) -> crate::Result<u32> {
+    u32::try_from(segment.number()).map_err(|_| {
+        Error::custom(format!(
+            "the segment number {} does not fit in a splice_event_id",
+            segment.number()
+        ))
+    })
+}
+
+fn date_time(value: &ExtXProgramDateTime<'_>) -> StartDate {
+    #[cfg(feature = "chrono")]
+    {
+        value.date_time
+    }
+    #[cfg(not(feature = "chrono"))]
+    {
+        Cow::Owned(value.date_time.to_string())
+    }
+}
+
+/// Returns the date of the segment at `index`, which is either its own
+/// [`ExtXProgramDateTime`] or (with the `chrono` feature) derived from the
+/// closest [`ExtXProgramDateTime`] in front of or after it.
+fn start_date(segments: &[&MediaSegment<'_>], index: usize) -> crate::Result<StartDate> {
+    if let Some(value) = &segments[index].program_date_time {
+        return Ok(date_time(value));
+    }
+
+    #[cfg(feature = "chrono")]
+    {
+        let offset = |range: &[&MediaSegment<'_>]| {
+            let duration = range.iter().map(|s| s.duration.exact_duration()).sum::<Decimal>();
+
+            chrono::Duration::from_std(duration.as_duration()).map_err(Error::custom)
+        };
+
+        if let Some(i) = (0..index).rev().find(|&i| segments[i].program_date_time.is_some()) {
+            if let Some(value) = &segments[i].program_date_time {
+                return Ok(value.date_time + offset(&segments[i..index])?);
+            }
+        }
+
+        if let Some(i) = (index..segments.len()).find(|&i| segments[i].program_date_time.is_some())
+        {
+            if let Some(value) = &segments[i].program_date_time {
+                return Ok(value.date_time - offset(&segments[index..i])?);
+            }
+        }
+    }
+
+    Err(Error::missing_tag(
+        ExtXProgramDateTime::PREFIX,
+        segments[index].uri(),
+    ))
+}
+
+fn decode_section(input: &str) -> crate::Result<SpliceInfoSection> {
+    SpliceInfoSection::decode(&decode_base64(input)?)
+}
+
+/// Decodes standard base64 (with or without padding).
+#[allow(clippy::cast_possible_truncation)]
+fn decode_base64(input: &str) -> crate::Result<Vec<u8>> {
+    let mut result = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+
+    for c in input.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(Error::custom(format!("invalid base64 character: {:?}", c as char))),
+        };
+
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_duration(input: &str) -> crate::Result<Duration> {
+    let value = input
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| Error::parse_float(input, e))?;
+
+    if !value.is_finite() || value < 0.0 {
+        return Err(Error::custom(format!("invalid duration: {}", input)));
+    }
+
+    Ok(Duration::from_secs_f64(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SECTION: &str =
+        "/DAvAAAAAAAA///wFAVIAACPf+/+c2nALv4AUsz1AAAAAAAKAAhDVUVJAAABNWLbowo=";
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-TARGETDURATION:10\n",
+        "#EXT-X-MEDIA-SEQUENCE:100\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:44Z\n",
+        "#EXTINF:10,\n",
+        "a.ts\n",
+        "#EXT-X-X-UNRELATED\n",
+        "#EXT-OATCLS-SCTE35:/DAvAAAAAAAA///wFAVIAACPf+/+c2nALv4AUsz1AAAAAAAKAAhDVUVJAAABNWLbowo=\n",
+        "#EXT-X-CUE-OUT:20\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:54Z\n",
+        "#EXTINF:10,\n",
+        "b.ts\n",
+        "#EXT-X-CUE-OUT-CONT:10/20\n",
+        "#EXTINF:10,\n",
+        "c.ts\n",
+        "#EXT-X-CUE-IN\n",
+        "#EXTINF:10,\n",
+        "d.ts\n",
+        "#EXT-X-CUE-OUT:DURATION=10\n",
+    );
+
+    #[test]
+    fn test_cue_out() {
+        assert_eq!(CueOut::try_from("#EXT-X-CUE-OUT").unwrap(), CueOut::new(None));
+        assert_eq!(
+            CueOut::try_from("#EXT-X-CUE-OUT:30.5").unwrap(),
+            CueOut::new(Some(Duration::from_secs_f64(30.5)))
+        );
+        assert_eq!(
+            CueOut::try_from("#EXT-X-CUE-OUT:DURATION=30").unwrap(),
+            CueOut::new(Some(Duration::from_secs(30)))
+        );
+        assert_eq!(
+            CueOut::new(Some(Duration::from_secs(30))).to_string(),
+            "#EXT-X-CUE-OUT:DURATION=30"
+        );
+        assert_eq!(CueOut::new(None).to_string(), "#EXT-X-CUE-OUT");
+
+        assert!(CueOut::try_from("#EXT-X-CUE-OUT:DURATION=-1").is_err());
+        assert!(CueOut::try_from("#EXT-X-CUE-OUT:x").is_err());
+    }
+
+    #[test]
+    fn test_cue_out_cont() {
+        let cue_out_cont = CueOutCont {
+            elapsed_time: Some(Duration::from_secs_f64(5.5)),
+            duration: Some(Duration::from_secs(30)),
+            scte35: Some(SECTION.into()),
+        };
+
+        let input = format!(
+            "#EXT-X-CUE-OUT-CONT:ElapsedTime=5.5,Duration=30,SCTE35={}",
+            SECTION
+        );
+
+        assert_eq!(CueOutCont::try_from(input.as_str()).unwrap(), cue_out_cont);
+        assert_eq!(cue_out_cont.to_string(), input);
+        assert_eq!(
+            cue_out_cont.section().unwrap().unwrap().splice_event_id(),
+            Some(0x4800_008F)
+        );
+
+        assert_eq!(
+            CueOutCont::try_from("#EXT-X-CUE-OUT-CONT:10/30").unwrap(),
+            CueOutCont {
+                elapsed_time: Some(Duration::from_secs(10)),
+                duration: Some(Duration::from_secs(30)),
+                scte35: None,
+            }
+        );
+        assert_eq!(
+            CueOutCont::try_from("#EXT-X-CUE-OUT-CONT:elapsedtime=10,DURATION=30").unwrap(),
+            CueOutCont {
+                elapsed_time: Some(Duration::from_secs(10)),
+                duration: Some(Duration::from_secs(30)),
+                scte35: None,
+            }
+        );
+        assert_eq!(
+            CueOutCont::try_from("#EXT-X-CUE-OUT-CONT").unwrap(),
+            CueOutCont::default()
+        );
+        assert_eq!(CueOutCont::default().to_string(), "#EXT-X-CUE-OUT-CONT");
+    }
+
+    #[test]
+    fn test_legacy_cue() {
+        assert_eq!(
+            LegacyCue::try_from("#EXT-X-CUE-IN").unwrap(),
+            LegacyCue::CueIn
+        );
+        assert_eq!(
+            LegacyCue::try_from("#EXT-X-CUE-OUT-CONT:10/30").unwrap(),
+            LegacyCue::CueOutCont(CueOutCont {
+                elapsed_time: Some(Duration::from_secs(10)),
+                duration: Some(Duration::from_secs(30)),
+                scte35: None,
+            })
+        );
+        assert_eq!(
+            LegacyCue::try_from("#EXT-X-CUE-OUT:30").unwrap(),
+            LegacyCue::CueOut(CueOut::new(Some(Duration::from_secs(30))))
+        );
+        assert_eq!(
+            LegacyCue::try_from(format!("#EXT-OATCLS-SCTE35:{}", SECTION).as_str()).unwrap(),
+            LegacyCue::OatclsScte35(OatclsScte35(SECTION.into()))
+        );
+        assert_eq!(LegacyCue::CueIn.to_string(), "#EXT-X-CUE-IN");
+
+        assert!(LegacyCue::try_from("#EXT-X-CUE-INX").is_err());
+        assert!(LegacyCue::try_from("#EXT-X-CUE-OUTX").is_err());
+        assert!(LegacyCue::try_from("#EXT-X-DISCONTINUITY").is_err());
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("Zg==").unwrap(), b"f");
+        assert_eq!(decode_base64("Zm9vYg").unwrap(), b"foob");
+        assert_eq!(decode_base64("Zm9vYmFy").unwrap(), b"foobar");
+        assert!(decode_base64("Zm9v*").is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(PLAYLIST).unwrap(),
+            vec![
+                CueMarker {
+                    segment: 1,
+                    cue: LegacyCue::OatclsScte35(OatclsScte35(SECTION.into())),
+                },
+                CueMarker {
+                    segment: 1,
+                    cue: LegacyCue::CueOut(CueOut::new(Some(Duration::from_secs(20)))),
+                },
+                CueMarker {
+                    segment: 2,
+                    cue: LegacyCue::CueOutCont(CueOutCont {
+                        elapsed_time: Some(Duration::from_secs(10)),
+                        duration: Some(Duration::from_secs(20)),
+                        scte35: None,
+                    }),
+                },
+                CueMarker {
+                    segment: 3,
+                    cue: LegacyCue::CueIn,
+                },
+                CueMarker {
+                    segment: 4,
+                    cue: LegacyCue::CueOut(CueOut::new(Some(Duration::from_secs(10)))),
+                },
+            ]
+        );
+
+        assert!(parse("#EXT-X-CUE-IN").is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let markers = parse(PLAYLIST).unwrap();
+        let mut playlist = MediaPlaylist::try_from(PLAYLIST).unwrap();
+
+        assert_eq!(playlist.unknown.len(), 6);
+
+        normalize(&mut playlist, &markers).unwrap();
+
+        assert_eq!(
+            playlist.unknown,
+            vec![
+                Cow::Borrowed("#EXT-X-X-UNRELATED"),
+                Cow::Borrowed("#EXT-X-CUE-OUT:DURATION=10"),
+            ]
+        );
+
+        let out = &playlist.segments[1].date_ranges[0];
+
+        assert_eq!(out.id(), "cue-101");
+        assert_eq!(out.planned_duration, Some(Duration::from_secs(20)));
+        // the section of the `#EXT-OATCLS-SCTE35` tag is used:
+        assert_eq!(
+            SpliceInfoSection::try_from(out.scte35_out().unwrap().as_ref()).unwrap(),
+            OatclsScte35(SECTION.into()).section().unwrap()
+        );
+
+        let cue_in = &playlist.segments[3].date_ranges[0];
+
+        assert_eq!(cue_in.id(), "cue-101");
+        assert_eq!(cue_in.start_date(), out.start_date());
+        assert_eq!(cue_in.duration, Some(Duration::from_secs(20)));
+
+        let section = SpliceInfoSection::try_from(cue_in.scte35_in().unwrap().as_ref()).unwrap();
+
+        assert_eq!(section.splice_event_id(), Some(101));
+        assert_eq!(section.is_out_of_network(), Some(false));
+
+        assert!(playlist.segments[0].date_ranges.is_empty());
+        assert!(playlist.segments[2].date_ranges.is_empty());
+    }
+
+    #[test]
+    fn test_to_date_ranges() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:44Z\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXT-X-CUE-OUT:DURATION=30\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+        );
+
+        let markers = parse(input).unwrap();
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+        let result = to_date_ranges(&playlist, &markers);
+
+        #[cfg(feature = "chrono")]
+        {
+            let result = result.unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].0, 1);
+            assert_eq!(
+                result[0].1.start_date(),
+                Some("2020-01-02T21:55:54Z".parse().unwrap())
+            );
+
+            let section =
+                SpliceInfoSection::try_from(result[0].1.scte35_out().unwrap().as_ref()).unwrap();
+
+            assert_eq!(section.splice_event_id(), Some(1));
+            assert_eq!(section.break_duration(), Some(Duration::from_secs(30)));
+        }
+        #[cfg(not(feature = "chrono"))]
+        {
+            // the segment has no `#EXT-X-PROGRAM-DATE-TIME`
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_to_date_ranges_cue_in_before_cue_out() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:44Z\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:54Z\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:56:04Z\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+        );
+
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+        let markers = [
+            CueMarker {
+                segment: 2,
+                cue: LegacyCue::CueOut(CueOut::default()),
+            },
+            CueMarker {
+                segment: 1,
+                cue: LegacyCue::CueIn,
+            },
+        ];
+
+        assert!(to_date_ranges(&playlist, &markers).is_err());
+    }
+
+    #[test]
+    fn test_to_date_ranges_out_of_range() {
+        // the duration does not fit in the 33 bits of a `break_duration`:
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:44Z\n",
+            "#EXT-X-CUE-OUT:DURATION=100000\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+        );
+
+        let markers = parse(input).unwrap();
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert!(to_date_ranges(&playlist, &markers).is_err());
+
+        // the segment number does not fit in a `splice_event_id`:
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:4294967296\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:44Z\n",
+            "#EXT-X-CUE-OUT:DURATION=30\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+        );
+
+        let markers = parse(input).unwrap();
+        let playlist = MediaPlaylist::try_from(input).unwrap();
+
+        assert!(to_date_ranges(&playlist, &markers).is_err());
+    }
+}
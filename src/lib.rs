@@ -115,6 +115,7 @@ pub mod builder {
 
         // media segment
         pub use crate::tags::media_segment::date_range::ExtXDateRangeBuilder;
+        pub use crate::tags::media_segment::interstitial::InterstitialBuilder;
 
         // media playlist
     }
@@ -127,10 +128,17 @@ pub mod builder {
 }
 pub mod tags;
 pub mod types;
+pub mod diff;
+pub mod legacy_cue;
+pub mod live;
+pub mod scte35;
+pub mod timeline;
+pub mod validate;
 
 #[macro_use]
 mod utils;
 mod attribute;
+mod canonical;
 mod error;
 mod line;
 mod master_playlist;
@@ -0,0 +1,1267 @@
+//! Playlists, that change over time.
+//!
+//! A [`LiveMediaPlaylist`] keeps a sliding window of the latest segments of a
+//! live stream and an [`EventMediaPlaylist`] only allows segments to be
+//! appended to a [`PlaylistType::Event`] playlist, until it is finalized.
+//!
+//! Clients, that reload a live playlist, can compare it with the previous
+//! version with [`MediaPlaylist::reconcile`], which returns a
+//! [`ReloadOutcome`].
+use std::fmt;
+use std::time::Duration;
+
+use crate::types::{Decimal, PlaylistType};
+use crate::{Error, MediaPlaylist, MediaSegment};
+
+/// The amount of segments, that is kept by a [`LiveMediaPlaylist`].
+///
+/// A playlist, that is shorter than three times the target duration, can
+/// not be shortened any further, so the effective window might be larger.
+///
+/// [rfc8216#section-6.2.2](https://tools.ietf.org/html/rfc8216#section-6.2.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Window {
+    /// Keeps the latest `n` segments.
+    Segments(usize),
+    /// Keeps the latest segments, which are together at least as long as the
+    /// [`Duration`].
+    Duration(Duration),
+}
+
+/// A live [`MediaPlaylist`], which removes old segments, when new segments
+/// are added.
+///
+/// If a segment is removed,
+///
+/// - the [`MediaPlaylist::media_sequence`] is incremented,
+/// - the [`MediaPlaylist::discontinuity_sequence`] is incremented, if the
+///   segment has a discontinuity, and
+/// - the [`ExtXKey`]s, the [`ExtXMap`] and (with the `chrono` feature) the
+///   [`ExtXProgramDateTime`] of the segment are moved to the next segment, if
+///   the next segment does not have them.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::live::{LiveMediaPlaylist, Window};
+/// use hls_m3u8::tags::ExtInf;
+/// use hls_m3u8::{MediaPlaylist, MediaSegment};
+/// use std::time::Duration;
+///
+/// let mut playlist = LiveMediaPlaylist::new(
+///     MediaPlaylist::builder()
+///         .target_duration(Duration::from_secs(2))
+///         .segments(vec![])
+///         .build()?,
+///     Window::Segments(3),
+/// )?;
+///
+/// for i in 0..5 {
+///     playlist.push_segment(
+///         MediaSegment::builder()
+///             .duration(ExtInf::new(Duration::from_secs(2)))
+///             .uri(format!("{}.ts", i))
+///             .build()?,
+///     )?;
+/// }
+///
+/// assert_eq!(
+///     playlist.to_string(),
+///     concat!(
+///         "#EXTM3U\n",
+///         "#EXT-X-TARGETDURATION:2\n",
+///         "#EXT-X-MEDIA-SEQUENCE:2\n",
+///         "#EXTINF:2,\n",
+///         "2.ts\n",
+///         "#EXTINF:2,\n",
+///         "3.ts\n",
+///         "#EXTINF:2,\n",
+///         "4.ts\n",
+///     )
+/// );
+///
+/// playlist.end_stream();
+/// assert!(playlist.playlist().has_end_list);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`ExtXKey`]: crate::tags::ExtXKey
+/// [`ExtXMap`]: crate::tags::ExtXMap
+/// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveMediaPlaylist<'a> {
+    playlist: MediaPlaylist<'a>,
+    window: Window,
+}
+
+impl<'a> LiveMediaPlaylist<'a> {
+    /// Makes a new [`LiveMediaPlaylist`] from a playlist, which might already
+    /// contain segments.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the playlist has a
+    /// [`MediaPlaylist::playlist_type`] (segments can not be removed from a
+    /// [`PlaylistType::Event`] or [`PlaylistType::Vod`] playlist) or if it
+    /// has an [`ExtXEndList`].
+    ///
+    /// [`PlaylistType::Event`]: crate::types::PlaylistType::Event
+    /// [`PlaylistType::Vod`]: crate::types::PlaylistType::Vod
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    pub fn new(playlist: MediaPlaylist<'a>, window: Window) -> crate::Result<Self> {
+        if let Some(playlist_type) = playlist.playlist_type {
+            return Err(Error::custom(format!(
+                "segments can not be removed from a playlist with the type {}",
+                playlist_type
+            )));
+        }
+
+        if playlist.has_end_list {
+            return Err(Error::custom("the playlist has already ended"));
+        }
+
+        let segments = playlist.segments.values().cloned().collect();
+        let mut result = Self { playlist, window };
+        result.update(segments)?;
+
+        Ok(result)
+    }
+
+    /// Returns the [`Window`] of the playlist.
+    #[must_use]
+    pub const fn window(&self) -> Window { self.window }
+
+    /// Returns the current [`MediaPlaylist`].
+    #[must_use]
+    pub const fn playlist(&self) -> &MediaPlaylist<'a> { &self.playlist }
+
+    /// Returns the current [`MediaPlaylist`].
+    #[must_use]
+    pub fn into_inner(self) -> MediaPlaylist<'a> { self.playlist }
+
+    /// Returns `true`, if [`LiveMediaPlaylist::end_stream`] has been called.
+    #[must_use]
+    pub const fn has_ended(&self) -> bool { self.playlist.has_end_list }
+
+    /// Adds a segment to the end of the playlist and removes the segments,
+    /// that are no longer in the [`Window`].
+    ///
+    /// The [`MediaSegment::number`] of the segment is assigned automatically.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the stream has ended or if the
+    /// segment is not valid in the playlist (for example, because it is
+    /// longer than the [`MediaPlaylist::target_duration`]). The playlist is
+    /// not changed in this case.
+    pub fn push_segment(&mut self, segment: MediaSegment<'a>) -> crate::Result<&mut Self> {
+        if self.has_ended() {
+            return Err(Error::custom("can not add a segment to an ended stream"));
+        }
+
+        let mut segments = self.playlist.segments.values().cloned().collect::<Vec<_>>();
+        segments.push(segment);
+
+        self.update(segments)?;
+
+        Ok(self)
+    }
+
+    /// Marks the end of the stream by adding an [`ExtXEndList`] to the
+    /// playlist. No segments can be added afterwards.
+    ///
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    pub fn end_stream(&mut self) -> &mut Self {
+        self.playlist.has_end_list = true;
+        self
+    }
+
+    /// Removes all segments, that are no longer in the window, and replaces
+    /// the segments of the playlist.
+    fn update(&mut self, mut segments: Vec<MediaSegment<'a>>) -> crate::Result<()> {
+        let mut media_sequence = self.playlist.media_sequence;
+        let mut discontinuity_sequence = self.playlist.discontinuity_sequence;
+
+        let mut duration = segments
+            .iter()
+            .map(|s| s.duration.exact_duration())
+            .sum::<Decimal>()
+            .as_duration();
+
+        // The server MUST NOT remove a Media Segment from a Playlist file
+        // without an EXT-X-ENDLIST tag if that would produce a Playlist whose
+        // duration is less than three times the target duration.
+        //
+        // https://tools.ietf.org/html/rfc8216#section-6.2.2
+        let minimum = self.playlist.target_duration * 3;
+
+        let mut removed = 0;
+
+        while segments.len() - removed > 1 {
+            let remaining = duration
+                .checked_sub(segments[removed].duration.exact_duration().as_duration())
+                .unwrap_or_default();
+
+            let is_outside = match self.window {
+                Window::Segments(n) => segments.len() - removed > n,
+                Window::Duration(value) => remaining >= value,
+            };
+
+            if !is_outside || remaining < minimum {
+                break;
+            }
+
+            let (evicted, next) = {
+                let (head, tail) = segments.split_at_mut(removed + 1);
+                (&head[removed], &mut tail[0])
+            };
+
+            carry_forward(evicted, next);
+
+            media_sequence += 1;
+
+            if evicted.has_discontinuity {
+                discontinuity_sequence += 1;
+            }
+
+            duration = remaining;
+            removed += 1;
+        }
+
+        self.playlist = rebuild(
+            &self.playlist,
+            media_sequence,
+            discontinuity_sequence,
+            segments.split_off(removed),
+        )?;
+
+        Ok(())
+    }
+}
+
+/// An error, that is returned, if a change to an [`EventMediaPlaylist`] would
+/// violate the rules for [`PlaylistType::Event`] playlists.
+///
+/// [rfc8216#section-6.2.1](https://tools.ietf.org/html/rfc8216#section-6.2.1)
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum EventError {
+    /// The playlist does not have the [`PlaylistType::Event`].
+    #[error("the playlist type is {found:?}, but expected EVENT")]
+    NotAnEvent {
+        /// The type of the playlist.
+        found: Option<PlaylistType>,
+    },
+    /// The event has an [`ExtXEndList`], so no segments can be added.
+    ///
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    #[error("the event has already ended")]
+    Ended,
+    /// A segment has been removed from the playlist.
+    #[error("the segment {number} has been removed")]
+    SegmentRemoved {
+        /// The [`MediaSegment::number`] of the removed segment.
+        number: usize,
+    },
+    /// A segment of the playlist has been changed.
+    #[error("the segment {number} has been changed")]
+    SegmentChanged {
+        /// The [`MediaSegment::number`] of the changed segment.
+        number: usize,
+    },
+    /// A tag, that applies to the entire playlist, has been changed.
+    #[error("the tag {tag} has been changed")]
+    TagChanged {
+        /// The name of the tag (for example `EXT-X-TARGETDURATION`).
+        tag: &'static str,
+    },
+    /// The duration of an appended segment exceeds the target duration, which
+    /// can not be changed.
+    #[error("the duration {duration:?} of the segment {number} exceeds the target duration")]
+    TargetDurationExceeded {
+        /// The [`MediaSegment::number`] of the segment.
+        number: usize,
+        /// The duration of the segment.
+        duration: Duration,
+    },
+    /// The playlist is invalid for another reason.
+    #[error("{0}")]
+    Invalid(Error),
+}
+
+/// An append-only [`PlaylistType::Event`] playlist.
+///
+/// The segments and the tags of the playlist can not be changed, segments can
+/// only be added to the end of the playlist and the playlist can only be
+/// ended.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::live::{EventError, EventMediaPlaylist};
+/// use hls_m3u8::tags::ExtInf;
+/// use hls_m3u8::types::PlaylistType;
+/// use hls_m3u8::{MediaPlaylist, MediaSegment};
+/// use std::time::Duration;
+///
+/// let mut event = EventMediaPlaylist::new(
+///     MediaPlaylist::builder()
+///         .target_duration(Duration::from_secs(10))
+///         .playlist_type(PlaylistType::Event)
+///         .segments(vec![])
+///         .build()?,
+/// )?;
+///
+/// event.push_segment(
+///     MediaSegment::builder()
+///         .duration(ExtInf::new(Duration::from_secs(10)))
+///         .uri("0.ts")
+///         .build()?,
+/// )?;
+///
+/// // the segment is longer than the target duration:
+/// assert_eq!(
+///     event.push_segment(
+///         MediaSegment::builder()
+///             .duration(ExtInf::new(Duration::from_secs(12)))
+///             .uri("1.ts")
+///             .build()?,
+///     ),
+///     Err(EventError::TargetDurationExceeded {
+///         number: 1,
+///         duration: Duration::from_secs(12),
+///     })
+/// );
+///
+/// let playlist = event.finalize();
+///
+/// assert_eq!(playlist.playlist_type, Some(PlaylistType::Vod));
+/// assert!(playlist.has_end_list);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventMediaPlaylist<'a> {
+    playlist: MediaPlaylist<'a>,
+}
+
+impl<'a> EventMediaPlaylist<'a> {
+    /// Makes a new [`EventMediaPlaylist`] from a playlist, which might already
+    /// contain segments.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the playlist does not have the
+    /// [`PlaylistType::Event`].
+    pub fn new(playlist: MediaPlaylist<'a>) -> Result<Self, EventError> {
+        if playlist.playlist_type != Some(PlaylistType::Event) {
+            return Err(EventError::NotAnEvent {
+                found: playlist.playlist_type,
+            });
+        }
+
+        Ok(Self { playlist })
+    }
+
+    /// Returns the current [`MediaPlaylist`].
+    #[must_use]
+    pub const fn playlist(&self) -> &MediaPlaylist<'a> { &self.playlist }
+
+    /// Returns `true`, if the playlist has an [`ExtXEndList`].
+    ///
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    #[must_use]
+    pub const fn has_ended(&self) -> bool { self.playlist.has_end_list }
+
+    /// Adds a segment to the end of the playlist.
+    ///
+    /// The [`MediaSegment::number`] of the segment is assigned automatically.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the event has ended or if the
+    /// segment is not valid in the playlist. The playlist is not changed in
+    /// this case.
+    pub fn push_segment(&mut self, segment: MediaSegment<'a>) -> Result<&mut Self, EventError> {
+        if self.has_ended() {
+            return Err(EventError::Ended);
+        }
+
+        let number = self.playlist.media_sequence + self.playlist.segments.num_elements();
+        check_target_duration(&self.playlist, number, &segment)?;
+
+        let mut segments = self.playlist.segments.values().cloned().collect::<Vec<_>>();
+        segments.push(segment);
+
+        self.playlist = rebuild(
+            &self.playlist,
+            self.playlist.media_sequence,
+            self.playlist.discontinuity_sequence,
+            segments,
+        )
+        .map_err(EventError::Invalid)?;
+
+        Ok(self)
+    }
+
+    /// Replaces the playlist with a newer version of the playlist (for
+    /// example, one that has been written by another process).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the newer version does not only
+    /// append segments or an [`ExtXEndList`] to the current playlist or if an
+    /// appended segment exceeds the target duration (like in
+    /// [`EventMediaPlaylist::push_segment`]). The playlist is not changed in
+    /// this case.
+    ///
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    pub fn update(&mut self, playlist: MediaPlaylist<'a>) -> Result<&mut Self, EventError> {
+        let current = &self.playlist;
+
+        if playlist.playlist_type != current.playlist_type {
+            return Err(EventError::TagChanged {
+                tag: "EXT-X-PLAYLIST-TYPE",
+            });
+        }
+
+        for (tag, is_changed) in &[
+            (
+                "EXT-X-TARGETDURATION",
+                playlist.target_duration != current.target_duration,
+            ),
+            (
+                "EXT-X-MEDIA-SEQUENCE",
+                playlist.media_sequence != current.media_sequence,
+            ),
+            (
+                "EXT-X-DISCONTINUITY-SEQUENCE",
+                playlist.discontinuity_sequence != current.discontinuity_sequence,
+            ),
+            (
+                "EXT-X-I-FRAMES-ONLY",
+                playlist.has_i_frames_only != current.has_i_frames_only,
+            ),
+            (
+                "EXT-X-INDEPENDENT-SEGMENTS",
+                playlist.has_independent_segments != current.has_independent_segments,
+            ),
+            ("EXT-X-START", playlist.start != current.start),
+            (
+                "EXT-X-ENDLIST",
+                current.has_end_list && !playlist.has_end_list,
+            ),
+        ] {
+            if *is_changed {
+                return Err(EventError::TagChanged { tag });
+            }
+        }
+
+        let mut segments = playlist.segments.values();
+
+        for segment in current.segments.values() {
+            match segments.next() {
+                Some(other) if other == segment => {}
+                Some(_) => {
+                    return Err(EventError::SegmentChanged {
+                        number: segment.number,
+                    })
+                }
+                None => {
+                    return Err(EventError::SegmentRemoved {
+                        number: segment.number,
+                    })
+                }
+            }
+        }
+
+        let appended = segments.collect::<Vec<_>>();
+
+        if current.has_end_list && !appended.is_empty() {
+            return Err(EventError::Ended);
+        }
+
+        let first = current.media_sequence + current.segments.num_elements();
+
+        for (i, segment) in appended.into_iter().enumerate() {
+            check_target_duration(current, first + i, segment)?;
+        }
+
+        self.playlist = playlist;
+
+        Ok(self)
+    }
+
+    /// Marks the end of the event by adding an [`ExtXEndList`] to the
+    /// playlist. No segments can be added afterwards.
+    ///
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    pub fn end_stream(&mut self) -> &mut Self {
+        self.playlist.has_end_list = true;
+        self
+    }
+
+    /// Ends the event and converts it into a [`PlaylistType::Vod`] playlist.
+    #[must_use]
+    pub fn finalize(self) -> MediaPlaylist<'a> {
+        let mut playlist = self.playlist;
+
+        playlist.has_end_list = true;
+        playlist.playlist_type = Some(PlaylistType::Vod);
+
+        playlist
+    }
+
+    /// Ends the event and converts it into a playlist without a
+    /// [`MediaPlaylist::playlist_type`].
+    #[must_use]
+    pub fn finalize_without_playlist_type(self) -> MediaPlaylist<'a> {
+        let mut playlist = self.playlist;
+
+        playlist.has_end_list = true;
+        playlist.playlist_type = None;
+
+        playlist
+    }
+}
+
+/// Checks, that the (rounded) duration of a segment, that is appended to an
+/// [`EventMediaPlaylist`], does not exceed the target duration.
+fn check_target_duration(
+    playlist: &MediaPlaylist<'_>,
+    number: usize,
+    segment: &MediaSegment<'_>,
+) -> Result<(), EventError> {
+    // the segment duration is rounded to the nearest integer:
+    // https://tools.ietf.org/html/rfc8216#section-4.3.3.1
+    if segment.duration.rounded_duration()
+        > playlist.target_duration + playlist.allowable_excess_duration
+    {
+        return Err(EventError::TargetDurationExceeded {
+            number,
+            duration: segment.duration.duration(),
+        });
+    }
+
+    Ok(())
+}
+
+impl<'a> fmt::Display for EventMediaPlaylist<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.playlist.fmt(f) }
+}
+
+/// An error, that is returned by [`MediaPlaylist::reconcile`], if a reloaded
+/// playlist is not a valid successor of the previous playlist.
+///
+/// [rfc8216#section-6.2.1](https://tools.ietf.org/html/rfc8216#section-6.2.1)
+/// and [rfc8216#section-6.3.4](https://tools.ietf.org/html/rfc8216#section-6.3.4)
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ReloadError {
+    /// The [`MediaPlaylist::media_sequence`] is smaller than in the previous
+    /// playlist.
+    #[error("the media sequence went back from {previous} to {current}")]
+    MediaSequenceRegressed {
+        /// The media sequence of the previous playlist.
+        previous: usize,
+        /// The media sequence of the reloaded playlist.
+        current: usize,
+    },
+    /// The [`MediaPlaylist::discontinuity_sequence`] does not match the
+    /// discontinuities of the removed segments.
+    #[error("expected the discontinuity sequence {expected}, but found {found}")]
+    DiscontinuitySequenceMismatch {
+        /// The discontinuity sequence of the previous playlist plus the
+        /// number of removed segments with a discontinuity.
+        expected: usize,
+        /// The discontinuity sequence of the reloaded playlist.
+        found: usize,
+    },
+    /// A segment of the previous playlist, that has not been removed from the
+    /// start of the playlist, is missing in the reloaded playlist.
+    #[error("the segment {number} is missing")]
+    SegmentMissing {
+        /// The [`MediaSegment::number`] of the missing segment.
+        number: usize,
+    },
+    /// A segment has a different URI or duration than in the previous
+    /// playlist (the title and the formatting of the duration are ignored).
+    #[error("the segment {number} has been changed")]
+    SegmentChanged {
+        /// The [`MediaSegment::number`] of the changed segment.
+        number: usize,
+    },
+    /// Segments have been removed from a [`PlaylistType::Event`] playlist.
+    #[error("{count} segment(s) have been removed from an EVENT playlist")]
+    EventSegmentsRemoved {
+        /// The number of removed segments.
+        count: usize,
+    },
+    /// The previous playlist had an [`ExtXEndList`], but the playlist has
+    /// been changed.
+    ///
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    #[error("the playlist has been changed after it ended")]
+    ChangedAfterEnd,
+    /// The playlist has not been changed for more than one and a half target
+    /// durations.
+    #[error("the playlist has not been changed for {elapsed:?} (limit {limit:?})")]
+    Stale {
+        /// The time since the playlist has been changed.
+        elapsed: Duration,
+        /// One and a half target durations.
+        limit: Duration,
+    },
+}
+
+/// The difference between a reloaded [`MediaPlaylist`] and the previous
+/// version of the playlist, which is returned by
+/// [`MediaPlaylist::reconcile`].
+///
+/// # Example
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use hls_m3u8::MediaPlaylist;
+/// use std::time::Duration;
+///
+/// let previous = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-MEDIA-SEQUENCE:1\n",
+///     "#EXTINF:10,\n",
+///     "1.ts\n",
+///     "#EXTINF:10,\n",
+///     "2.ts\n",
+/// ))?;
+///
+/// let current = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-MEDIA-SEQUENCE:2\n",
+///     "#EXTINF:10,\n",
+///     "2.ts\n",
+///     "#EXTINF:10,\n",
+///     "3.ts\n",
+/// ))?;
+///
+/// let outcome = current.reconcile(&previous)?;
+///
+/// assert_eq!(outcome.added.len(), 1);
+/// assert_eq!(outcome.added[0].uri(), "3.ts");
+/// assert_eq!(outcome.removed.len(), 1);
+/// assert_eq!(outcome.removed[0].uri(), "1.ts");
+/// assert_eq!(outcome.reload_delay(), Duration::from_secs(10));
+///
+/// // the reloaded playlist did not change:
+/// let outcome = current.reconcile(&current)?;
+///
+/// assert!(outcome.is_unchanged());
+/// assert_eq!(outcome.reload_delay(), Duration::from_secs(5));
+/// assert!(outcome.check_staleness(Duration::from_secs(12)).is_ok());
+/// assert!(outcome.check_staleness(Duration::from_secs(16)).is_err());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReloadOutcome<'p, 'a> {
+    /// The segments, that have been appended to the playlist.
+    pub added: Vec<&'p MediaSegment<'a>>,
+    /// The segments of the previous playlist, that have been removed from
+    /// the start of the playlist.
+    pub removed: Vec<&'p MediaSegment<'a>>,
+    target_duration: Duration,
+    has_end_list: bool,
+    is_unchanged: bool,
+}
+
+impl<'p, 'a> ReloadOutcome<'p, 'a> {
+    pub(crate) fn new(
+        current: &'p MediaPlaylist<'a>,
+        previous: &'p MediaPlaylist<'a>,
+    ) -> Result<Self, ReloadError> {
+        if current.media_sequence < previous.media_sequence {
+            return Err(ReloadError::MediaSequenceRegressed {
+                previous: previous.media_sequence,
+                current: current.media_sequence,
+            });
+        }
+
+        let removed = previous
+            .segments
+            .values()
+            .take_while(|s| s.number < current.media_sequence)
+            .collect::<Vec<_>>();
+
+        if previous.playlist_type == Some(PlaylistType::Event) && !removed.is_empty() {
+            return Err(ReloadError::EventSegmentsRemoved {
+                count: removed.len(),
+            });
+        }
+
+        // the discontinuities of segments, that have never been seen, are
+        // unknown:
+        let is_gapless = previous
+            .last_number()
+            .map_or(true, |number| current.media_sequence <= number + 1);
+
+        if is_gapless {
+            let expected = previous.discontinuity_sequence
+                + removed.iter().filter(|s| s.has_discontinuity).count();
+
+            if expected != current.discontinuity_sequence {
+                return Err(ReloadError::DiscontinuitySequenceMismatch {
+                    expected,
+                    found: current.discontinuity_sequence,
+                });
+            }
+        }
+
+        for segment in previous.segments.values().skip(removed.len()) {
+            match current.segment_by_number(segment.number) {
+                Some(other) => {
+                    if other.uri() != segment.uri()
+                        || other.duration.duration() != segment.duration.duration()
+                    {
+                        return Err(ReloadError::SegmentChanged {
+                            number: segment.number,
+                        });
+                    }
+                }
+                None => {
+                    return Err(ReloadError::SegmentMissing {
+                        number: segment.number,
+                    })
+                }
+            }
+        }
+
+        let added = current
+            .segments
+            .values()
+            .filter(|s| previous.last_number().map_or(true, |number| s.number > number))
+            .collect::<Vec<_>>();
+
+        let is_unchanged =
+            added.is_empty() && removed.is_empty() && current.has_end_list == previous.has_end_list;
+
+        if previous.has_end_list && !is_unchanged {
+            return Err(ReloadError::ChangedAfterEnd);
+        }
+
+        Ok(Self {
+            added,
+            removed,
+            target_duration: current.target_duration,
+            has_end_list: current.has_end_list,
+            is_unchanged,
+        })
+    }
+
+    /// Returns `true`, if the reloaded playlist did not change.
+    #[must_use]
+    pub const fn is_unchanged(&self) -> bool { self.is_unchanged }
+
+    /// Returns the minimum time, that a client has to wait, before it reloads
+    /// the playlist again, measured from the time it began to load the
+    /// playlist.
+    ///
+    /// This is the target duration, if the playlist has changed, and half of
+    /// the target duration otherwise.
+    ///
+    /// [rfc8216#section-6.3.4](https://tools.ietf.org/html/rfc8216#section-6.3.4)
+    #[must_use]
+    pub fn reload_delay(&self) -> Duration {
+        if self.is_unchanged {
+            self.target_duration / 2
+        } else {
+            self.target_duration
+        }
+    }
+
+    /// Checks, that an unchanged playlist is not stale.
+    ///
+    /// The server must make a new version of a live playlist available within
+    /// one and a half target durations after the previous version.
+    ///
+    /// [rfc8216#section-6.2.1](https://tools.ietf.org/html/rfc8216#section-6.2.1)
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`ReloadError::Stale`], if the playlist is
+    /// unchanged, has no [`ExtXEndList`] and `since_last_change` (the time
+    /// since the playlist has been changed last) exceeds one and a half target
+    /// durations.
+    ///
+    /// [`ExtXEndList`]: crate::tags::ExtXEndList
+    pub fn check_staleness(&self, since_last_change: Duration) -> Result<(), ReloadError> {
+        let limit = self.target_duration * 3 / 2;
+
+        if self.is_unchanged && !self.has_end_list && since_last_change > limit {
+            return Err(ReloadError::Stale {
+                elapsed: since_last_change,
+                limit,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a new playlist with the tags of `template` and the `segments`.
+fn rebuild<'a>(
+    template: &MediaPlaylist<'a>,
+    media_sequence: usize,
+    discontinuity_sequence: usize,
+    mut segments: Vec<MediaSegment<'a>>,
+) -> crate::Result<MediaPlaylist<'a>> {
+    for segment in &mut segments {
+        // the segments are numbered from the media sequence:
+        segment.explicit_number = false;
+    }
+
+    let mut builder = MediaPlaylist::builder();
+
+    builder
+        .target_duration(template.target_duration)
+        .media_sequence(media_sequence)
+        .discontinuity_sequence(discontinuity_sequence)
+        .has_i_frames_only(template.has_i_frames_only)
+        .has_independent_segments(template.has_independent_segments)
+        .has_end_list(template.has_end_list)
+        .allowable_excess_duration(template.allowable_excess_duration)
+        .unknown(template.unknown.clone())
+        .segments(segments);
+
+    if let Some(value) = template.playlist_type {
+        builder.playlist_type(value);
+    }
+
+    if let Some(value) = template.start {
+        builder.start(value);
+    }
+
+    builder.build().map_err(Error::builder)
+}
+
+/// Moves the tags, that still apply to the next segment, from a removed
+/// segment to the next segment.
+fn carry_forward<'a>(evicted: &MediaSegment<'a>, next: &mut MediaSegment<'a>) {
+    if next.keys.is_empty() {
+        next.keys.clone_from(&evicted.keys);
+    }
+
+    if next.map.is_none() {
+        next.map.clone_from(&evicted.map);
+    }
+
+    #[cfg(feature = "chrono")]
+    {
+        if next.program_date_time.is_none() {
+            if let Some(mut program_date_time) = evicted.program_date_time {
+                if let Ok(duration) = chrono::Duration::from_std(evicted.duration.duration()) {
+                    program_date_time.date_time += duration;
+                    next.program_date_time = Some(program_date_time);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Display for LiveMediaPlaylist<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.playlist.fmt(f) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::convert::TryFrom;
+    use crate::tags::{ExtInf, ExtXKey, ExtXMap};
+    use crate::types::{DecryptionKey, EncryptionMethod};
+    use pretty_assertions::assert_eq;
+
+    fn segment<T: Into<Cow<'static, str>>>(duration: u64, uri: T) -> MediaSegment<'static> {
+        MediaSegment::builder()
+            .duration(ExtInf::new(Duration::from_secs(duration)))
+            .uri(uri)
+            .build()
+            .unwrap()
+    }
+
+    fn live(window: Window) -> LiveMediaPlaylist<'static> {
+        LiveMediaPlaylist::new(
+            MediaPlaylist::builder()
+                .target_duration(Duration::from_secs(2))
+                .segments(vec![])
+                .build()
+                .unwrap(),
+            window,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_window_segments() {
+        let mut playlist = live(Window::Segments(4));
+
+        let mut first = segment(2, "0.ts");
+        first.keys = vec![ExtXKey::new(DecryptionKey::new(
+            EncryptionMethod::Aes128,
+            "https://example.com/key",
+        ))];
+        first.map = Some(ExtXMap::new("init.mp4"));
+
+        playlist.push_segment(first).unwrap();
+
+        for i in 1..6 {
+            let mut segment = segment(2, format!("{}.ts", i));
+            segment.has_discontinuity = i == 1;
+            playlist.push_segment(segment).unwrap();
+        }
+
+        let inner = playlist.playlist();
+
+        assert_eq!(inner.segments.num_elements(), 4);
+        assert_eq!(inner.media_sequence, 2);
+        assert_eq!(inner.discontinuity_sequence, 1);
+        assert_eq!(inner.segments[0].number(), 2);
+        assert_eq!(inner.segments[0].uri(), "2.ts");
+        assert_eq!(inner.segments[0].map, Some(ExtXMap::new("init.mp4")));
+        assert_eq!(
+            inner.segments[0].keys[0].as_ref().map(|key| key.uri().as_ref()),
+            Some("https://example.com/key")
+        );
+    }
+
+    #[test]
+    fn test_window_duration() {
+        let mut playlist = live(Window::Duration(Duration::from_secs(7)));
+
+        for i in 0..6 {
+            playlist.push_segment(segment(2, format!("{}.ts", i))).unwrap();
+        }
+
+        assert_eq!(playlist.playlist().duration(), Duration::from_secs(8));
+        assert_eq!(playlist.playlist().media_sequence, 2);
+    }
+
+    #[test]
+    fn test_minimum_window() {
+        // the window must be at least three times the target duration:
+        let mut playlist = live(Window::Segments(1));
+
+        for i in 0..5 {
+            playlist.push_segment(segment(2, format!("{}.ts", i))).unwrap();
+        }
+
+        assert_eq!(playlist.playlist().segments.num_elements(), 3);
+        assert_eq!(playlist.playlist().media_sequence, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_program_date_time() {
+        use crate::tags::ExtXProgramDateTime;
+        use chrono::DateTime;
+
+        let date = |value| DateTime::parse_from_rfc3339(value).unwrap();
+        let mut playlist = live(Window::Segments(3));
+
+        let mut first = segment(2, "0.ts");
+        first.program_date_time = Some(ExtXProgramDateTime::new(date("2020-01-01T00:00:00Z")));
+
+        playlist.push_segment(first).unwrap();
+
+        for i in 1..5 {
+            playlist.push_segment(segment(2, format!("{}.ts", i))).unwrap();
+        }
+
+        assert_eq!(
+            playlist.playlist().segments[0].program_date_time,
+            Some(ExtXProgramDateTime::new(date("2020-01-01T00:00:04Z")))
+        );
+    }
+
+    fn event() -> EventMediaPlaylist<'static> {
+        EventMediaPlaylist::new(
+            MediaPlaylist::builder()
+                .target_duration(Duration::from_secs(2))
+                .playlist_type(PlaylistType::Event)
+                .segments(vec![segment(2, "0.ts")])
+                .build()
+                .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_event() {
+        let mut playlist = event();
+
+        playlist.push_segment(segment(2, "1.ts")).unwrap();
+
+        assert_eq!(playlist.playlist().segments.num_elements(), 2);
+        assert_eq!(playlist.playlist().segments[1].number(), 1);
+        assert_eq!(
+            playlist.push_segment(segment(3, "2.ts")),
+            Err(EventError::TargetDurationExceeded {
+                number: 2,
+                duration: Duration::from_secs(3)
+            })
+        );
+
+        playlist.end_stream();
+
+        assert_eq!(playlist.push_segment(segment(2, "2.ts")), Err(EventError::Ended));
+
+        let finalized = playlist.clone().finalize();
+        assert_eq!(finalized.playlist_type, Some(PlaylistType::Vod));
+        assert!(finalized.has_end_list);
+
+        let finalized = playlist.finalize_without_playlist_type();
+        assert_eq!(finalized.playlist_type, None);
+        assert!(finalized.to_string().ends_with("#EXT-X-ENDLIST\n"));
+
+        assert_eq!(
+            EventMediaPlaylist::new(finalized),
+            Err(EventError::NotAnEvent { found: None })
+        );
+    }
+
+    #[test]
+    fn test_event_update() {
+        let mut playlist = event();
+        let current = playlist.playlist().clone();
+
+        let mut appended = current.clone();
+        appended.segments.push(segment(2, "1.ts"));
+        appended.has_end_list = true;
+
+        let mut removed = current.clone();
+        removed.segments.clear();
+
+        let mut changed = current.clone();
+        changed.segments[0].set_uri("other.ts");
+
+        let mut target_duration = current.clone();
+        target_duration.target_duration = Duration::from_secs(4);
+
+        let mut vod = current.clone();
+        vod.playlist_type = Some(PlaylistType::Vod);
+
+        let mut too_long = current.clone();
+        too_long.segments.push(segment(3, "1.ts"));
+
+        assert_eq!(
+            playlist.update(removed),
+            Err(EventError::SegmentRemoved { number: 0 })
+        );
+        assert_eq!(
+            playlist.update(changed),
+            Err(EventError::SegmentChanged { number: 0 })
+        );
+        assert_eq!(
+            playlist.update(target_duration),
+            Err(EventError::TagChanged {
+                tag: "EXT-X-TARGETDURATION"
+            })
+        );
+        assert_eq!(
+            playlist.update(vod),
+            Err(EventError::TagChanged {
+                tag: "EXT-X-PLAYLIST-TYPE"
+            })
+        );
+
+        assert_eq!(
+            playlist.update(too_long),
+            Err(EventError::TargetDurationExceeded {
+                number: 1,
+                duration: Duration::from_secs(3)
+            })
+        );
+
+        playlist.update(appended).unwrap();
+        assert!(playlist.has_ended());
+
+        assert_eq!(
+            playlist.update(current),
+            Err(EventError::TagChanged {
+                tag: "EXT-X-ENDLIST"
+            })
+        );
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let previous = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:3\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:2,\n",
+            "10.ts\n",
+            "#EXTINF:2,\n",
+            "11.ts\n",
+        ))
+        .unwrap();
+
+        let reload = |input| MediaPlaylist::try_from(input).unwrap();
+
+        let current = reload(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-MEDIA-SEQUENCE:11\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:4\n",
+            "#EXTINF:2,\n",
+            "11.ts\n",
+            "#EXTINF:2,\n",
+            "12.ts\n",
+        ));
+
+        let outcome = current.reconcile(&previous).unwrap();
+
+        assert_eq!(outcome.removed, vec![&previous.segments[0]]);
+        assert_eq!(outcome.added, vec![&current.segments[1]]);
+        assert!(!outcome.is_unchanged());
+        assert_eq!(outcome.check_staleness(Duration::from_secs(60)), Ok(()));
+
+        // the discontinuity of the removed segment is not counted:
+        assert_eq!(
+            reload(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:2\n",
+                "#EXT-X-MEDIA-SEQUENCE:11\n",
+                "#EXT-X-DISCONTINUITY-SEQUENCE:3\n",
+                "#EXTINF:2,\n",
+                "11.ts\n",
+            ))
+            .reconcile(&previous),
+            Err(ReloadError::DiscontinuitySequenceMismatch {
+                expected: 4,
+                found: 3
+            })
+        );
+
+        assert_eq!(
+            reload(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:2\n",
+                "#EXT-X-MEDIA-SEQUENCE:9\n",
+                "#EXTINF:2,\n",
+                "9.ts\n",
+            ))
+            .reconcile(&previous),
+            Err(ReloadError::MediaSequenceRegressed {
+                previous: 10,
+                current: 9
+            })
+        );
+
+        assert_eq!(
+            reload(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:2\n",
+                "#EXT-X-MEDIA-SEQUENCE:10\n",
+                "#EXT-X-DISCONTINUITY-SEQUENCE:3\n",
+                "#EXTINF:2,\n",
+                "10.ts\n",
+                "#EXTINF:2,\n",
+                "other.ts\n",
+            ))
+            .reconcile(&previous),
+            Err(ReloadError::SegmentChanged { number: 11 })
+        );
+
+        assert_eq!(
+            reload(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:2\n",
+                "#EXT-X-MEDIA-SEQUENCE:10\n",
+                "#EXT-X-DISCONTINUITY-SEQUENCE:3\n",
+                "#EXTINF:2,\n",
+                "10.ts\n",
+            ))
+            .reconcile(&previous),
+            Err(ReloadError::SegmentMissing { number: 11 })
+        );
+
+        // the title and the formatting of the duration are not compared:
+        assert!(reload(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:3\n",
+            "#EXTINF:2.000,title\n",
+            "10.ts\n",
+            "#EXTINF:2,\n",
+            "11.ts\n",
+        ))
+        .reconcile(&previous)
+        .is_ok());
+
+        // segments, that have never been seen, can not be checked:
+        assert!(reload(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-MEDIA-SEQUENCE:20\n",
+            "#EXTINF:2,\n",
+            "20.ts\n",
+        ))
+        .reconcile(&previous)
+        .is_ok());
+
+        let outcome = previous.reconcile(&previous).unwrap();
+
+        assert!(outcome.is_unchanged());
+        assert_eq!(outcome.reload_delay(), Duration::from_secs(1));
+        assert_eq!(outcome.check_staleness(Duration::from_secs(3)), Ok(()));
+        assert_eq!(
+            outcome.check_staleness(Duration::from_secs(4)),
+            Err(ReloadError::Stale {
+                elapsed: Duration::from_secs(4),
+                limit: Duration::from_secs(3)
+            })
+        );
+    }
+
+    #[test]
+    fn test_reconcile_ended() {
+        let mut previous = event().playlist().clone();
+
+        let mut current = event().push_segment(segment(2, "1.ts")).unwrap().playlist().clone();
+
+        current.media_sequence = 1;
+        assert_eq!(
+            current.reconcile(&previous),
+            Err(ReloadError::EventSegmentsRemoved { count: 1 })
+        );
+        current.media_sequence = 0;
+
+        previous.has_end_list = true;
+        current.has_end_list = true;
+
+        assert_eq!(current.reconcile(&previous), Err(ReloadError::ChangedAfterEnd));
+
+        let outcome = previous.reconcile(&previous).unwrap();
+        assert_eq!(outcome.check_staleness(Duration::from_secs(60)), Ok(()));
+    }
+
+    #[test]
+    fn test_end_stream() {
+        let mut playlist = live(Window::Segments(3));
+
+        // the segment is longer than the target duration:
+        assert!(playlist.push_segment(segment(4, "0.ts")).is_err());
+        assert_eq!(playlist.playlist().segments.num_elements(), 0);
+
+        playlist.push_segment(segment(2, "0.ts")).unwrap();
+        playlist.end_stream();
+
+        assert!(playlist.has_ended());
+        assert!(playlist.push_segment(segment(2, "1.ts")).is_err());
+        assert!(playlist.to_string().ends_with("#EXT-X-ENDLIST\n"));
+
+        assert!(LiveMediaPlaylist::new(playlist.into_inner(), Window::Segments(3)).is_err());
+    }
+}
@@ -2,15 +2,18 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::fmt;
+use std::mem;
 
 use derive_builder::Builder;
 
+use crate::canonical;
+use crate::diff::MasterPlaylistDiff;
 use crate::line::{Line, Lines, Tag};
 use crate::tags::{
     ExtM3u, ExtXIndependentSegments, ExtXMedia, ExtXSessionData, ExtXSessionKey, ExtXStart,
     ExtXVersion, VariantStream,
 };
-use crate::types::{ClosedCaptions, MediaType, ProtocolVersion};
+use crate::types::{ClosedCaptions, HdcpLevel, MediaType, ProtocolVersion};
 use crate::utils::{tag, BoolExt};
 use crate::{Error, RequiredVersion};
 
@@ -262,16 +265,131 @@ impl<'a> MasterPlaylist<'a> {
         })
     }
 
+    /// Returns all streams, that can be played by a client, whose output is
+    /// protected with the provided [`HdcpLevel`].
+    ///
+    /// A stream without an [`HdcpLevel`] does not require output copy
+    /// protection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use hls_m3u8::types::HdcpLevel;
+    ///
+    /// # let master_playlist = MasterPlaylist::builder().build()?;
+    /// for stream in master_playlist.hdcp_compatible_streams(HdcpLevel::Type0) {
+    ///     // the client can play `stream`
+    /// }
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn hdcp_compatible_streams(
+        &self,
+        capability: HdcpLevel,
+    ) -> impl Iterator<Item = &VariantStream<'a>> {
+        self.variant_streams.iter().filter(move |stream| {
+            stream
+                .hdcp_level()
+                .map_or(true, |level| level.is_satisfied_by(capability))
+        })
+    }
+
     /// Returns all `ExtXMedia` tags, associated with the provided stream.
     pub fn associated_with<'b>(
         &'b self,
         stream: &'b VariantStream<'_>,
-    ) -> impl Iterator<Item = &ExtXMedia<'a>> + 'b {
+    ) -> impl Iterator<Item = &'b ExtXMedia<'a>> + 'b {
         self.media
             .iter()
             .filter(move |media| stream.is_associated(media))
     }
 
+    /// Returns all audio renditions, associated with the provided stream, that
+    /// can be played by a client, which supports audio with up to
+    /// `max_bit_depth` bits and a sample rate of up to `max_sample_rate` Hz.
+    ///
+    /// See [`ExtXMedia::is_supported_by`] for more information.
+    pub fn supported_audio_renditions<'b>(
+        &'b self,
+        stream: &'b VariantStream<'_>,
+        max_bit_depth: u64,
+        max_sample_rate: u64,
+    ) -> impl Iterator<Item = &'b ExtXMedia<'a>> + 'b {
+        self.associated_with(stream).filter(move |media| {
+            media.media_type == MediaType::Audio
+                && media.is_supported_by(max_bit_depth, max_sample_rate)
+        })
+    }
+
+    /// Returns `true`, if both playlists are semantically equal.
+    ///
+    /// Unlike [`PartialEq`], this ignores differences, that do not change the
+    /// meaning of the playlist, like the order of attributes, the formatting
+    /// of numbers (outside of quoted strings), the order of the
+    /// [`MasterPlaylist::unknown_tags`] tags and the `EXT-X-VERSION` tag.
+    #[must_use]
+    pub fn is_equivalent(&self, other: &MasterPlaylist<'_>) -> bool {
+        self.canonical_form() == other.canonical_form()
+    }
+
+    /// Returns a stable hash of the playlist, which is equal for all
+    /// playlists, that are semantically equal (see
+    /// [`MasterPlaylist::is_equivalent`]).
+    ///
+    /// The hash does not depend on the platform or the compiler, so it can be
+    /// stored (for example, to avoid republishing a playlist, that has not
+    /// changed). It might change with a new version of this crate (for
+    /// example, if a tag is written differently), so stored hashes should
+    /// only be compared with hashes of the same version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MasterPlaylist;
+    /// use core::convert::TryFrom;
+    ///
+    /// let a = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=150000,RESOLUTION=416x234,CODECS=\"avc1.42e00a\"\n",
+    ///     "low.m3u8\n",
+    ///     "#EXT-X-CUSTOM-FIRST\n",
+    ///     "#EXT-X-CUSTOM-SECOND\n",
+    /// ))?;
+    ///
+    /// let b = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-STREAM-INF:CODECS=\"avc1.42e00a\",RESOLUTION=416x234,BANDWIDTH=150000\n",
+    ///     "low.m3u8\n",
+    ///     "#EXT-X-CUSTOM-SECOND\n",
+    ///     "#EXT-X-CUSTOM-FIRST\n",
+    /// ))?;
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.is_equivalent(&b));
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 { canonical::fingerprint(&self.canonical_form()) }
+
+    fn canonical_form(&self) -> String {
+        let mut playlist = self.clone();
+        let unknown = mem::take(&mut playlist.unknown_tags);
+
+        canonical::canonical_form(&playlist.to_string(), &unknown)
+    }
+
+    /// Returns the structural difference between this playlist and a newer
+    /// version of the playlist.
+    ///
+    /// See [`diff`] for an example.
+    ///
+    /// [`diff`]: crate::diff
+    #[must_use]
+    pub fn diff(&self, new: &MasterPlaylist<'_>) -> MasterPlaylistDiff {
+        MasterPlaylistDiff::new(self, new)
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -636,6 +754,95 @@ mod tests {
         assert_eq!(audio_streams.next(), None);
     }
 
+    #[test]
+    fn test_hdcp_compatible_streams() {
+        let stream = |uri: &'static str, hdcp_level: Option<HdcpLevel>| {
+            let mut stream_data = StreamData::new(150_000);
+            stream_data.set_hdcp_level(hdcp_level);
+
+            VariantStream::ExtXStreamInf {
+                uri: uri.into(),
+                frame_rate: None,
+                audio: None,
+                subtitles: None,
+                closed_captions: None,
+                stream_data,
+            }
+        };
+
+        let master_playlist = MasterPlaylist::builder()
+            .variant_streams(vec![
+                stream("sd.m3u8", None),
+                stream("hd.m3u8", Some(HdcpLevel::None)),
+                stream("fhd.m3u8", Some(HdcpLevel::Type0)),
+                stream("uhd.m3u8", Some(HdcpLevel::Type1)),
+            ])
+            .build()
+            .unwrap();
+
+        let uris = |capability| {
+            master_playlist
+                .hdcp_compatible_streams(capability)
+                .map(|stream| match stream {
+                    VariantStream::ExtXStreamInf { uri, .. }
+                    | VariantStream::ExtXIFrame { uri, .. } => uri.to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(uris(HdcpLevel::None), vec!["sd.m3u8", "hd.m3u8"]);
+        assert_eq!(
+            uris(HdcpLevel::Type0),
+            vec!["sd.m3u8", "hd.m3u8", "fhd.m3u8"]
+        );
+        assert_eq!(
+            uris(HdcpLevel::Type1),
+            vec!["sd.m3u8", "hd.m3u8", "fhd.m3u8", "uhd.m3u8"]
+        );
+    }
+
+    #[test]
+    fn test_supported_audio_renditions() {
+        let audio = |name: &'static str, bit_depth: Option<u64>, sample_rate: Option<u64>| {
+            let mut media = ExtXMedia::new(MediaType::Audio, "aud", name);
+            media.set_uri(Some(format!("{}.m3u8", name)));
+            media.bit_depth = bit_depth;
+            media.sample_rate = sample_rate;
+            media
+        };
+
+        let stream = VariantStream::ExtXStreamInf {
+            uri: "video.m3u8".into(),
+            frame_rate: None,
+            audio: Some("aud".into()),
+            subtitles: None,
+            closed_captions: None,
+            stream_data: StreamData::new(150_000),
+        };
+
+        let master_playlist = MasterPlaylist::builder()
+            .media(vec![
+                audio("aac", None, None),
+                audio("alac", Some(16), Some(48_000)),
+                audio("hires", Some(24), Some(192_000)),
+            ])
+            .variant_streams(vec![stream.clone()])
+            .build()
+            .unwrap();
+
+        let names = |max_bit_depth, max_sample_rate| {
+            master_playlist
+                .supported_audio_renditions(&stream, max_bit_depth, max_sample_rate)
+                .map(|media| media.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(16, 44_100), vec!["aac"]);
+        assert_eq!(names(16, 48_000), vec!["aac", "alac"]);
+        assert_eq!(names(24, 96_000), vec!["aac", "alac"]);
+        assert_eq!(names(32, 192_000), vec!["aac", "alac", "hires"]);
+    }
+
     #[test]
     fn test_parser() {
         assert_eq!(
@@ -1,24 +1,42 @@
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt;
+use std::mem;
+#[cfg(feature = "chrono")]
+use std::ops::Range;
+use std::ops::{Bound, RangeBounds};
 use std::str::FromStr;
 use std::time::Duration;
 
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset};
 use derive_builder::Builder;
 use stable_vec::StableVec;
 
+use crate::canonical;
+use crate::diff::MediaPlaylistDiff;
 use crate::line::{Line, Lines, Tag};
+use crate::live::{ReloadError, ReloadOutcome};
 use crate::media_segment::MediaSegment;
 use crate::tags::{
-    ExtM3u, ExtXByteRange, ExtXDiscontinuitySequence, ExtXEndList, ExtXIFramesOnly,
-    ExtXIndependentSegments, ExtXKey, ExtXMediaSequence, ExtXStart, ExtXTargetDuration,
-    ExtXVersion,
+    ExtM3u, ExtXByteRange, ExtXDateRange, ExtXDiscontinuitySequence, ExtXEndList,
+    ExtXIFramesOnly, ExtXIndependentSegments, ExtXKey, ExtXMediaSequence, ExtXStart,
+    ExtXTargetDuration, ExtXVersion,
+};
+#[cfg(feature = "chrono")]
+use crate::tags::ExtXProgramDateTime;
+use crate::timeline::{Periods, Timeline};
+#[cfg(feature = "chrono")]
+use crate::timeline::{
+    program_date_time_issues, DateRangeTimeline, ProgramDateTimeGenerator, ProgramDateTimeIssue,
+    TimelineSegment,
 };
 use crate::types::{
-    DecryptionKey, EncryptionMethod, InitializationVector, KeyFormat, PlaylistType, ProtocolVersion,
+    Decimal, DecryptionKey, EncryptionMethod, Float, InitializationVector, KeyFormat,
+    PlaylistType, ProtocolVersion,
 };
-use crate::utils::{tag, BoolExt};
+use crate::utils::{resolve_uri, tag, BoolExt};
 use crate::{Error, RequiredVersion};
 
 /// Media playlist.
@@ -141,6 +159,31 @@ impl<'a> MediaPlaylistBuilder<'a> {
                 .map_err(|e| e.to_string())?;
         }
 
+        self.validate_date_ranges().map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    fn validate_date_ranges(&self) -> crate::Result<()> {
+        if let Some(segments) = &self.segments {
+            // merging the date ranges verifies, that all tags with the same ID
+            // have the same attribute values and that the combined ranges are
+            // valid:
+            let date_ranges = merge_date_ranges(segments.values())?;
+
+            // If a Playlist contains an EXT-X-DATERANGE tag, it MUST also
+            // contain at least one EXT-X-PROGRAM-DATE-TIME tag.
+            //
+            // https://tools.ietf.org/html/rfc8216#section-4.3.2.7
+            if !date_ranges.is_empty()
+                && segments.values().all(|s| s.program_date_time.is_none())
+            {
+                return Err(Error::custom(
+                    "a playlist with an `EXT-X-DATERANGE` tag must contain an `EXT-X-PROGRAM-DATE-TIME` tag",
+                ));
+            }
+        }
+
         Ok(())
     }
 
@@ -188,8 +231,7 @@ impl<'a> MediaPlaylistBuilder<'a> {
                 let segment_duration = segment.duration.duration();
 
                 // round the duration if it is .5s
-                let rounded_segment_duration =
-                    Duration::from_secs(segment_duration.as_secs_f64().round() as u64);
+                let rounded_segment_duration = segment.duration.rounded_duration();
 
                 let max_segment_duration = self
                     .allowable_excess_duration
@@ -403,9 +445,822 @@ impl<'a> MediaPlaylist<'a> {
 
     /// Computes the `Duration` of the [`MediaPlaylist`], by adding each segment
     /// duration together.
+    ///
+    /// The exact durations of the segments (see [`ExtInf::exact_duration`])
+    /// are added together, so the result does not drift from the literal
+    /// values in the playlist.
+    ///
+    /// [`ExtInf::exact_duration`]: crate::tags::ExtInf::exact_duration
     #[must_use]
     pub fn duration(&self) -> Duration {
-        self.segments.values().map(|s| s.duration.duration()).sum()
+        self.segments
+            .values()
+            .map(|s| s.duration.exact_duration())
+            .sum::<Decimal>()
+            .as_duration()
+    }
+
+    /// Returns the [`MediaSegment`] with the provided [`MediaSegment::number`].
+    ///
+    /// The lookup takes constant time, because [`MediaPlaylistBuilder::build`]
+    /// stores every segment at the index `number - media_sequence` (or at the
+    /// index `number` for a segment with an explicit number) and removing a
+    /// segment from the [`StableVec`] does not move the other segments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    ///
+    /// let mut playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:100\n",
+    ///     "#EXTINF:10,\n",
+    ///     "a.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "b.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "c.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(playlist.segment_by_number(101).map(|s| s.uri().as_ref()), Some("b.ts"));
+    ///
+    /// playlist.segments.remove(0);
+    ///
+    /// assert!(playlist.segment_by_number(100).is_none());
+    /// assert_eq!(playlist.segment_by_number(102).map(|s| s.uri().as_ref()), Some("c.ts"));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn segment_by_number(&self, number: usize) -> Option<&MediaSegment<'a>> {
+        number
+            .checked_sub(self.media_sequence)
+            .and_then(|index| self.segments.get(index))
+            .filter(|segment| segment.number == number)
+            .or_else(|| {
+                // a segment with an explicit number:
+                self.segments
+                    .get(number)
+                    .filter(|segment| segment.number == number)
+            })
+    }
+
+    /// Returns all [`MediaSegment`]s, whose [`MediaSegment::number`] is in the
+    /// provided range, in the order of their numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:100\n",
+    ///     "#EXTINF:10,\n",
+    ///     "a.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "b.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "c.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(
+    ///     playlist
+    ///         .range_by_number(101..)
+    ///         .map(|s| s.uri().as_ref())
+    ///         .collect::<Vec<_>>(),
+    ///     vec!["b.ts", "c.ts"]
+    /// );
+    /// assert_eq!(playlist.range_by_number(0..101).count(), 1);
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    pub fn range_by_number<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = &MediaSegment<'a>> {
+        let start = match range.start_bound() {
+            Bound::Included(value) => *value,
+            Bound::Excluded(value) => value.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(value) => value.saturating_add(1),
+            Bound::Excluded(value) => *value,
+            Bound::Unbounded => usize::max_value(),
+        };
+
+        // there are no segments outside of the first and the last number, so
+        // the range can be limited to them:
+        let first = self.segments.find_first().map_or(0, |s| s.number);
+        let last = self.last_number().map_or(0, |value| value.saturating_add(1));
+
+        (start.max(first)..end.min(last))
+            .filter_map(move |number| self.segment_by_number(number))
+    }
+
+    /// Returns the [`MediaSegment::number`] of the last [`MediaSegment`] or
+    /// `None`, if the playlist has no segments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-MEDIA-SEQUENCE:100\n",
+    ///     "#EXTINF:10,\n",
+    ///     "a.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "b.ts\n",
+    /// ))?;
+    ///
+    /// assert_eq!(playlist.last_number(), Some(101));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn last_number(&self) -> Option<usize> { self.segments.find_last().map(|s| s.number) }
+
+    /// Returns a new [`PlaylistType::Vod`] playlist with all segments, that
+    /// overlap the provided range of offsets from the start of the playlist.
+    ///
+    /// The clip
+    ///
+    /// - keeps the [`MediaSegment::number`]s and the discontinuity sequence of
+    ///   the segments (the [`MediaPlaylist::media_sequence`] is the number of
+    ///   the first segment),
+    /// - adds the [`ExtXMap`], that applies to the first segment, to the first
+    ///   segment (the [`ExtXKey`]s are kept by every segment),
+    /// - starts at the exact in-point with a precise [`ExtXStart`] and
+    /// - has an [`ExtXEndList`].
+    ///
+    /// With the `chrono` feature, the first segment gets an
+    /// [`ExtXProgramDateTime`] (if the date of the segment is known) and the
+    /// [`ExtXDateRange`]s, that have been declared in front of the clip, but
+    /// overlap the clip, are added to it. Without the `chrono` feature, the
+    /// date of a segment can not be calculated, so only the date ranges of the
+    /// clipped segments are kept and only if one of the clipped segments has
+    /// an [`ExtXProgramDateTime`]; otherwise the date ranges are removed.
+    ///
+    /// The [`MediaPlaylist::unknown`] tags are not part of the clip.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    /// use hls_m3u8::types::PlaylistType;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-MAP:URI=\"init.mp4\"\n",
+    ///     "#EXTINF:10,\n",
+    ///     "a.m4s\n",
+    ///     "#EXTINF:10,\n",
+    ///     "b.m4s\n",
+    ///     "#EXTINF:10,\n",
+    ///     "c.m4s\n",
+    /// ))?;
+    ///
+    /// let clip = playlist.clip(Duration::from_secs(12)..Duration::from_secs(18))?;
+    ///
+    /// assert_eq!(clip.playlist_type, Some(PlaylistType::Vod));
+    /// assert_eq!(
+    ///     clip.to_string(),
+    ///     concat!(
+    ///         "#EXTM3U\n",
+    ///         "#EXT-X-VERSION:6\n",
+    ///         "#EXT-X-TARGETDURATION:10\n",
+    ///         "#EXT-X-MEDIA-SEQUENCE:1\n",
+    ///         "#EXT-X-PLAYLIST-TYPE:VOD\n",
+    ///         "#EXT-X-START:TIME-OFFSET=2,PRECISE=YES\n",
+    ///         "#EXT-X-MAP:URI=\"init.mp4\"\n",
+    ///         "#EXTINF:10,\n",
+    ///         "b.m4s\n",
+    ///         "#EXT-X-ENDLIST\n"
+    ///     )
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if no segment overlaps the range or if
+    /// the clip is not a valid playlist (for example, because it contains an
+    /// [`ExtXDateRange`], but no [`ExtXProgramDateTime`]).
+    ///
+    /// [`ExtXMap`]: crate::tags::ExtXMap
+    /// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+    pub fn clip<R: RangeBounds<Duration>>(&self, range: R) -> crate::Result<Self> {
+        let timeline = self.timeline();
+
+        let start = match range.start_bound() {
+            Bound::Included(value) | Bound::Excluded(value) => *value,
+            Bound::Unbounded => Duration::from_secs(0),
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(value) | Bound::Excluded(value) => Some(*value),
+            Bound::Unbounded => None,
+        };
+
+        let clipped = timeline
+            .segments()
+            .iter()
+            .skip_while(|s| s.end() <= start && s.offset < start)
+            .take_while(|s| end.map_or(true, |end| s.offset < end))
+            .collect::<Vec<_>>();
+
+        let first = clipped.first().ok_or_else(|| {
+            Error::custom(format!(
+                "there are no segments between {:?} and {:?}",
+                start, end
+            ))
+        })?;
+
+        let mut segments = clipped
+            .iter()
+            .map(|s| {
+                let mut segment = s.segment.clone();
+                // the segments are numbered from the media sequence:
+                segment.explicit_number = false;
+                segment
+            })
+            .collect::<Vec<_>>();
+
+        segments[0].has_discontinuity = false;
+
+        if segments[0].map.is_none() {
+            segments[0].map = timeline
+                .segments()
+                .iter()
+                .take_while(|s| s.number() < first.number())
+                .filter_map(|s| s.segment.map.clone())
+                .last();
+        }
+
+        #[cfg(feature = "chrono")]
+        {
+            let last = clipped[clipped.len() - 1];
+            self.carry_date_ranges(first, last, &mut segments[0])?;
+        }
+
+        #[cfg(not(feature = "chrono"))]
+        {
+            // a playlist with date ranges must have a program date time, which
+            // can not be calculated without chrono:
+            if segments.iter().all(|s| s.program_date_time.is_none()) {
+                for segment in &mut segments {
+                    segment.date_ranges.clear();
+                }
+            }
+        }
+
+        let in_point = start.checked_sub(first.offset).unwrap_or_default();
+
+        Self::builder()
+            .target_duration(self.target_duration)
+            .media_sequence(first.number())
+            .discontinuity_sequence(first.discontinuity_sequence)
+            .playlist_type(PlaylistType::Vod)
+            .has_i_frames_only(self.has_i_frames_only)
+            .has_independent_segments(self.has_independent_segments)
+            .start(ExtXStart::with_precise(
+                Float::new(in_point.as_secs_f32()),
+                true,
+            ))
+            .has_end_list(true)
+            .allowable_excess_duration(self.allowable_excess_duration)
+            .segments(segments)
+            .build()
+            .map_err(Error::builder)
+    }
+
+    /// Returns a new [`PlaylistType::Vod`] playlist with all segments, that
+    /// overlap the provided range of wall clock times, which are derived from
+    /// the [`ExtXProgramDateTime`] tags (see [`MediaPlaylist::clip`]).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if no segment contains the start of the
+    /// range, if the end of the range is neither in the playlist nor after
+    /// the end of the playlist or if [`MediaPlaylist::clip`] fails.
+    ///
+    /// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+    #[cfg(feature = "chrono")]
+    pub fn clip_by_date(
+        &self,
+        range: Range<DateTime<FixedOffset>>,
+    ) -> crate::Result<Self> {
+        let timeline = self.timeline();
+
+        let start = timeline.offset_at_date(range.start).ok_or_else(|| {
+            Error::custom(format!(
+                "no segment contains the date {}",
+                range.start.to_rfc3339()
+            ))
+        })?;
+
+        let end = {
+            if let Some(value) = timeline.offset_at_date(range.end) {
+                value
+            } else if timeline
+                .segments()
+                .last()
+                .and_then(TimelineSegment::end_date)
+                .map_or(false, |end_date| end_date <= range.end)
+            {
+                timeline.duration()
+            } else {
+                return Err(Error::custom(format!(
+                    "no segment contains the date {}",
+                    range.end.to_rfc3339()
+                )));
+            }
+        };
+
+        self.clip(start..end)
+    }
+
+    /// Adds the program date time and the date ranges, that have been
+    /// declared in front of the clip, but overlap the clip, to the first
+    /// segment of the clip.
+    #[cfg(feature = "chrono")]
+    fn carry_date_ranges(
+        &self,
+        first: &TimelineSegment<'_, 'a>,
+        last: &TimelineSegment<'_, 'a>,
+        segment: &mut MediaSegment<'a>,
+    ) -> crate::Result<()> {
+        let (start_date, end_date) = match (first.start_date, last.end_date()) {
+            (Some(start_date), Some(end_date)) => (start_date, end_date),
+            _ => return Ok(()),
+        };
+
+        // the date ranges of the clipped segments need a program date time as
+        // well, so it is always added:
+        if segment.program_date_time.is_none() {
+            segment.program_date_time = Some(ExtXProgramDateTime::new(start_date));
+        }
+
+        let declared_in_front = self
+            .segments
+            .values()
+            .take_while(|s| s.number < first.number())
+            .flat_map(|s| s.date_ranges.iter().map(ExtXDateRange::id))
+            .collect::<HashSet<_>>();
+
+        let mut carried = vec![];
+
+        for range in self.date_range_timeline()?.ranges() {
+            if declared_in_front.contains(range.date_range.id())
+                && range.overlaps(start_date, end_date)
+            {
+                let mut date_range = range.date_range.clone();
+                date_range.set_start_date(Some(range.start_date));
+                carried.push(date_range);
+            }
+        }
+
+        carried.append(&mut segment.date_ranges);
+        segment.date_ranges = carried;
+
+        Ok(())
+    }
+
+    /// Concatenates multiple playlists into one playlist.
+    ///
+    /// Every playlist is provided together with its own URI, which is used to
+    /// resolve the relative URIs of its segments, [`ExtXMap`]s and
+    /// [`ExtXKey`]s, so that the combined playlist can be served from the URI
+    /// of the first playlist (an empty URI keeps the URIs unchanged).
+    ///
+    /// The combined playlist
+    ///
+    /// - marks the first segment of every following playlist with
+    ///   [`MediaSegment::has_discontinuity`],
+    /// - adds an [`ExtXKey::empty`] to the first segment of a playlist, if it
+    ///   is unencrypted, but follows an encrypted segment (the keys of an
+    ///   encrypted playlist are written again, because the first segment
+    ///   of every playlist has its own keys),
+    /// - writes the [`ExtXMap`] of every playlist again,
+    /// - has the largest [`MediaPlaylist::target_duration`] and
+    ///   [`MediaPlaylist::allowable_excess_duration`] of all playlists,
+    /// - has the media sequence, the discontinuity sequence and the
+    ///   [`ExtXStart`] of the first playlist and
+    /// - only has an [`ExtXEndList`] or a [`PlaylistType`], if all playlists
+    ///   have them.
+    ///
+    /// The [`MediaPlaylist::unknown`] tags are not part of the combined
+    /// playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    ///
+    /// let ad = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:4\n",
+    ///     "#EXTINF:4,\n",
+    ///     "ad.ts\n",
+    ///     "#EXT-X-ENDLIST\n",
+    /// ))?;
+    ///
+    /// let movie = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "movie.ts\n",
+    ///     "#EXT-X-ENDLIST\n",
+    /// ))?;
+    ///
+    /// let playlist = MediaPlaylist::concat(vec![
+    ///     ("https://ads.example.com/pre/index.m3u8", &ad),
+    ///     ("https://example.com/movie/index.m3u8", &movie),
+    /// ])?;
+    ///
+    /// assert_eq!(
+    ///     playlist.to_string(),
+    ///     concat!(
+    ///         "#EXTM3U\n",
+    ///         "#EXT-X-TARGETDURATION:10\n",
+    ///         "#EXTINF:4,\n",
+    ///         "https://ads.example.com/pre/ad.ts\n",
+    ///         "#EXT-X-DISCONTINUITY\n",
+    ///         "#EXTINF:10,\n",
+    ///         "https://example.com/movie/movie.ts\n",
+    ///         "#EXT-X-ENDLIST\n"
+    ///     )
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if no playlist is provided, if some,
+    /// but not all playlists have an [`ExtXIFramesOnly`] tag, if a playlist
+    /// without an [`ExtXMap`] follows a playlist with an [`ExtXMap`] (a map
+    /// can not be removed) or if the combined playlist is invalid (for
+    /// example, because two [`ExtXDateRange`]s with the same id conflict).
+    ///
+    /// [`ExtXMap`]: crate::tags::ExtXMap
+    pub fn concat<'p, I>(playlists: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = (&'p str, &'p MediaPlaylist<'a>)>,
+        'a: 'p,
+    {
+        let mut playlists = playlists.into_iter().peekable();
+
+        let first = playlists
+            .peek()
+            .map(|(_, playlist)| *playlist)
+            .ok_or_else(|| Error::custom("there are no playlists to concatenate"))?;
+
+        let mut target_duration = first.target_duration;
+        let mut allowable_excess_duration = first.allowable_excess_duration;
+        let mut playlist_type = first.playlist_type;
+        let mut has_end_list = first.has_end_list;
+        let mut has_independent_segments = first.has_independent_segments;
+
+        let media_sequence = first.media_sequence;
+        let mut segments: Vec<MediaSegment<'a>> = vec![];
+        let mut has_map = false;
+        let mut is_encrypted = false;
+
+        for (base, playlist) in playlists {
+            if playlist.has_i_frames_only != first.has_i_frames_only {
+                return Err(Error::custom(
+                    "can not concatenate an I-frames only playlist with a regular playlist",
+                ));
+            }
+
+            target_duration = target_duration.max(playlist.target_duration);
+            allowable_excess_duration =
+                allowable_excess_duration.max(playlist.allowable_excess_duration);
+
+            if playlist_type != playlist.playlist_type {
+                playlist_type = None;
+            }
+
+            has_end_list &= playlist.has_end_list;
+            has_independent_segments &= playlist.has_independent_segments;
+
+            for (i, segment) in playlist.segments.values().enumerate() {
+                let mut segment = segment.clone();
+                let number = media_sequence + segments.len();
+
+                // the segments are numbered from the media sequence:
+                segment.explicit_number = false;
+                segment.set_uri(resolve_uri(base, segment.uri().clone()));
+
+                for key in &mut segment.keys {
+                    resolve_key(base, key, segment.number, number);
+                }
+
+                if let Some(map) = &mut segment.map {
+                    map.set_uri(resolve_uri(base, map.uri().clone()));
+
+                    for key in &mut map.keys {
+                        resolve_key(base, key, segment.number, number);
+                    }
+                }
+
+                if i == 0 {
+                    if !segments.is_empty() {
+                        segment.has_discontinuity = true;
+                    }
+
+                    if segment.map.is_none() && has_map {
+                        return Err(Error::custom(format!(
+                            "the segment {} has no map, but follows a segment with a map",
+                            segment.uri()
+                        )));
+                    }
+
+                    if segment.keys.is_empty() && is_encrypted {
+                        segment.keys = vec![ExtXKey::empty()];
+                    }
+                }
+
+                has_map |= segment.map.is_some();
+
+                if !segment.keys.is_empty() {
+                    is_encrypted = segment.keys.iter().any(ExtXKey::is_some);
+                }
+
+                segments.push(segment);
+            }
+        }
+
+        let mut builder = Self::builder();
+
+        builder
+            .target_duration(target_duration)
+            .media_sequence(media_sequence)
+            .discontinuity_sequence(first.discontinuity_sequence)
+            .has_i_frames_only(first.has_i_frames_only)
+            .has_independent_segments(has_independent_segments)
+            .has_end_list(has_end_list)
+            .allowable_excess_duration(allowable_excess_duration)
+            .segments(segments);
+
+        if let Some(value) = playlist_type {
+            builder.playlist_type(value);
+        }
+
+        if let Some(value) = first.start {
+            builder.start(value);
+        }
+
+        builder.build().map_err(Error::builder)
+    }
+
+    /// Returns the effective date ranges of the playlist, in the order of
+    /// their first appearance.
+    ///
+    /// All [`ExtXDateRange`]s with the same id are combined into one range
+    /// with [`ExtXDateRange::merge`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-PROGRAM-DATE-TIME:2014-03-05T11:15:00Z\n",
+    ///     "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2014-03-05T11:15:00Z\",PLANNED-DURATION=20\n",
+    ///     "#EXTINF:10,\n",
+    ///     "a.ts\n",
+    ///     "#EXTINF:10,\n",
+    ///     "b.ts\n",
+    ///     "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2014-03-05T11:15:00Z\",DURATION=20\n",
+    ///     "#EXTINF:10,\n",
+    ///     "c.ts\n",
+    /// ))?;
+    ///
+    /// let date_ranges = playlist.merged_date_ranges()?;
+    ///
+    /// assert_eq!(date_ranges.len(), 1);
+    /// assert_eq!(date_ranges[0].planned_duration, Some(Duration::from_secs(20)));
+    /// assert_eq!(date_ranges[0].duration, Some(Duration::from_secs(20)));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if two tags with the same id have
+    /// conflicting attributes (which can only happen, if the
+    /// [`MediaPlaylist::segments`] have been modified after building the
+    /// playlist).
+    pub fn merged_date_ranges(&self) -> crate::Result<Vec<ExtXDateRange<'a>>> {
+        merge_date_ranges(self.segments.values())
+    }
+
+    /// Returns `true`, if both playlists are semantically equal.
+    ///
+    /// Unlike [`PartialEq`], this ignores differences, that do not change the
+    /// meaning of the playlist, like the order of attributes, the formatting
+    /// of numbers (outside of quoted strings), the order of the
+    /// [`MediaPlaylist::unknown`] tags and the `EXT-X-VERSION` tag.
+    /// The [`MediaPlaylist::allowable_excess_duration`] is ignored as well.
+    #[must_use]
+    pub fn is_equivalent(&self, other: &MediaPlaylist<'_>) -> bool {
+        self.canonical_form() == other.canonical_form()
+    }
+
+    /// Returns a stable hash of the playlist, which is equal for all
+    /// playlists, that are semantically equal (see
+    /// [`MediaPlaylist::is_equivalent`]).
+    ///
+    /// The hash does not depend on the platform or the compiler, so it can be
+    /// stored (for example, to avoid republishing a playlist, that has not
+    /// changed). It might change with a new version of this crate (for
+    /// example, if a tag is written differently), so stored hashes should
+    /// only be compared with hashes of the same version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    ///
+    /// let a = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:9.5,\n",
+    ///     "a.ts\n",
+    /// ))?;
+    ///
+    /// let b = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:9.500,\n",
+    ///     "a.ts\n",
+    /// ))?;
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.is_equivalent(&b));
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 { canonical::fingerprint(&self.canonical_form()) }
+
+    fn canonical_form(&self) -> String {
+        let mut playlist = self.clone();
+        let unknown = mem::take(&mut playlist.unknown);
+
+        canonical::canonical_form(&playlist.to_string(), &unknown)
+    }
+
+    /// Returns the structural difference between this playlist and a newer
+    /// version of the playlist.
+    ///
+    /// See [`MediaPlaylistDiff`] for an example.
+    ///
+    /// [`MediaPlaylistDiff`]: crate::diff::MediaPlaylistDiff
+    #[must_use]
+    pub fn diff(&self, new: &MediaPlaylist<'_>) -> MediaPlaylistDiff {
+        MediaPlaylistDiff::new(self, new)
+    }
+
+    /// Compares a reloaded live playlist with the previous version of the
+    /// playlist and returns the segments, that have been added and removed.
+    ///
+    /// See [`ReloadOutcome`] for an example.
+    ///
+    /// # Errors
+    ///
+    /// This function returns a [`ReloadError`], if the playlist is not a valid
+    /// successor of the previous playlist (for example, because the media
+    /// sequence went back or because the discontinuity sequence does not
+    /// match the removed segments).
+    ///
+    /// [`ReloadOutcome`]: crate::live::ReloadOutcome
+    /// [`ReloadError`]: crate::live::ReloadError
+    pub fn reconcile<'p>(
+        &'p self,
+        previous: &'p Self,
+    ) -> Result<ReloadOutcome<'p, 'a>, ReloadError> {
+        ReloadOutcome::new(self, previous)
+    }
+
+    /// Returns a [`Timeline`] of the [`MediaPlaylist::segments`], which knows
+    /// the start offset, the discontinuity sequence and (with the `chrono`
+    /// feature) the wall clock time of every [`MediaSegment`].
+    ///
+    /// See [`Timeline`] for an example.
+    ///
+    /// [`Timeline`]: crate::timeline::Timeline
+    #[must_use]
+    pub fn timeline(&self) -> Timeline<'_, 'a> { Timeline::new(self) }
+
+    /// Returns an iterator over the continuous [`Period`]s of the playlist,
+    /// which are separated by [`MediaSegment::has_discontinuity`].
+    ///
+    /// See [`Periods`] for an example.
+    ///
+    /// [`Period`]: crate::timeline::Period
+    /// [`Periods`]: crate::timeline::Periods
+    #[must_use]
+    pub fn periods(&self) -> Periods<'_, 'a> { Periods::new(self) }
+
+    /// Maps all [`ExtXDateRange`]s onto the timeline of the playlist with the
+    /// help of the [`ExtXProgramDateTime`] tags, which allows to query the
+    /// ranges, that are active at a date, at an offset or during a
+    /// [`MediaSegment`].
+    ///
+    /// See [`DateRangeTimeline`] for an example.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the playlist contains date ranges,
+    /// but no [`ExtXProgramDateTime`] tag or if the date ranges can not be
+    /// merged (see [`MediaPlaylist::merged_date_ranges`]).
+    ///
+    /// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+    /// [`DateRangeTimeline`]: crate::timeline::DateRangeTimeline
+    #[cfg(feature = "chrono")]
+    pub fn date_range_timeline(&self) -> crate::Result<DateRangeTimeline<'a>> {
+        DateRangeTimeline::new(self)
+    }
+
+    /// Adds the [`ExtXProgramDateTime`] tags of a [`ProgramDateTimeGenerator`]
+    /// to the segments of the playlist.
+    ///
+    /// See [`ProgramDateTimeGenerator`] for an example.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if a generated date is out of range.
+    ///
+    /// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+    #[cfg(feature = "chrono")]
+    pub fn generate_program_date_times(
+        &mut self,
+        generator: ProgramDateTimeGenerator,
+    ) -> crate::Result<()> {
+        generator.apply(&mut self.segments)
+    }
+
+    /// Checks, that the [`ExtXProgramDateTime`] tags of the playlist do not go
+    /// backwards and that they match the durations of the segments in between
+    /// (with the provided tolerance). A tag after a discontinuity is only
+    /// checked to not go backwards.
+    ///
+    /// This check is not performed while parsing, because many playlists in
+    /// the wild contain slightly inaccurate dates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::MediaPlaylist;
+    /// use core::convert::TryFrom;
+    /// use hls_m3u8::timeline::ProgramDateTimeIssue;
+    /// use std::time::Duration;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+    ///     "#EXTINF:10,\n",
+    ///     "a.ts\n",
+    ///     "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:52Z\n",
+    ///     "#EXTINF:10,\n",
+    ///     "b.ts\n",
+    /// ))?;
+    ///
+    /// assert!(playlist
+    ///     .program_date_time_issues(Duration::from_secs(2))
+    ///     .is_empty());
+    ///
+    /// assert!(matches!(
+    ///     playlist.program_date_time_issues(Duration::from_secs(1))[..],
+    ///     [ProgramDateTimeIssue::Drift { number: 1, .. }]
+    /// ));
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn program_date_time_issues(&self, tolerance: Duration) -> Vec<ProgramDateTimeIssue> {
+        program_date_time_issues(self, tolerance)
     }
 
     /// Makes the struct independent of its lifetime, by taking ownership of all
@@ -442,6 +1297,23 @@ impl<'a> MediaPlaylist<'a> {
     }
 }
 
+/// Resolves the URI of a key and replaces an [`InitializationVector`], that
+/// has been derived from the segment number, if the number of the segment
+/// changes.
+fn resolve_key(base: &str, key: &mut ExtXKey<'_>, number: usize, new_number: usize) {
+    if let ExtXKey(Some(decryption_key)) = key {
+        decryption_key.uri = resolve_uri(base, decryption_key.uri.clone());
+
+        if let InitializationVector::Number(_) = decryption_key.iv {
+            if number != new_number {
+                if let Some(iv) = decryption_key.iv.to_slice() {
+                    decryption_key.iv = InitializationVector::Aes128(iv);
+                }
+            }
+        }
+    }
+}
+
 impl<'a> RequiredVersion for MediaPlaylist<'a> {
     fn required_version(&self) -> ProtocolVersion {
         required_version![
@@ -565,6 +1437,24 @@ impl<'a> fmt::Display for MediaPlaylist<'a> {
     }
 }
 
+fn merge_date_ranges<'a, 'b: 'a>(
+    segments: impl Iterator<Item = &'a MediaSegment<'b>>,
+) -> crate::Result<Vec<ExtXDateRange<'b>>> {
+    let mut result: Vec<ExtXDateRange<'b>> = vec![];
+    let mut indices: HashMap<_, usize> = HashMap::new();
+
+    for date_range in segments.flat_map(|s| s.date_ranges.iter()) {
+        if let Some(&index) = indices.get(date_range.id()) {
+            result[index].merge(date_range)?;
+        } else {
+            indices.insert(date_range.id().clone(), result.len());
+            result.push(date_range.clone());
+        }
+    }
+
+    Ok(result)
+}
+
 fn parse_media_playlist<'a>(
     input: &'a str,
     builder: &mut MediaPlaylistBuilder<'a>,
@@ -650,7 +1540,7 @@ fn parse_media_playlist<'a>(
                     }
                     Tag::ExtXDateRange(t) => {
                         has_partial_segment = true;
-                        segment.date_range(t);
+                        segment.push_date_range(t);
                     }
                     Tag::ExtXTargetDuration(t) => {
                         builder.target_duration(t.0);
@@ -659,7 +1549,7 @@ fn parse_media_playlist<'a>(
                         builder.media_sequence(t.0);
                     }
                     Tag::ExtXDiscontinuitySequence(t) => {
-                        if segments.is_empty() {
+                        if !segments.is_empty() {
                             return Err(Error::invalid_input());
                         }
 
@@ -859,6 +1749,533 @@ mod tests {
         assert_eq!(segments.next(), None);
     }
 
+    #[test]
+    fn test_segment_by_number() {
+        let segment = |number: Option<usize>| {
+            MediaSegment::builder()
+                .duration(Duration::from_secs(1))
+                .number(number)
+                .uri("a.ts")
+                .build()
+                .unwrap()
+        };
+
+        let mut playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(1))
+            .media_sequence(10)
+            .segments(vec![segment(None), segment(None), segment(None), segment(None)])
+            .build()
+            .unwrap();
+
+        assert_eq!(playlist.segment_by_number(9), None);
+        assert_eq!(playlist.segment_by_number(11).map(|s| s.number), Some(11));
+        assert_eq!(playlist.segment_by_number(14), None);
+        assert_eq!(playlist.last_number(), Some(13));
+
+        // holes:
+        playlist.segments.remove(1);
+        playlist.segments.remove(3);
+
+        assert_eq!(playlist.segment_by_number(11), None);
+        assert_eq!(playlist.segment_by_number(12).map(|s| s.number), Some(12));
+        assert_eq!(playlist.last_number(), Some(12));
+        assert_eq!(
+            playlist
+                .range_by_number(..)
+                .map(|s| s.number)
+                .collect::<Vec<_>>(),
+            vec![10, 12]
+        );
+        assert_eq!(
+            playlist
+                .range_by_number(11..=12)
+                .map(|s| s.number)
+                .collect::<Vec<_>>(),
+            vec![12]
+        );
+
+        // explicit numbers:
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(1))
+            .segments(vec![segment(Some(1)), segment(None), segment(Some(0))])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist
+                .range_by_number(0..3)
+                .map(|s| s.number)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(playlist.segment_by_number(2).map(|s| s.number), Some(2));
+        assert_eq!(playlist.last_number(), Some(2));
+    }
+
+    #[test]
+    fn test_clip() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:20\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://example.com/key\"\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+            "#EXTINF:10,\n",
+            "d.ts\n",
+        ))
+        .unwrap();
+
+        let clip = playlist
+            .clip(Duration::from_millis(20500)..Duration::from_secs(30))
+            .unwrap();
+
+        assert_eq!(clip.media_sequence, 22);
+        assert_eq!(clip.discontinuity_sequence, 1);
+        assert_eq!(clip.segments.num_elements(), 1);
+        assert!(!clip.segments[0].has_discontinuity);
+        assert_eq!(clip.segments[0].keys, playlist.segments[2].keys);
+        assert!(clip.has_end_list);
+        assert_eq!(
+            clip.start,
+            Some(ExtXStart::with_precise(Float::new(0.5), true))
+        );
+
+        let clip = playlist.clip(..Duration::from_secs(10)).unwrap();
+
+        assert_eq!(clip.media_sequence, 20);
+        assert_eq!(clip.segments[0].uri(), "a.ts");
+        assert_eq!(clip.segments.num_elements(), 1);
+
+        assert_eq!(
+            playlist.clip(Duration::from_secs(15)..).unwrap().duration(),
+            Duration::from_secs(30)
+        );
+        assert!(playlist.clip(Duration::from_secs(40)..).is_err());
+    }
+
+    #[test]
+    fn test_is_equivalent() {
+        let a = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://example.com/key\",IV=0X10EF8F758CA555115584BB5B3C687F52\n",
+            "#EXTINF:10.0,\n",
+            "a.ts\n",
+            "#EXT-X-CUSTOM-A:VALUE=1\n",
+            "#EXT-X-CUSTOM-B\n",
+        ))
+        .unwrap();
+
+        let mut b = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-KEY:IV=0x10ef8f758ca555115584bb5b3c687f52,URI=\"https://example.com/key\",METHOD=AES-128\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXT-X-CUSTOM-B\n",
+            "#EXT-X-CUSTOM-A:VALUE=1.0\n",
+        ))
+        .unwrap();
+
+        b.allowable_excess_duration = Duration::from_secs(1);
+
+        assert_ne!(a, b);
+        assert!(a.is_equivalent(&b));
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        // quoted strings are not normalized:
+        b.unknown[1] = "#EXT-X-CUSTOM-A:VALUE=\"1\"".into();
+
+        assert!(!a.is_equivalent(&b));
+
+        // a quoted comma does not separate attributes:
+        let date_range = |value: &str| {
+            MediaPlaylist::try_from(format!(
+                concat!(
+                    "#EXTM3U\n",
+                    "#EXT-X-TARGETDURATION:10\n",
+                    "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:44Z\n",
+                    "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2020-01-02T21:55:44Z\",{}\n",
+                    "#EXTINF:10,\n",
+                    "a.ts\n",
+                ),
+                value
+            )
+            .as_str())
+            .unwrap()
+            .into_owned()
+        };
+
+        let quoted = date_range("X-A=\"1,X-B=2\"");
+        let unquoted = date_range("X-A=1,X-B=2");
+
+        assert!(!quoted.is_equivalent(&unquoted));
+        assert_ne!(quoted.fingerprint(), unquoted.fingerprint());
+
+        b.unknown[1] = "#EXT-X-CUSTOM-A:VALUE=1".into();
+        b.segments[0].set_uri("b.ts");
+
+        assert!(!a.is_equivalent(&b));
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn test_concat() {
+        let encrypted = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:6\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"../key\"\n",
+            "#EXTINF:6,\n",
+            "a.m4s\n",
+            "#EXTINF:6,\n",
+            "b.m4s\n",
+            "#EXT-X-ENDLIST\n",
+        ))
+        .unwrap();
+
+        let clear = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:10,\n",
+            "c.m4s\n",
+        ))
+        .unwrap();
+
+        let playlist = MediaPlaylist::concat(vec![
+            ("https://example.com/a/index.m3u8", &encrypted),
+            ("https://example.com/b/index.m3u8", &clear),
+            ("", &encrypted),
+        ])
+        .unwrap();
+
+        assert_eq!(playlist.target_duration, Duration::from_secs(10));
+        assert_eq!(playlist.media_sequence, 5);
+        assert!(!playlist.has_end_list);
+        assert_eq!(playlist.duration(), Duration::from_secs(34));
+
+        assert_eq!(
+            playlist.to_string(),
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:6\n",
+                "#EXT-X-TARGETDURATION:10\n",
+                "#EXT-X-MEDIA-SEQUENCE:5\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"https://example.com/key\"\n",
+                "#EXT-X-MAP:URI=\"https://example.com/a/init.mp4\"\n",
+                "#EXTINF:6,\n",
+                "https://example.com/a/a.m4s\n",
+                "#EXTINF:6,\n",
+                "https://example.com/a/b.m4s\n",
+                "#EXT-X-KEY:METHOD=NONE\n",
+                "#EXT-X-MAP:URI=\"https://example.com/b/init.mp4\"\n",
+                "#EXT-X-DISCONTINUITY\n",
+                "#EXTINF:10,\n",
+                "https://example.com/b/c.m4s\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"../key\",",
+                "IV=0x00000000000000000000000000000005\n",
+                "#EXT-X-MAP:URI=\"init.mp4\"\n",
+                "#EXT-X-DISCONTINUITY\n",
+                "#EXTINF:6,\n",
+                "a.m4s\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"../key\",",
+                "IV=0x00000000000000000000000000000006\n",
+                "#EXTINF:6,\n",
+                "b.m4s\n",
+            )
+        );
+
+        // the segments of the first playlist keep their number as iv:
+        assert_eq!(
+            playlist.segments[0].keys[0].as_ref().map(|key| key.iv),
+            Some(InitializationVector::Number(5))
+        );
+
+        // a map can not be removed:
+        let without_map = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "d.ts\n",
+        ))
+        .unwrap();
+
+        assert!(MediaPlaylist::concat(vec![("", &clear), ("", &without_map)]).is_err());
+        assert!(MediaPlaylist::concat(vec![("", &without_map), ("", &clear)]).is_ok());
+
+        let mut i_frames = without_map.clone();
+        i_frames.has_i_frames_only = true;
+
+        assert!(MediaPlaylist::concat(vec![("", &without_map), ("", &i_frames)]).is_err());
+        assert!(MediaPlaylist::concat(vec![]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_clip_date_ranges() {
+        use chrono::DateTime;
+
+        let date = |value| DateTime::parse_from_rfc3339(value).unwrap();
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+            "#EXT-X-DATERANGE:ID=\"ended\",START-DATE=\"2020-01-02T21:55:40Z\",DURATION=5\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2020-01-02T21:55:45Z\",DURATION=20\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXT-X-DATERANGE:ID=\"later\",START-DATE=\"2020-01-02T21:56:00Z\"\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+        ))
+        .unwrap();
+
+        let clip = playlist
+            .clip_by_date(date("2020-01-02T21:55:52Z")..date("2020-01-02T21:56:30Z"))
+            .unwrap();
+
+        assert_eq!(clip.media_sequence, 1);
+        assert_eq!(
+            clip.segments[0].program_date_time,
+            Some(ExtXProgramDateTime::new(date("2020-01-02T21:55:50Z")))
+        );
+        assert_eq!(
+            clip.segments
+                .values()
+                .map(|s| s
+                    .date_ranges
+                    .iter()
+                    .map(|date_range| date_range.id().as_ref())
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec!["ad"], vec!["later"]]
+        );
+        assert_eq!(
+            clip.start,
+            Some(ExtXStart::with_precise(Float::new(2.0), true))
+        );
+
+        assert!(playlist
+            .clip_by_date(date("2020-01-02T21:55:00Z")..date("2020-01-02T21:56:00Z"))
+            .is_err());
+        assert!(playlist
+            .clip_by_date(date("2020-01-02T21:55:40Z")..date("2020-01-02T21:57:00Z"))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_clip_program_date_time() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXT-X-DATERANGE:ID=\"later\",START-DATE=\"2020-01-02T21:56:00Z\"\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+        ))
+        .unwrap();
+
+        let clip = playlist
+            .clip(Duration::from_secs(20)..Duration::from_secs(30))
+            .unwrap();
+
+        #[cfg(feature = "chrono")]
+        {
+            use chrono::DateTime;
+
+            assert_eq!(
+                clip.segments[0].program_date_time,
+                Some(ExtXProgramDateTime::new(
+                    DateTime::parse_from_rfc3339("2020-01-02T21:56:00Z").unwrap()
+                ))
+            );
+            assert_eq!(clip.segments[0].date_ranges.len(), 1);
+        }
+        #[cfg(not(feature = "chrono"))]
+        {
+            // the date can not be calculated, so the date ranges are removed:
+            assert_eq!(clip.segments[0].program_date_time, None);
+            assert!(clip.segments[0].date_ranges.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_exact_duration() {
+        let mut input = "#EXTM3U\n#EXT-X-TARGETDURATION:7\n".to_string();
+
+        for i in 0..4000 {
+            input.push_str(&format!("#EXTINF:6.006,\n{}.ts\n", i));
+        }
+
+        let playlist = MediaPlaylist::try_from(input.as_str()).unwrap();
+
+        assert_eq!(playlist.duration(), Duration::from_secs(24024));
+        assert_eq!(
+            playlist.timeline().start_of(3999),
+            Some(Duration::from_millis(3999 * 6006))
+        );
+        assert!(playlist.to_string().contains("#EXTINF:6.006,\n"));
+    }
+
+    #[test]
+    fn test_exact_duration_overflow() {
+        // the sum of both durations does not fit with 19 decimal places:
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:1.0000000000000000001,\n",
+            "a.ts\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.duration(), Duration::from_secs(21));
+        assert_eq!(
+            playlist.timeline().start_of(2),
+            Some(Duration::from_secs(11))
+        );
+        assert_eq!(
+            playlist.periods().next().unwrap().duration,
+            Duration::from_secs(21)
+        );
+    }
+
+    #[test]
+    fn test_discontinuity_sequence() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:2\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(playlist.discontinuity_sequence, 2);
+        assert!(!playlist.segments[0].has_discontinuity);
+
+        // the tag must appear before the first segment:
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:2\n",
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_multiple_date_ranges() {
+        let playlist = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+            "#EXT-X-DATERANGE:ID=\"ad-break\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+            "#EXT-X-DATERANGE:ID=\"chapter-2\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/first.ts\n",
+        );
+
+        let media_playlist = MediaPlaylist::try_from(playlist).unwrap();
+        let ids = media_playlist.segments[0]
+            .date_ranges
+            .iter()
+            .map(|date_range| date_range.id().as_ref())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec!["ad-break", "chapter-2"]);
+    }
+
+    #[test]
+    fn test_date_range_validation() {
+        // a date range requires an `EXT-X-PROGRAM-DATE-TIME` tag
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/first.ts\n",
+        ))
+        .is_err());
+
+        // the attributes of tags with the same id must be consistent
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/first.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2010-02-19T14:54:33.031+08:00\"\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/second.ts\n",
+        ))
+        .is_err());
+
+        // END-ON-NEXT must not be combined with a DURATION of an update
+        assert!(MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",CLASS=\"ad\",START-DATE=\"2010-02-19T14:54:23.031+08:00\",END-ON-NEXT=YES\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/first.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",DURATION=10\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/second.ts\n",
+        ))
+        .is_err());
+
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2010-02-19T14:54:23.031+08:00\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2010-02-19T14:54:23.031+08:00\",PLANNED-DURATION=10\n",
+            "#EXT-X-DATERANGE:ID=\"chapter\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/first.ts\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",DURATION=10\n",
+            "#EXTINF:10,\n",
+            "http://media.example.com/second.ts\n",
+        ))
+        .unwrap();
+
+        let date_ranges = playlist.merged_date_ranges().unwrap();
+
+        assert_eq!(
+            date_ranges
+                .iter()
+                .map(|date_range| date_range.id().as_ref())
+                .collect::<Vec<_>>(),
+            vec!["ad", "chapter"]
+        );
+        assert_eq!(date_ranges[0].duration, Some(Duration::from_secs(10)));
+        assert_eq!(
+            date_ranges[0].planned_duration,
+            Some(Duration::from_secs(10))
+        );
+    }
+
     #[test]
     fn test_empty_playlist() {
         let playlist = "";
@@ -104,14 +104,18 @@ pub struct MediaSegment<'a> {
     /// This field is optional.
     #[builder(default, setter(into))]
     pub byte_range: Option<ExtXByteRange>,
-    /// This field associates a date-range (i.e., a range of time defined by a
+    /// This field associates date-ranges (i.e., a range of time defined by a
     /// starting and ending date) with a set of attribute/value pairs.
     ///
+    /// A segment might be preceded by multiple [`ExtXDateRange`] tags (for
+    /// example an ad break and a chapter marker), which are kept in the order
+    /// in which they appear in the playlist.
+    ///
     /// ## Note
     ///
     /// This field is optional.
-    #[builder(default)]
-    pub date_range: Option<ExtXDateRange<'a>>,
+    #[builder(default, setter(into))]
+    pub date_ranges: Vec<ExtXDateRange<'a>>,
     /// This field indicates a discontinuity between the `MediaSegment` that
     /// follows it and the one that preceded it.
     ///
@@ -190,7 +194,7 @@ impl<'a> MediaSegment<'a> {
             keys: self.keys.into_iter().map(|k| k.into_owned()).collect(),
             map: self.map.map(|v| v.into_owned()),
             byte_range: self.byte_range,
-            date_range: self.date_range.map(|v| v.into_owned()),
+            date_ranges: self.date_ranges.into_iter().map(|v| v.into_owned()).collect(),
             has_discontinuity: self.has_discontinuity,
             program_date_time: self.program_date_time.map(|v| v.into_owned()),
             duration: self.duration.into_owned(),
@@ -211,6 +215,17 @@ impl<'a> MediaSegmentBuilder<'a> {
         self
     }
 
+    /// Pushes an [`ExtXDateRange`] tag.
+    pub fn push_date_range<VALUE: Into<ExtXDateRange<'a>>>(&mut self, value: VALUE) -> &mut Self {
+        if let Some(date_ranges) = &mut self.date_ranges {
+            date_ranges.push(value.into());
+        } else {
+            self.date_ranges = Some(vec![value.into()]);
+        }
+
+        self
+    }
+
     /// The number of a [`MediaSegment`]. Normally this should not be set
     /// explicitly, because the [`MediaPlaylist::builder`] will automatically
     /// apply the correct number.
@@ -236,7 +251,7 @@ impl<'a> fmt::Display for MediaSegment<'a> {
             writeln!(f, "{}", value)?;
         }
 
-        if let Some(value) = &self.date_range {
+        for value in &self.date_ranges {
             writeln!(f, "{}", value)?;
         }
 
@@ -260,7 +275,7 @@ impl<'a> RequiredVersion for MediaSegment<'a> {
             self.keys,
             self.map,
             self.byte_range,
-            self.date_range,
+            self.date_ranges,
             {
                 if self.has_discontinuity {
                     Some(ExtXDiscontinuity)
@@ -284,6 +299,7 @@ impl<'a> Decryptable<'a> for MediaSegment<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::convert::TryFrom;
     use pretty_assertions::assert_eq;
     use std::time::Duration;
 
@@ -309,4 +325,41 @@ mod tests {
             .to_string()
         );
     }
+
+    #[test]
+    fn test_date_ranges() {
+        let segment = MediaSegment::builder()
+            .push_date_range(
+                ExtXDateRange::try_from(concat!(
+                    "#EXT-X-DATERANGE:ID=\"ad-break\",",
+                    "START-DATE=\"2010-02-19T14:54:23.031+08:00\""
+                ))
+                .unwrap(),
+            )
+            .push_date_range(
+                ExtXDateRange::try_from(concat!(
+                    "#EXT-X-DATERANGE:ID=\"chapter-2\",CLASS=\"com.example.chapter\",",
+                    "START-DATE=\"2010-02-19T14:54:23.031+08:00\""
+                ))
+                .unwrap(),
+            )
+            .duration(ExtInf::new(Duration::from_secs(4)))
+            .uri("http://www.uri.com/")
+            .build()
+            .unwrap();
+
+        assert_eq!(segment.date_ranges.len(), 2);
+        assert_eq!(segment.required_version(), ProtocolVersion::V1);
+        assert_eq!(
+            segment.to_string(),
+            concat!(
+                "#EXT-X-DATERANGE:ID=\"ad-break\",START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+                "#EXT-X-DATERANGE:ID=\"chapter-2\",CLASS=\"com.example.chapter\",",
+                "START-DATE=\"2010-02-19T14:54:23.031+08:00\"\n",
+                "#EXTINF:4,\n",
+                "http://www.uri.com/\n"
+            )
+            .to_string()
+        );
+    }
 }
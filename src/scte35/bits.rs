@@ -0,0 +1,184 @@
+use crate::Error;
+
+/// Reads big-endian values with an arbitrary bit width from a byte slice.
+#[derive(Debug, Clone)]
+pub(crate) struct BitReader<'a> {
+    data: &'a [u8],
+    /// The position in bits.
+    position: usize,
+}
+
+#[allow(clippy::cast_possible_truncation)]
+impl<'a> BitReader<'a> {
+    pub(crate) const fn new(data: &'a [u8]) -> Self { Self { data, position: 0 } }
+
+    pub(crate) const fn remaining_bits(&self) -> usize { self.data.len() * 8 - self.position }
+
+    pub(crate) const fn remaining_bytes(&self) -> usize { self.remaining_bits() / 8 }
+
+    pub(crate) fn read(&mut self, bits: usize) -> crate::Result<u64> {
+        debug_assert!(bits <= 64);
+
+        if bits > self.remaining_bits() {
+            return Err(Error::scte35("unexpected end of input"));
+        }
+
+        let mut result = 0_u64;
+
+        for _ in 0..bits {
+            let bit = (self.data[self.position / 8] >> (7 - self.position % 8)) & 1;
+            result = (result << 1) | u64::from(bit);
+            self.position += 1;
+        }
+
+        Ok(result)
+    }
+
+    pub(crate) fn read_bool(&mut self) -> crate::Result<bool> { Ok(self.read(1)? == 1) }
+
+    pub(crate) fn read_u8(&mut self) -> crate::Result<u8> { Ok(self.read(8)? as u8) }
+
+    pub(crate) fn read_u16(&mut self, bits: usize) -> crate::Result<u16> {
+        debug_assert!(bits <= 16);
+        Ok(self.read(bits)? as u16)
+    }
+
+    pub(crate) fn read_u32(&mut self) -> crate::Result<u32> { Ok(self.read(32)? as u32) }
+
+    pub(crate) fn skip(&mut self, bits: usize) -> crate::Result<()> {
+        self.read(bits)?;
+        Ok(())
+    }
+
+    pub(crate) fn read_bytes(&mut self, length: usize) -> crate::Result<&'a [u8]> {
+        if self.position % 8 != 0 {
+            return Err(Error::scte35("unaligned read"));
+        }
+
+        if length > self.remaining_bytes() {
+            return Err(Error::scte35("unexpected end of input"));
+        }
+
+        let start = self.position / 8;
+        self.position += length * 8;
+
+        Ok(&self.data[start..start + length])
+    }
+}
+
+/// Writes big-endian values with an arbitrary bit width into a buffer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BitWriter {
+    data: Vec<u8>,
+    /// The position in bits.
+    position: usize,
+}
+
+impl BitWriter {
+    pub(crate) fn new() -> Self { Self::default() }
+
+    /// Writes the lowest `bits` bits of the value.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the value does not fit into `bits`
+    /// bits (for example a 33 bit PTS or a 40 bit duration).
+    pub(crate) fn write(&mut self, value: u64, bits: usize) -> crate::Result<()> {
+        debug_assert!(bits <= 64);
+
+        if bits < 64 && value >> bits != 0 {
+            return Err(Error::scte35(format!(
+                "the value {} does not fit into {} bits",
+                value, bits
+            )));
+        }
+
+        self.write_unchecked(value, bits);
+
+        Ok(())
+    }
+
+    fn write_unchecked(&mut self, value: u64, bits: usize) {
+        for i in (0..bits).rev() {
+            if self.position % 8 == 0 {
+                self.data.push(0);
+            }
+
+            if (value >> i) & 1 == 1 {
+                if let Some(last) = self.data.last_mut() {
+                    *last |= 1 << (7 - self.position % 8);
+                }
+            }
+
+            self.position += 1;
+        }
+    }
+
+    pub(crate) fn write_bool(&mut self, value: bool) { self.write_unchecked(u64::from(value), 1); }
+
+    /// Reserved bits are always set to `1`.
+    pub(crate) fn reserved(&mut self, bits: usize) { self.write_unchecked(u64::max_value(), bits); }
+
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        debug_assert!(self.position % 8 == 0);
+
+        self.data.extend_from_slice(bytes);
+        self.position += bytes.len() * 8;
+    }
+
+    pub(crate) fn into_inner(self) -> Vec<u8> { self.data }
+}
+
+/// Calculates the `CRC-32/MPEG-2` checksum, which is used by the
+/// `splice_info_section`.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFF_u32;
+
+    for byte in data {
+        crc ^= u32::from(*byte) << 24;
+
+        for _ in 0..8 {
+            if crc & 0x8000_0000 == 0 {
+                crc <<= 1;
+            } else {
+                crc = (crc << 1) ^ 0x04C1_1DB7;
+            }
+        }
+    }
+
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_read_write() {
+        let mut writer = BitWriter::new();
+        writer.write_bool(true);
+        writer.reserved(6);
+        writer.write(0x1_2345_6789, 33).unwrap();
+        writer.write_bytes(&[0xAB]);
+
+        let data = writer.into_inner();
+        assert_eq!(data, vec![0xFF, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+
+        let mut reader = BitReader::new(&data);
+        assert_eq!(reader.read_bool().unwrap(), true);
+        assert_eq!(reader.read(6).unwrap(), 0x3F);
+        assert_eq!(reader.read(33).unwrap(), 0x1_2345_6789);
+        assert_eq!(reader.read_bytes(1).unwrap(), &[0xAB]);
+        assert!(reader.read(1).is_err());
+
+        // values, that do not fit, are rejected:
+        assert!(BitWriter::new().write(1 << 33, 33).is_err());
+        assert!(BitWriter::new().write(u64::max_value(), 64).is_ok());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0x0376_E6E7);
+    }
+}
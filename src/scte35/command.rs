@@ -0,0 +1,487 @@
+use std::time::Duration;
+
+use crate::scte35::bits::{BitReader, BitWriter};
+use crate::Error;
+
+/// The number of ticks per second of the 90 kHz clock, which is used for all
+/// time values of a `splice_info_section`.
+pub const TICKS_PER_SECOND: u64 = 90_000;
+
+/// Converts ticks of the 90 kHz clock into a [`Duration`].
+#[must_use]
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_secs(ticks / TICKS_PER_SECOND)
+        + Duration::from_nanos((ticks % TICKS_PER_SECOND) * 1_000_000_000 / TICKS_PER_SECOND)
+}
+
+/// Converts a [`Duration`] into ticks of the 90 kHz clock.
+#[must_use]
+pub fn duration_to_ticks(duration: Duration) -> u64 {
+    duration.as_secs() * TICKS_PER_SECOND
+        + u64::from(duration.subsec_nanos()) * TICKS_PER_SECOND / 1_000_000_000
+}
+
+/// The `splice_time()` structure, which specifies the time of a splice event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SpliceTime {
+    /// The presentation time stamp in ticks of the 90 kHz clock or `None`, if
+    /// the `time_specified_flag` is not set.
+    pub pts_time: Option<u64>,
+}
+
+impl SpliceTime {
+    /// Makes a new [`SpliceTime`] with the provided `pts_time`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::scte35::SpliceTime;
+    /// let splice_time = SpliceTime::new(1_924_989_008);
+    /// ```
+    #[must_use]
+    pub const fn new(pts_time: u64) -> Self {
+        Self {
+            pts_time: Some(pts_time),
+        }
+    }
+
+    pub(crate) fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        if reader.read_bool()? {
+            reader.skip(6)?;
+
+            Ok(Self::new(reader.read(33)?))
+        } else {
+            reader.skip(7)?;
+
+            Ok(Self { pts_time: None })
+        }
+    }
+
+    pub(crate) fn write(&self, writer: &mut BitWriter) -> crate::Result<()> {
+        if let Some(pts_time) = self.pts_time {
+            writer.write_bool(true);
+            writer.reserved(6);
+            writer.write(pts_time, 33)?;
+        } else {
+            writer.write_bool(false);
+            writer.reserved(7);
+        }
+
+        Ok(())
+    }
+}
+
+/// The `break_duration()` structure, which specifies the duration of a
+/// commercial break.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BreakDuration {
+    /// Whether the splicer should return to the network at the end of the
+    /// break.
+    pub auto_return: bool,
+    /// The duration of the break in ticks of the 90 kHz clock.
+    pub ticks: u64,
+}
+
+impl BreakDuration {
+    /// Makes a new [`BreakDuration`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::scte35::BreakDuration;
+    /// use std::time::Duration;
+    ///
+    /// let break_duration = BreakDuration::new(true, Duration::from_secs(30));
+    ///
+    /// assert_eq!(break_duration.ticks, 2_700_000);
+    /// ```
+    #[must_use]
+    pub fn new(auto_return: bool, duration: Duration) -> Self {
+        Self {
+            auto_return,
+            ticks: duration_to_ticks(duration),
+        }
+    }
+
+    /// Returns the duration of the break.
+    #[must_use]
+    pub fn duration(&self) -> Duration { ticks_to_duration(self.ticks) }
+
+    fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        let auto_return = reader.read_bool()?;
+        reader.skip(6)?;
+
+        Ok(Self {
+            auto_return,
+            ticks: reader.read(33)?,
+        })
+    }
+
+    fn write(&self, writer: &mut BitWriter) -> crate::Result<()> {
+        writer.write_bool(self.auto_return);
+        writer.reserved(6);
+        writer.write(self.ticks, 33)
+    }
+}
+
+/// A component (elementary stream) of a [`SpliceInsert`], that is spliced
+/// independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpliceInsertComponent {
+    /// Identifies the elementary stream.
+    pub component_tag: u8,
+    /// The time of the splice event or `None` if
+    /// [`SpliceInsert::is_splice_immediate`] is `true`.
+    pub splice_time: Option<SpliceTime>,
+}
+
+/// The `splice_insert()` command, which signals a splice event (for example
+/// the start or the end of an ad break).
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SpliceInsert {
+    /// Identifies the splice event.
+    pub splice_event_id: u32,
+    /// Indicates, that a previously sent splice event with the same
+    /// [`SpliceInsert::splice_event_id`] has been cancelled
+    /// (`splice_event_cancel_indicator`).
+    ///
+    /// If this is `true`, all other fields are ignored.
+    pub is_cancelled: bool,
+    /// `true` for a splice out of the network (the start of a break) and
+    /// `false` for a splice back into the network (the end of a break).
+    pub is_out_of_network: bool,
+    /// Indicates, that all components of the program are spliced at the same
+    /// time (`program_splice_flag`). Otherwise
+    /// [`SpliceInsert::components`] specifies the components.
+    pub is_program_splice: bool,
+    /// Indicates, that the splice should happen at the next possible splice
+    /// point (`splice_immediate_flag`).
+    pub is_splice_immediate: bool,
+    /// The time of a program splice.
+    ///
+    /// ## Note
+    ///
+    /// This field is only used, if [`SpliceInsert::is_program_splice`] is
+    /// `true` and [`SpliceInsert::is_splice_immediate`] is `false`.
+    pub splice_time: Option<SpliceTime>,
+    /// The components of a component splice.
+    ///
+    /// ## Note
+    ///
+    /// This field is only used, if [`SpliceInsert::is_program_splice`] is
+    /// `false`.
+    pub components: Vec<SpliceInsertComponent>,
+    /// The duration of the break.
+    pub break_duration: Option<BreakDuration>,
+    /// Identifies the viewing event within the service.
+    pub unique_program_id: u16,
+    /// Identifies a specific avail within a program.
+    pub avail_num: u8,
+    /// The expected number of avails within the current viewing event.
+    pub avails_expected: u8,
+}
+
+impl SpliceInsert {
+    /// Makes a new [`SpliceInsert`], that splices the entire program
+    /// immediately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::scte35::SpliceInsert;
+    /// use hls_m3u8::scte35::{BreakDuration, SpliceTime};
+    /// use std::time::Duration;
+    ///
+    /// let mut splice_insert = SpliceInsert::new(0x4800_008F, true);
+    ///
+    /// splice_insert.is_splice_immediate = false;
+    /// splice_insert.splice_time = Some(SpliceTime::new(1_936_310_318));
+    /// splice_insert.break_duration = Some(BreakDuration::new(true, Duration::from_secs(30)));
+    /// ```
+    #[must_use]
+    pub fn new(splice_event_id: u32, is_out_of_network: bool) -> Self {
+        Self {
+            splice_event_id,
+            is_out_of_network,
+            is_program_splice: true,
+            is_splice_immediate: true,
+            ..Self::default()
+        }
+    }
+
+    fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        let mut result = Self {
+            splice_event_id: reader.read_u32()?,
+            is_cancelled: reader.read_bool()?,
+            ..Self::default()
+        };
+
+        reader.skip(7)?;
+
+        if result.is_cancelled {
+            return Ok(result);
+        }
+
+        result.is_out_of_network = reader.read_bool()?;
+        result.is_program_splice = reader.read_bool()?;
+        let has_duration = reader.read_bool()?;
+        result.is_splice_immediate = reader.read_bool()?;
+        reader.skip(4)?;
+
+        if result.is_program_splice && !result.is_splice_immediate {
+            result.splice_time = Some(SpliceTime::read(reader)?);
+        }
+
+        if !result.is_program_splice {
+            let component_count = reader.read_u8()?;
+
+            for _ in 0..component_count {
+                let component_tag = reader.read_u8()?;
+                let splice_time = {
+                    if result.is_splice_immediate {
+                        None
+                    } else {
+                        Some(SpliceTime::read(reader)?)
+                    }
+                };
+
+                result.components.push(SpliceInsertComponent {
+                    component_tag,
+                    splice_time,
+                });
+            }
+        }
+
+        if has_duration {
+            result.break_duration = Some(BreakDuration::read(reader)?);
+        }
+
+        result.unique_program_id = reader.read_u16(16)?;
+        result.avail_num = reader.read_u8()?;
+        result.avails_expected = reader.read_u8()?;
+
+        Ok(result)
+    }
+
+    fn write(&self, writer: &mut BitWriter) -> crate::Result<()> {
+        writer.write(u64::from(self.splice_event_id), 32)?;
+        writer.write_bool(self.is_cancelled);
+        writer.reserved(7);
+
+        if self.is_cancelled {
+            return Ok(());
+        }
+
+        writer.write_bool(self.is_out_of_network);
+        writer.write_bool(self.is_program_splice);
+        writer.write_bool(self.break_duration.is_some());
+        writer.write_bool(self.is_splice_immediate);
+        writer.reserved(4);
+
+        if self.is_program_splice && !self.is_splice_immediate {
+            self.splice_time.unwrap_or_default().write(writer)?;
+        }
+
+        if !self.is_program_splice {
+            if self.components.len() > usize::from(u8::max_value()) {
+                return Err(Error::scte35("too many components"));
+            }
+
+            writer.write(self.components.len() as u64, 8)?;
+
+            for component in &self.components {
+                writer.write(u64::from(component.component_tag), 8)?;
+
+                if !self.is_splice_immediate {
+                    component.splice_time.unwrap_or_default().write(writer)?;
+                }
+            }
+        }
+
+        if let Some(break_duration) = &self.break_duration {
+            break_duration.write(writer)?;
+        }
+
+        writer.write(u64::from(self.unique_program_id), 16)?;
+        writer.write(u64::from(self.avail_num), 8)?;
+        writer.write(u64::from(self.avails_expected), 8)?;
+
+        Ok(())
+    }
+}
+
+/// The command of a `splice_info_section`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpliceCommand {
+    /// The `splice_null()` command (`0x00`), which is used as a heartbeat or to
+    /// carry descriptors.
+    SpliceNull,
+    /// The `splice_insert()` command (`0x05`).
+    SpliceInsert(SpliceInsert),
+    /// The `time_signal()` command (`0x06`), whose meaning is defined by the
+    /// descriptors of the `splice_info_section`.
+    TimeSignal(SpliceTime),
+    /// The `bandwidth_reservation()` command (`0x07`).
+    BandwidthReservation,
+    /// The `private_command()` command (`0xFF`).
+    PrivateCommand {
+        /// Identifies the owner of the command.
+        identifier: u32,
+        /// The private data.
+        data: Vec<u8>,
+    },
+    /// Any other command (for example `splice_schedule()`), which is kept as
+    /// raw bytes.
+    Other {
+        /// The `splice_command_type`.
+        command_type: u8,
+        /// The raw command.
+        data: Vec<u8>,
+    },
+}
+
+impl SpliceCommand {
+    /// Returns the `splice_command_type`.
+    #[must_use]
+    pub const fn command_type(&self) -> u8 {
+        match self {
+            Self::SpliceNull => 0x00,
+            Self::SpliceInsert(_) => 0x05,
+            Self::TimeSignal(_) => 0x06,
+            Self::BandwidthReservation => 0x07,
+            Self::PrivateCommand { .. } => 0xFF,
+            Self::Other { command_type, .. } => *command_type,
+        }
+    }
+
+    /// Reads a command. Commands with an unknown structure consume the entire
+    /// `reader`.
+    pub(crate) fn read(command_type: u8, reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        Ok(match command_type {
+            0x00 => Self::SpliceNull,
+            0x05 => Self::SpliceInsert(SpliceInsert::read(reader)?),
+            0x06 => Self::TimeSignal(SpliceTime::read(reader)?),
+            0x07 => Self::BandwidthReservation,
+            0xFF => {
+                Self::PrivateCommand {
+                    identifier: reader.read_u32()?,
+                    data: reader.read_bytes(reader.remaining_bytes())?.to_vec(),
+                }
+            }
+            _ => {
+                Self::Other {
+                    command_type,
+                    data: reader.read_bytes(reader.remaining_bytes())?.to_vec(),
+                }
+            }
+        })
+    }
+
+    pub(crate) fn write(&self, writer: &mut BitWriter) -> crate::Result<()> {
+        match self {
+            Self::SpliceNull | Self::BandwidthReservation => {}
+            Self::SpliceInsert(splice_insert) => splice_insert.write(writer)?,
+            Self::TimeSignal(splice_time) => splice_time.write(writer)?,
+            Self::PrivateCommand { identifier, data } => {
+                writer.write(u64::from(*identifier), 32)?;
+                writer.write_bytes(data);
+            }
+            Self::Other { data, .. } => writer.write_bytes(data),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(ticks_to_duration(5_426_421), Duration::from_nanos(60_293_566_666));
+        assert_eq!(duration_to_ticks(Duration::from_secs(30)), 2_700_000);
+        assert_eq!(duration_to_ticks(Duration::from_millis(59_993)), 5_399_370);
+    }
+
+    #[test]
+    fn test_splice_insert() {
+        let data = [
+            0x48, 0x00, 0x00, 0x8F, 0x7F, 0xEF, 0xFE, 0x73, 0x69, 0xC0, 0x2E, 0xFE, 0x00, 0x52,
+            0xCC, 0xF5, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let command = SpliceCommand::read(0x05, &mut BitReader::new(&data)).unwrap();
+
+        assert_eq!(
+            command,
+            SpliceCommand::SpliceInsert(SpliceInsert {
+                splice_event_id: 0x4800_008F,
+                is_out_of_network: true,
+                is_program_splice: true,
+                is_splice_immediate: false,
+                splice_time: Some(SpliceTime::new(0x7369_C02E)),
+                break_duration: Some(BreakDuration {
+                    auto_return: true,
+                    ticks: 0x0052_CCF5,
+                }),
+                ..SpliceInsert::default()
+            })
+        );
+
+        let mut writer = BitWriter::new();
+        command.write(&mut writer).unwrap();
+        assert_eq!(writer.into_inner(), data.to_vec());
+    }
+
+    #[test]
+    fn test_component_splice() {
+        let mut splice_insert = SpliceInsert::new(1, false);
+        splice_insert.is_program_splice = false;
+        splice_insert.is_splice_immediate = false;
+        splice_insert.components = vec![
+            SpliceInsertComponent {
+                component_tag: 1,
+                splice_time: Some(SpliceTime::new(90_000)),
+            },
+            SpliceInsertComponent {
+                component_tag: 2,
+                splice_time: Some(SpliceTime { pts_time: None }),
+            },
+        ];
+
+        let command = SpliceCommand::SpliceInsert(splice_insert);
+
+        let mut writer = BitWriter::new();
+        command.write(&mut writer).unwrap();
+        let data = writer.into_inner();
+
+        assert_eq!(
+            SpliceCommand::read(0x05, &mut BitReader::new(&data)).unwrap(),
+            command
+        );
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let mut splice_insert = SpliceInsert::new(7, true);
+        splice_insert.is_cancelled = true;
+
+        let mut writer = BitWriter::new();
+        SpliceCommand::SpliceInsert(splice_insert).write(&mut writer).unwrap();
+        let data = writer.into_inner();
+
+        assert_eq!(data, vec![0, 0, 0, 7, 0xFF]);
+        assert_eq!(
+            SpliceCommand::read(0x05, &mut BitReader::new(&data)).unwrap(),
+            SpliceCommand::SpliceInsert(SpliceInsert {
+                splice_event_id: 7,
+                is_cancelled: true,
+                ..SpliceInsert::default()
+            })
+        );
+    }
+}
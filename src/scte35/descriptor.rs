@@ -0,0 +1,665 @@
+use core::convert::TryFrom;
+use std::time::Duration;
+
+use crate::scte35::bits::{BitReader, BitWriter};
+use crate::scte35::command::ticks_to_duration;
+use crate::scte35::upid::{self, Upid};
+use crate::Error;
+
+/// The identifier of all descriptors defined by SCTE 35 (`"CUEI"`).
+pub const CUEI: u32 = 0x4355_4549;
+
+macro_rules! segmentation_types {
+    ( $( $(#[$meta:meta])* $name:ident = $value:expr ),* $(,)* ) => {
+        /// The `segmentation_type_id` of a [`SegmentationDescriptor`].
+        #[non_exhaustive]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum SegmentationType {
+            $(
+                $(#[$meta])*
+                $name,
+            )*
+            /// Any other `segmentation_type_id`.
+            Other(u8),
+        }
+
+        impl From<u8> for SegmentationType {
+            fn from(value: u8) -> Self {
+                match value {
+                    $( $value => Self::$name, )*
+                    _ => Self::Other(value),
+                }
+            }
+        }
+
+        impl From<SegmentationType> for u8 {
+            fn from(value: SegmentationType) -> Self {
+                match value {
+                    $( SegmentationType::$name => $value, )*
+                    SegmentationType::Other(value) => value,
+                }
+            }
+        }
+    };
+}
+
+segmentation_types! {
+    /// `0x00`
+    NotIndicated = 0x00,
+    /// `0x01`
+    ContentIdentification = 0x01,
+    /// `0x10`
+    ProgramStart = 0x10,
+    /// `0x11`
+    ProgramEnd = 0x11,
+    /// `0x12`
+    ProgramEarlyTermination = 0x12,
+    /// `0x13`
+    ProgramBreakaway = 0x13,
+    /// `0x14`
+    ProgramResumption = 0x14,
+    /// `0x15`
+    ProgramRunoverPlanned = 0x15,
+    /// `0x16`
+    ProgramRunoverUnplanned = 0x16,
+    /// `0x17`
+    ProgramOverlapStart = 0x17,
+    /// `0x18`
+    ProgramBlackoutOverride = 0x18,
+    /// `0x19`
+    ProgramJoin = 0x19,
+    /// `0x20`
+    ChapterStart = 0x20,
+    /// `0x21`
+    ChapterEnd = 0x21,
+    /// `0x22`
+    BreakStart = 0x22,
+    /// `0x23`
+    BreakEnd = 0x23,
+    /// `0x24`
+    OpeningCreditStart = 0x24,
+    /// `0x25`
+    OpeningCreditEnd = 0x25,
+    /// `0x26`
+    ClosingCreditStart = 0x26,
+    /// `0x27`
+    ClosingCreditEnd = 0x27,
+    /// `0x30`
+    ProviderAdvertisementStart = 0x30,
+    /// `0x31`
+    ProviderAdvertisementEnd = 0x31,
+    /// `0x32`
+    DistributorAdvertisementStart = 0x32,
+    /// `0x33`
+    DistributorAdvertisementEnd = 0x33,
+    /// `0x34`
+    ProviderPlacementOpportunityStart = 0x34,
+    /// `0x35`
+    ProviderPlacementOpportunityEnd = 0x35,
+    /// `0x36`
+    DistributorPlacementOpportunityStart = 0x36,
+    /// `0x37`
+    DistributorPlacementOpportunityEnd = 0x37,
+    /// `0x38`
+    ProviderOverlayPlacementOpportunityStart = 0x38,
+    /// `0x39`
+    ProviderOverlayPlacementOpportunityEnd = 0x39,
+    /// `0x3A`
+    DistributorOverlayPlacementOpportunityStart = 0x3A,
+    /// `0x3B`
+    DistributorOverlayPlacementOpportunityEnd = 0x3B,
+    /// `0x3C`
+    ProviderPromoStart = 0x3C,
+    /// `0x3D`
+    ProviderPromoEnd = 0x3D,
+    /// `0x3E`
+    DistributorPromoStart = 0x3E,
+    /// `0x3F`
+    DistributorPromoEnd = 0x3F,
+    /// `0x40`
+    UnscheduledEventStart = 0x40,
+    /// `0x41`
+    UnscheduledEventEnd = 0x41,
+    /// `0x42`
+    AlternateContentOpportunityStart = 0x42,
+    /// `0x43`
+    AlternateContentOpportunityEnd = 0x43,
+    /// `0x44`
+    ProviderAdBlockStart = 0x44,
+    /// `0x45`
+    ProviderAdBlockEnd = 0x45,
+    /// `0x46`
+    DistributorAdBlockStart = 0x46,
+    /// `0x47`
+    DistributorAdBlockEnd = 0x47,
+    /// `0x50`
+    NetworkStart = 0x50,
+    /// `0x51`
+    NetworkEnd = 0x51,
+}
+
+impl SegmentationType {
+    /// Returns `true`, if the type marks the start of a segment (for example
+    /// the start of an ad break).
+    #[must_use]
+    pub const fn is_start(self) -> bool {
+        match self {
+            Self::ProgramStart
+            | Self::ProgramOverlapStart
+            | Self::ChapterStart
+            | Self::BreakStart
+            | Self::OpeningCreditStart
+            | Self::ClosingCreditStart
+            | Self::ProviderAdvertisementStart
+            | Self::DistributorAdvertisementStart
+            | Self::ProviderPlacementOpportunityStart
+            | Self::DistributorPlacementOpportunityStart
+            | Self::ProviderOverlayPlacementOpportunityStart
+            | Self::DistributorOverlayPlacementOpportunityStart
+            | Self::ProviderPromoStart
+            | Self::DistributorPromoStart
+            | Self::UnscheduledEventStart
+            | Self::AlternateContentOpportunityStart
+            | Self::ProviderAdBlockStart
+            | Self::DistributorAdBlockStart
+            | Self::NetworkStart => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true`, if the type marks the end of a segment (for example
+    /// the end of an ad break).
+    #[must_use]
+    pub const fn is_end(self) -> bool {
+        match self {
+            Self::ProgramEnd
+            | Self::ProgramEarlyTermination
+            | Self::ChapterEnd
+            | Self::BreakEnd
+            | Self::OpeningCreditEnd
+            | Self::ClosingCreditEnd
+            | Self::ProviderAdvertisementEnd
+            | Self::DistributorAdvertisementEnd
+            | Self::ProviderPlacementOpportunityEnd
+            | Self::DistributorPlacementOpportunityEnd
+            | Self::ProviderOverlayPlacementOpportunityEnd
+            | Self::DistributorOverlayPlacementOpportunityEnd
+            | Self::ProviderPromoEnd
+            | Self::DistributorPromoEnd
+            | Self::UnscheduledEventEnd
+            | Self::AlternateContentOpportunityEnd
+            | Self::ProviderAdBlockEnd
+            | Self::DistributorAdBlockEnd
+            | Self::NetworkEnd => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true`, if a [`SegmentationDescriptor`] with this type may
+    /// carry `sub_segment_num` and `sub_segments_expected`.
+    fn has_sub_segments(self) -> bool {
+        match u8::from(self) {
+            0x34 | 0x36 | 0x38 | 0x3A | 0x44 | 0x46 => true,
+            _ => false,
+        }
+    }
+}
+
+/// The delivery restrictions of a [`SegmentationDescriptor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeliveryRestrictions {
+    /// Whether the segment may be delivered over the internet.
+    pub web_delivery_allowed: bool,
+    /// Whether the segment is free of regional blackout restrictions.
+    pub no_regional_blackout: bool,
+    /// Whether the segment may be recorded.
+    pub archive_allowed: bool,
+    /// The device groups, that are restricted (2 bits).
+    pub device_restrictions: u8,
+}
+
+/// A component (elementary stream) of a [`SegmentationDescriptor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SegmentationComponent {
+    /// Identifies the elementary stream.
+    pub component_tag: u8,
+    /// The offset of the component in ticks of the 90 kHz clock.
+    pub pts_offset: u64,
+}
+
+/// The `segmentation_descriptor()`, which describes a segment of the content
+/// (for example a program, a chapter or an ad break).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SegmentationDescriptor {
+    /// Identifies the segmentation event.
+    pub segmentation_event_id: u32,
+    /// Indicates, that a previously sent segmentation event with the same
+    /// [`SegmentationDescriptor::segmentation_event_id`] has been cancelled.
+    ///
+    /// If this is `true`, all other fields are ignored.
+    pub is_cancelled: bool,
+    /// Indicates, that the segmentation applies to all components of the
+    /// program (`program_segmentation_flag`). Otherwise
+    /// [`SegmentationDescriptor::components`] specifies the components.
+    pub is_program_segmentation: bool,
+    /// The delivery restrictions or `None`, if the delivery is not restricted.
+    pub delivery_restrictions: Option<DeliveryRestrictions>,
+    /// The components of the segmentation.
+    ///
+    /// ## Note
+    ///
+    /// This field is only used, if
+    /// [`SegmentationDescriptor::is_program_segmentation`] is `false`.
+    pub components: Vec<SegmentationComponent>,
+    /// The duration of the segment in ticks of the 90 kHz clock.
+    pub segmentation_duration: Option<u64>,
+    /// Identifies the content of the segment.
+    pub upid: Upid,
+    /// The type of the segmentation.
+    pub segmentation_type: SegmentationType,
+    /// The number of this segment in a collection of segments.
+    pub segment_num: u8,
+    /// The expected number of segments in the collection.
+    pub segments_expected: u8,
+    /// The number of this sub-segment in a collection of sub-segments.
+    pub sub_segment_num: Option<u8>,
+    /// The expected number of sub-segments in the collection.
+    pub sub_segments_expected: Option<u8>,
+}
+
+impl SegmentationDescriptor {
+    /// Makes a new [`SegmentationDescriptor`] for the entire program without
+    /// delivery restrictions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::scte35::{SegmentationDescriptor, SegmentationType, Upid};
+    ///
+    /// let descriptor = SegmentationDescriptor::new(
+    ///     0x4800_008E,
+    ///     SegmentationType::ProviderPlacementOpportunityStart,
+    ///     Upid::AiringId(0x2CA0_A18A),
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn new(
+        segmentation_event_id: u32,
+        segmentation_type: SegmentationType,
+        upid: Upid,
+    ) -> Self {
+        Self {
+            segmentation_event_id,
+            is_cancelled: false,
+            is_program_segmentation: true,
+            delivery_restrictions: None,
+            components: Vec::new(),
+            segmentation_duration: None,
+            upid,
+            segmentation_type,
+            segment_num: 0,
+            segments_expected: 0,
+            sub_segment_num: None,
+            sub_segments_expected: None,
+        }
+    }
+
+    /// Returns the [`SegmentationDescriptor::segmentation_duration`] as a
+    /// [`Duration`].
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> { self.segmentation_duration.map(ticks_to_duration) }
+
+    fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        let segmentation_event_id = reader.read_u32()?;
+        let is_cancelled = reader.read_bool()?;
+        reader.skip(7)?;
+
+        let mut result = Self::new(
+            segmentation_event_id,
+            SegmentationType::NotIndicated,
+            Upid::NotUsed,
+        );
+
+        if is_cancelled {
+            result.is_cancelled = true;
+            return Ok(result);
+        }
+
+        result.is_program_segmentation = reader.read_bool()?;
+        let has_duration = reader.read_bool()?;
+        let is_delivery_not_restricted = reader.read_bool()?;
+
+        if is_delivery_not_restricted {
+            reader.skip(5)?;
+        } else {
+            result.delivery_restrictions = Some(DeliveryRestrictions {
+                web_delivery_allowed: reader.read_bool()?,
+                no_regional_blackout: reader.read_bool()?,
+                archive_allowed: reader.read_bool()?,
+                device_restrictions: u8::try_from(reader.read(2)?).unwrap_or_default(),
+            });
+        }
+
+        if !result.is_program_segmentation {
+            let component_count = reader.read_u8()?;
+
+            for _ in 0..component_count {
+                let component_tag = reader.read_u8()?;
+                reader.skip(7)?;
+
+                result.components.push(SegmentationComponent {
+                    component_tag,
+                    pts_offset: reader.read(33)?,
+                });
+            }
+        }
+
+        if has_duration {
+            result.segmentation_duration = Some(reader.read(40)?);
+        }
+
+        let upid_type = reader.read_u8()?;
+        let upid_length = reader.read_u8()?;
+        result.upid = Upid::read(upid_type, reader.read_bytes(usize::from(upid_length))?)?;
+
+        result.segmentation_type = SegmentationType::from(reader.read_u8()?);
+        result.segment_num = reader.read_u8()?;
+        result.segments_expected = reader.read_u8()?;
+
+        // some encoders omit the sub-segment fields
+        if result.segmentation_type.has_sub_segments() && reader.remaining_bytes() >= 2 {
+            result.sub_segment_num = Some(reader.read_u8()?);
+            result.sub_segments_expected = Some(reader.read_u8()?);
+        }
+
+        Ok(result)
+    }
+
+    fn write(&self, writer: &mut BitWriter) -> crate::Result<()> {
+        writer.write(u64::from(self.segmentation_event_id), 32)?;
+        writer.write_bool(self.is_cancelled);
+        writer.reserved(7);
+
+        if self.is_cancelled {
+            return Ok(());
+        }
+
+        writer.write_bool(self.is_program_segmentation);
+        writer.write_bool(self.segmentation_duration.is_some());
+        writer.write_bool(self.delivery_restrictions.is_none());
+
+        if let Some(restrictions) = &self.delivery_restrictions {
+            writer.write_bool(restrictions.web_delivery_allowed);
+            writer.write_bool(restrictions.no_regional_blackout);
+            writer.write_bool(restrictions.archive_allowed);
+            writer.write(u64::from(restrictions.device_restrictions), 2)?;
+        } else {
+            writer.reserved(5);
+        }
+
+        if !self.is_program_segmentation {
+            if self.components.len() > usize::from(u8::max_value()) {
+                return Err(Error::scte35("too many components"));
+            }
+
+            writer.write(self.components.len() as u64, 8)?;
+
+            for component in &self.components {
+                writer.write(u64::from(component.component_tag), 8)?;
+                writer.reserved(7);
+                writer.write(component.pts_offset, 33)?;
+            }
+        }
+
+        if let Some(duration) = self.segmentation_duration {
+            writer.write(duration, 40)?;
+        }
+
+        let upid = self.upid.to_bytes()?;
+        writer.write(u64::from(self.upid.upid_type()), 8)?;
+        writer.write(u64::from(upid::length(&upid)?), 8)?;
+        writer.write_bytes(&upid);
+
+        writer.write(u64::from(u8::from(self.segmentation_type)), 8)?;
+        writer.write(u64::from(self.segment_num), 8)?;
+        writer.write(u64::from(self.segments_expected), 8)?;
+
+        if let (Some(num), Some(expected)) = (self.sub_segment_num, self.sub_segments_expected) {
+            writer.write(u64::from(num), 8)?;
+            writer.write(u64::from(expected), 8)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A descriptor of a `splice_info_section`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpliceDescriptor {
+    /// The `avail_descriptor()` (`0x00`).
+    Avail {
+        /// Identifies the avail (for example for ad-insertion systems).
+        provider_avail_id: u32,
+    },
+    /// The `DTMF_descriptor()` (`0x01`).
+    Dtmf {
+        /// The time in tenths of seconds between the splice event and the
+        /// DTMF sequence.
+        preroll: u8,
+        /// The DTMF characters.
+        dtmf_chars: String,
+    },
+    /// The `segmentation_descriptor()` (`0x02`).
+    Segmentation(SegmentationDescriptor),
+    /// Any other descriptor, which is kept as raw bytes.
+    Other {
+        /// The `splice_descriptor_tag`.
+        tag: u8,
+        /// Identifies the owner of the descriptor.
+        identifier: u32,
+        /// The data following the identifier.
+        data: Vec<u8>,
+    },
+}
+
+impl SpliceDescriptor {
+    /// Returns the `splice_descriptor_tag`.
+    #[must_use]
+    pub const fn tag(&self) -> u8 {
+        match self {
+            Self::Avail { .. } => 0x00,
+            Self::Dtmf { .. } => 0x01,
+            Self::Segmentation(_) => 0x02,
+            Self::Other { tag, .. } => *tag,
+        }
+    }
+
+    /// Reads a single descriptor, including its tag and length.
+    pub(crate) fn read(reader: &mut BitReader<'_>) -> crate::Result<Self> {
+        let tag = reader.read_u8()?;
+        let length = reader.read_u8()?;
+        let mut reader = BitReader::new(reader.read_bytes(usize::from(length))?);
+
+        let identifier = reader.read_u32()?;
+
+        if identifier != CUEI {
+            return Ok(Self::Other {
+                tag,
+                identifier,
+                data: reader.read_bytes(reader.remaining_bytes())?.to_vec(),
+            });
+        }
+
+        Ok(match tag {
+            0x00 => {
+                Self::Avail {
+                    provider_avail_id: reader.read_u32()?,
+                }
+            }
+            0x01 => {
+                let preroll = reader.read_u8()?;
+                let count = reader.read(3)?;
+                reader.skip(5)?;
+
+                let count = u8::try_from(count).unwrap_or_default();
+                let dtmf_chars = reader.read_bytes(usize::from(count))?;
+
+                Self::Dtmf {
+                    preroll,
+                    dtmf_chars: String::from_utf8(dtmf_chars.to_vec()).map_err(Error::scte35)?,
+                }
+            }
+            0x02 => Self::Segmentation(SegmentationDescriptor::read(&mut reader)?),
+            _ => {
+                Self::Other {
+                    tag,
+                    identifier,
+                    data: reader.read_bytes(reader.remaining_bytes())?.to_vec(),
+                }
+            }
+        })
+    }
+
+    /// Writes a single descriptor, including its tag and length.
+    pub(crate) fn write(&self, writer: &mut BitWriter) -> crate::Result<()> {
+        let mut inner = BitWriter::new();
+
+        match self {
+            Self::Avail { provider_avail_id } => {
+                inner.write(u64::from(CUEI), 32)?;
+                inner.write(u64::from(*provider_avail_id), 32)?;
+            }
+            Self::Dtmf {
+                preroll,
+                dtmf_chars,
+            } => {
+                if dtmf_chars.len() > 7 {
+                    return Err(Error::scte35("too many DTMF characters"));
+                }
+
+                inner.write(u64::from(CUEI), 32)?;
+                inner.write(u64::from(*preroll), 8)?;
+                inner.write(dtmf_chars.len() as u64, 3)?;
+                inner.reserved(5);
+                inner.write_bytes(dtmf_chars.as_bytes());
+            }
+            Self::Segmentation(descriptor) => {
+                inner.write(u64::from(CUEI), 32)?;
+                descriptor.write(&mut inner)?;
+            }
+            Self::Other {
+                identifier, data, ..
+            } => {
+                inner.write(u64::from(*identifier), 32)?;
+                inner.write_bytes(data);
+            }
+        }
+
+        let data = inner.into_inner();
+        let length =
+            u8::try_from(data.len()).map_err(|_| Error::scte35("the descriptor is too long"))?;
+
+        writer.write(u64::from(self.tag()), 8)?;
+        writer.write(u64::from(length), 8)?;
+        writer.write_bytes(&data);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn round_trip(descriptor: &SpliceDescriptor) -> SpliceDescriptor {
+        let mut writer = BitWriter::new();
+        descriptor.write(&mut writer).unwrap();
+
+        SpliceDescriptor::read(&mut BitReader::new(&writer.into_inner())).unwrap()
+    }
+
+    #[test]
+    fn test_segmentation_descriptor() {
+        let data = [
+            0x02, 0x1C, 0x43, 0x55, 0x45, 0x49, 0x48, 0x00, 0x00, 0x8E, 0x7F, 0xCF, 0x00, 0x01,
+            0xA5, 0x99, 0xB0, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x2C, 0xA0, 0xA1, 0x8A, 0x34,
+            0x02, 0x00,
+        ];
+
+        let descriptor = SpliceDescriptor::read(&mut BitReader::new(&data)).unwrap();
+
+        assert_eq!(
+            descriptor,
+            SpliceDescriptor::Segmentation(SegmentationDescriptor {
+                delivery_restrictions: Some(DeliveryRestrictions {
+                    web_delivery_allowed: false,
+                    no_regional_blackout: true,
+                    archive_allowed: true,
+                    device_restrictions: 3,
+                }),
+                segmentation_duration: Some(0x0001_A599_B0),
+                segment_num: 2,
+                ..SegmentationDescriptor::new(
+                    0x4800_008E,
+                    SegmentationType::ProviderPlacementOpportunityStart,
+                    Upid::AiringId(0x2CA0_A18A)
+                )
+            })
+        );
+
+        let mut writer = BitWriter::new();
+        descriptor.write(&mut writer).unwrap();
+        assert_eq!(writer.into_inner(), data.to_vec());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut segmentation = SegmentationDescriptor::new(
+            1,
+            SegmentationType::DistributorPlacementOpportunityStart,
+            Upid::AdId("ABCD01234567".to_string()),
+        );
+        segmentation.is_program_segmentation = false;
+        segmentation.components = vec![SegmentationComponent {
+            component_tag: 1,
+            pts_offset: 90_000,
+        }];
+        segmentation.sub_segment_num = Some(1);
+        segmentation.sub_segments_expected = Some(2);
+
+        for descriptor in vec![
+            SpliceDescriptor::Avail {
+                provider_avail_id: 309,
+            },
+            SpliceDescriptor::Dtmf {
+                preroll: 10,
+                dtmf_chars: "1*".to_string(),
+            },
+            SpliceDescriptor::Segmentation(segmentation),
+            SpliceDescriptor::Other {
+                tag: 0x03,
+                identifier: 0x1234_5678,
+                data: vec![1, 2, 3],
+            },
+        ] {
+            assert_eq!(round_trip(&descriptor), descriptor);
+        }
+    }
+
+    #[test]
+    fn test_segmentation_type() {
+        assert_eq!(
+            SegmentationType::from(0x34),
+            SegmentationType::ProviderPlacementOpportunityStart
+        );
+        assert_eq!(SegmentationType::from(0xEE), SegmentationType::Other(0xEE));
+        assert_eq!(u8::from(SegmentationType::BreakEnd), 0x23);
+
+        assert!(SegmentationType::BreakStart.is_start());
+        assert!(!SegmentationType::BreakStart.is_end());
+        assert!(SegmentationType::ProgramEarlyTermination.is_end());
+        assert!(!SegmentationType::ContentIdentification.is_start());
+    }
+}
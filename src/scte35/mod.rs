@@ -0,0 +1,612 @@
+//! Decoding and encoding of SCTE 35 `splice_info_section`s.
+//!
+//! The [`ExtXDateRange::scte35_cmd`], [`ExtXDateRange::scte35_out`] and
+//! [`ExtXDateRange::scte35_in`] attributes contain a big-endian binary
+//! encoded `splice_info_section` as a hexadecimal sequence, which can be
+//! parsed into a [`SpliceInfoSection`].
+//!
+//! # Example
+//!
+//! ```
+//! use core::convert::TryFrom;
+//! use hls_m3u8::scte35::{SpliceCommand, SpliceInfoSection};
+//! use std::time::Duration;
+//!
+//! let section = SpliceInfoSection::try_from(concat!(
+//!     "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF5000000",
+//!     "00000A0008435545490000013562DBA30A"
+//! ))?;
+//!
+//! if let SpliceCommand::SpliceInsert(splice_insert) = &section.splice_command {
+//!     assert_eq!(splice_insert.splice_event_id, 0x4800_008F);
+//!     assert!(splice_insert.is_out_of_network);
+//! }
+//!
+//! assert_eq!(
+//!     section.break_duration(),
+//!     Some(Duration::from_nanos(60_293_566_666))
+//! );
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+//!
+//! [`ExtXDateRange::scte35_cmd`]: crate::tags::ExtXDateRange::scte35_cmd
+//! [`ExtXDateRange::scte35_out`]: crate::tags::ExtXDateRange::scte35_out
+//! [`ExtXDateRange::scte35_in`]: crate::tags::ExtXDateRange::scte35_in
+use core::convert::TryFrom;
+use std::time::Duration;
+
+mod bits;
+mod command;
+mod descriptor;
+mod upid;
+
+pub use command::*;
+pub use descriptor::*;
+pub use upid::Upid;
+
+use crate::builder::tags::ExtXDateRangeBuilder;
+use crate::tags::ExtXDateRange;
+use crate::Error;
+use bits::{crc32, BitReader, BitWriter};
+
+/// The `table_id` of a `splice_info_section`.
+const TABLE_ID: u8 = 0xFC;
+
+/// The `splice_command_length`, that is used by legacy encoders, if the
+/// length of the command is unknown.
+const UNKNOWN_COMMAND_LENGTH: u16 = 0xFFF;
+
+/// A SCTE 35 `splice_info_section`, which carries a [`SpliceCommand`] and a
+/// list of [`SpliceDescriptor`]s.
+///
+/// ## Note
+///
+/// Encrypted sections are not supported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpliceInfoSection {
+    /// The Stream Access Point type (2 bits). `3` means, that the type is not
+    /// specified.
+    pub sap_type: u8,
+    /// The version of the `splice_info_section` (should be `0`).
+    pub protocol_version: u8,
+    /// An offset in ticks of the 90 kHz clock, that is added to all
+    /// [`SpliceTime`]s of the section (33 bits).
+    pub pts_adjustment: u64,
+    /// The control word index, that would be used for encryption.
+    pub cw_index: u8,
+    /// An authorization tier (12 bits). `0xFFF` means, that the tier is not
+    /// used.
+    pub tier: u16,
+    /// The command of the section.
+    pub splice_command: SpliceCommand,
+    /// The descriptors of the section.
+    pub splice_descriptors: Vec<SpliceDescriptor>,
+}
+
+impl SpliceInfoSection {
+    /// Makes a new [`SpliceInfoSection`] with the provided command.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::scte35::{SpliceCommand, SpliceInfoSection, SpliceInsert};
+    ///
+    /// let section =
+    ///     SpliceInfoSection::new(SpliceCommand::SpliceInsert(SpliceInsert::new(1, true)));
+    /// ```
+    #[must_use]
+    pub const fn new(splice_command: SpliceCommand) -> Self {
+        Self {
+            sap_type: 3,
+            protocol_version: 0,
+            pts_adjustment: 0,
+            cw_index: 0xFF,
+            tier: 0xFFF,
+            splice_command,
+            splice_descriptors: Vec::new(),
+        }
+    }
+
+    /// Decodes a binary `splice_info_section` and verifies its CRC-32.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the data is not a valid
+    /// `splice_info_section`, if the section is encrypted or if the CRC-32
+    /// does not match.
+    pub fn decode(data: &[u8]) -> crate::Result<Self> {
+        let mut reader = BitReader::new(data);
+
+        let table_id = reader.read_u8()?;
+
+        if table_id != TABLE_ID {
+            return Err(Error::scte35(format!(
+                "expected the table_id {:#04X}, but found {:#04X}",
+                TABLE_ID, table_id
+            )));
+        }
+
+        // section_syntax_indicator and private_indicator
+        reader.skip(2)?;
+        let sap_type = u8::try_from(reader.read(2)?).unwrap_or_default();
+        let section_length = usize::from(reader.read_u16(12)?);
+
+        if section_length != reader.remaining_bytes() {
+            return Err(Error::scte35(format!(
+                "expected a section_length of {}, but found {}",
+                reader.remaining_bytes(),
+                section_length
+            )));
+        }
+
+        if section_length < 4 {
+            return Err(Error::scte35("the section is too short"));
+        }
+
+        let (_, crc) = data.split_at(data.len() - 4);
+        let expected = crc32(&data[..data.len() - 4]);
+        let found = u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]);
+
+        if expected != found {
+            return Err(Error::crc32(expected, found));
+        }
+
+        let protocol_version = reader.read_u8()?;
+
+        if reader.read_bool()? {
+            return Err(Error::scte35("encrypted sections are not supported"));
+        }
+
+        // encryption_algorithm
+        reader.skip(6)?;
+        let pts_adjustment = reader.read(33)?;
+        let cw_index = reader.read_u8()?;
+        let tier = reader.read_u16(12)?;
+        let command_length = reader.read_u16(12)?;
+        let command_type = reader.read_u8()?;
+
+        let splice_command = {
+            if command_length == UNKNOWN_COMMAND_LENGTH {
+                match command_type {
+                    0x00 | 0x05 | 0x06 | 0x07 => SpliceCommand::read(command_type, &mut reader)?,
+                    _ => {
+                        return Err(Error::scte35(format!(
+                            "the splice_command_length of the command {:#04X} is unknown",
+                            command_type
+                        )));
+                    }
+                }
+            } else {
+                let data = reader.read_bytes(usize::from(command_length))?;
+                SpliceCommand::read(command_type, &mut BitReader::new(data))?
+            }
+        };
+
+        let descriptor_loop_length = usize::from(reader.read_u16(16)?);
+        let mut descriptors = BitReader::new(reader.read_bytes(descriptor_loop_length)?);
+        let mut splice_descriptors = vec![];
+
+        while descriptors.remaining_bytes() > 0 {
+            splice_descriptors.push(SpliceDescriptor::read(&mut descriptors)?);
+        }
+
+        // only the CRC_32 might remain (there is no alignment_stuffing, because
+        // the section is not encrypted)
+        if reader.remaining_bytes() != 4 {
+            return Err(Error::scte35("unexpected data after the descriptors"));
+        }
+
+        Ok(Self {
+            sap_type,
+            protocol_version,
+            pts_adjustment,
+            cw_index,
+            tier,
+            splice_command,
+            splice_descriptors,
+        })
+    }
+
+    /// Encodes the [`SpliceInfoSection`] into its binary representation,
+    /// including the CRC-32.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if a value does not fit in its field
+    /// (for example a [`Upid`] with more than 255 bytes).
+    pub fn encode(&self) -> crate::Result<Vec<u8>> {
+        let mut command = BitWriter::new();
+        self.splice_command.write(&mut command)?;
+        let command = command.into_inner();
+
+        let mut descriptors = BitWriter::new();
+
+        for descriptor in &self.splice_descriptors {
+            descriptor.write(&mut descriptors)?;
+        }
+
+        let descriptors = descriptors.into_inner();
+
+        // protocol_version (1) + encryption/pts_adjustment (5) + cw_index (1) +
+        // tier/splice_command_length (3) + splice_command_type (1) +
+        // descriptor_loop_length (2) + CRC_32 (4)
+        let section_length = 17 + command.len() + descriptors.len();
+
+        if section_length > 4093 {
+            return Err(Error::scte35("the section is too long"));
+        }
+
+        let mut writer = BitWriter::new();
+        writer.write(u64::from(TABLE_ID), 8)?;
+        // section_syntax_indicator and private_indicator
+        writer.write(0, 2)?;
+        writer.write(u64::from(self.sap_type), 2)?;
+        writer.write(section_length as u64, 12)?;
+        writer.write(u64::from(self.protocol_version), 8)?;
+        // encrypted_packet
+        writer.write_bool(false);
+        // encryption_algorithm
+        writer.write(0, 6)?;
+        writer.write(self.pts_adjustment, 33)?;
+        writer.write(u64::from(self.cw_index), 8)?;
+        writer.write(u64::from(self.tier), 12)?;
+        writer.write(command.len() as u64, 12)?;
+        writer.write(u64::from(self.splice_command.command_type()), 8)?;
+        writer.write_bytes(&command);
+        writer.write(descriptors.len() as u64, 16)?;
+        writer.write_bytes(&descriptors);
+
+        let mut result = writer.into_inner();
+        let crc = crc32(&result);
+        result.extend_from_slice(&crc.to_be_bytes());
+
+        Ok(result)
+    }
+
+    /// Encodes the [`SpliceInfoSection`] as an uppercase hexadecimal sequence
+    /// with a `0x` prefix, as it is used by the [`ExtXDateRange`] tag.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the section can not be encoded
+    /// (see [`SpliceInfoSection::encode`]).
+    pub fn to_hex(&self) -> crate::Result<String> {
+        Ok(format!("0x{}", hex::encode_upper(self.encode()?)))
+    }
+
+    /// Returns an iterator over all [`SegmentationDescriptor`]s.
+    pub fn segmentation_descriptors(&self) -> impl Iterator<Item = &SegmentationDescriptor> {
+        self.splice_descriptors
+            .iter()
+            .filter_map(|descriptor| match descriptor {
+                SpliceDescriptor::Segmentation(value) => Some(value),
+                _ => None,
+            })
+    }
+
+    /// Returns the id of the splice event, which is either the
+    /// [`SpliceInsert::splice_event_id`] or the
+    /// [`SegmentationDescriptor::segmentation_event_id`] of the first
+    /// segmentation descriptor.
+    #[must_use]
+    pub fn splice_event_id(&self) -> Option<u32> {
+        if let SpliceCommand::SpliceInsert(splice_insert) = &self.splice_command {
+            return Some(splice_insert.splice_event_id);
+        }
+
+        self.segmentation_descriptors()
+            .next()
+            .map(|descriptor| descriptor.segmentation_event_id)
+    }
+
+    /// Returns `Some(true)`, if the section signals the start of a break (a
+    /// splice out of the network), `Some(false)`, if it signals the end of a
+    /// break and `None`, if it signals neither (for example a cancelled
+    /// event).
+    #[must_use]
+    pub fn is_out_of_network(&self) -> Option<bool> {
+        if let SpliceCommand::SpliceInsert(splice_insert) = &self.splice_command {
+            if splice_insert.is_cancelled {
+                return None;
+            }
+
+            return Some(splice_insert.is_out_of_network);
+        }
+
+        let descriptor = self
+            .segmentation_descriptors()
+            .find(|descriptor| !descriptor.is_cancelled)?;
+
+        if descriptor.segmentation_type.is_start() {
+            Some(true)
+        } else if descriptor.segmentation_type.is_end() {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the duration of the break, which is either the
+    /// [`SpliceInsert::break_duration`] or the
+    /// [`SegmentationDescriptor::segmentation_duration`] of the first
+    /// segmentation descriptor, that has one.
+    #[must_use]
+    pub fn break_duration(&self) -> Option<Duration> {
+        if let SpliceCommand::SpliceInsert(splice_insert) = &self.splice_command {
+            if let Some(break_duration) = &splice_insert.break_duration {
+                return Some(break_duration.duration());
+            }
+        }
+
+        self.segmentation_descriptors()
+            .find_map(SegmentationDescriptor::duration)
+    }
+
+    /// Returns an [`ExtXDateRangeBuilder`], that has been prefilled with this
+    /// splice event.
+    ///
+    /// - The `ID` is `splice-` followed by the hexadecimal
+    ///   [`SpliceInfoSection::splice_event_id`] (if there is one).
+    /// - The section is written to `SCTE35-OUT`, if it is an out of network
+    ///   splice, to `SCTE35-IN`, if it is an into network splice and to
+    ///   `SCTE35-CMD` otherwise.
+    /// - The `PLANNED-DURATION` of an out of network splice is set to the
+    ///   [`SpliceInfoSection::break_duration`].
+    ///
+    /// The `START-DATE` has to be set by the caller.
+    ///
+    /// # Example
+    #[cfg_attr(
+        feature = "chrono",
+        doc = r#"
+```
+use core::convert::TryFrom;
+use hls_m3u8::scte35::SpliceInfoSection;
+use chrono::{FixedOffset, TimeZone};
+
+let section = SpliceInfoSection::try_from(concat!(
+    "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF5000000",
+    "00000A0008435545490000013562DBA30A"
+))?;
+
+let date_range = section
+    .date_range_builder()?
+    .start_date(FixedOffset::east(0).ymd(2014, 3, 5).and_hms(11, 15, 0))
+    .build()?;
+
+assert_eq!(date_range.id(), "splice-4800008F");
+assert!(date_range.scte35_out().is_some());
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+"#
+    )]
+    #[cfg_attr(
+        not(feature = "chrono"),
+        doc = r#"
+```
+use core::convert::TryFrom;
+use hls_m3u8::scte35::SpliceInfoSection;
+
+let section = SpliceInfoSection::try_from(concat!(
+    "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF5000000",
+    "00000A0008435545490000013562DBA30A"
+))?;
+
+let date_range = section
+    .date_range_builder()?
+    .start_date("2014-03-05T11:15:00Z")
+    .build()?;
+
+assert_eq!(date_range.id(), "splice-4800008F");
+assert!(date_range.scte35_out().is_some());
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+"#
+    )]
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the section can not be encoded.
+    pub fn date_range_builder(&self) -> crate::Result<ExtXDateRangeBuilder<'static>> {
+        let value = self.to_hex()?;
+        let mut builder = ExtXDateRange::builder();
+
+        if let Some(id) = self.splice_event_id() {
+            builder.id(format!("splice-{:X}", id));
+        }
+
+        match self.is_out_of_network() {
+            Some(true) => {
+                builder.scte35_out(value);
+
+                if let Some(duration) = self.break_duration() {
+                    builder.planned_duration(duration);
+                }
+            }
+            Some(false) => {
+                builder.scte35_in(value);
+            }
+            None => {
+                builder.scte35_cmd(value);
+            }
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Parses a hexadecimal sequence (with or without a `0x` prefix).
+impl TryFrom<&str> for SpliceInfoSection {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let input = input.trim_start_matches("0x").trim_start_matches("0X");
+
+        Self::decode(&hex::decode(input).map_err(Error::hex)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SPLICE_INSERT: &str = concat!(
+        "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF5000000",
+        "00000A0008435545490000013562DBA30A"
+    );
+
+    const TIME_SIGNAL: &str = concat!(
+        "0xFC3034000000000000FFFFF00506FE72BD0050001E021C435545494800008E7FCF",
+        "0001A599B00808000000002CA0A18A3402009AC9D17E"
+    );
+
+    #[test]
+    fn test_splice_insert() {
+        let section = SpliceInfoSection::try_from(SPLICE_INSERT).unwrap();
+
+        let mut splice_insert = SpliceInsert::new(0x4800_008F, true);
+        splice_insert.is_splice_immediate = false;
+        splice_insert.splice_time = Some(SpliceTime::new(0x7369_C02E));
+        splice_insert.break_duration = Some(BreakDuration {
+            auto_return: true,
+            ticks: 0x0052_CCF5,
+        });
+
+        let mut expected = SpliceInfoSection::new(SpliceCommand::SpliceInsert(splice_insert));
+        expected.splice_descriptors = vec![SpliceDescriptor::Avail {
+            provider_avail_id: 0x135,
+        }];
+
+        assert_eq!(section, expected);
+        assert_eq!(section.to_hex().unwrap(), SPLICE_INSERT);
+        assert_eq!(section.splice_event_id(), Some(0x4800_008F));
+        assert_eq!(section.is_out_of_network(), Some(true));
+    }
+
+    #[test]
+    fn test_time_signal() {
+        let section = SpliceInfoSection::try_from(TIME_SIGNAL).unwrap();
+
+        assert_eq!(
+            section.splice_command,
+            SpliceCommand::TimeSignal(SpliceTime::new(0x72BD_0050))
+        );
+
+        let descriptor = section.segmentation_descriptors().next().unwrap();
+        assert_eq!(descriptor.upid, Upid::AiringId(0x2CA0_A18A));
+        assert_eq!(
+            descriptor.segmentation_type,
+            SegmentationType::ProviderPlacementOpportunityStart
+        );
+
+        assert_eq!(section.to_hex().unwrap(), TIME_SIGNAL);
+        assert_eq!(section.splice_event_id(), Some(0x4800_008E));
+        assert_eq!(section.is_out_of_network(), Some(true));
+        assert_eq!(section.break_duration(), Some(Duration::from_secs(307)));
+    }
+
+    #[test]
+    fn test_errors() {
+        // invalid crc
+        assert_eq!(
+            SpliceInfoSection::try_from(&TIME_SIGNAL.replace("9AC9D17E", "9AC9D17F")[..]),
+            Err(Error::crc32(0x9AC9_D17E, 0x9AC9_D17F))
+        );
+        // invalid table_id
+        assert!(SpliceInfoSection::try_from(&SPLICE_INSERT.replace("0xFC", "0xFD")[..]).is_err());
+        // truncated
+        assert!(SpliceInfoSection::try_from(&SPLICE_INSERT[..40]).is_err());
+        assert!(SpliceInfoSection::try_from("0x").is_err());
+        assert!(SpliceInfoSection::try_from("garbage").is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut descriptor = SegmentationDescriptor::new(
+            1,
+            SegmentationType::BreakEnd,
+            Upid::Uri("urn:uuid:1".to_string()),
+        );
+        descriptor.segment_num = 1;
+        descriptor.segments_expected = 1;
+
+        let mut section =
+            SpliceInfoSection::new(SpliceCommand::TimeSignal(SpliceTime::new(90_000)));
+        section.splice_descriptors = vec![SpliceDescriptor::Segmentation(descriptor)];
+
+        let data = section.encode().unwrap();
+
+        assert_eq!(SpliceInfoSection::decode(&data).unwrap(), section);
+        assert_eq!(section.is_out_of_network(), Some(false));
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        // pts_time is a 33 bit field:
+        let section =
+            SpliceInfoSection::new(SpliceCommand::TimeSignal(SpliceTime::new(1 << 33)));
+        assert!(section.encode().is_err());
+        assert!(section.date_range_builder().is_err());
+
+        // an Ad-ID has exactly 12 characters:
+        let mut section =
+            SpliceInfoSection::new(SpliceCommand::TimeSignal(SpliceTime::new(90_000)));
+        section.splice_descriptors = vec![SpliceDescriptor::Segmentation(
+            SegmentationDescriptor::new(
+                1,
+                SegmentationType::BreakStart,
+                Upid::AdId("ABCD".to_string()),
+            ),
+        )];
+        assert!(section.encode().is_err());
+        assert!(section.date_range_builder().is_err());
+    }
+
+    #[test]
+    fn test_date_range_builder() {
+        let section = SpliceInfoSection::try_from(SPLICE_INSERT).unwrap();
+        let builder = section.date_range_builder().unwrap();
+
+        let date_range = builder
+            .clone()
+            .start_date({
+                #[cfg(feature = "chrono")]
+                {
+                    use chrono::{FixedOffset, TimeZone};
+                    FixedOffset::east(0).ymd(2014, 3, 5).and_hms(11, 15, 0)
+                }
+                #[cfg(not(feature = "chrono"))]
+                {
+                    "2014-03-05T11:15:00Z"
+                }
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(date_range.id(), "splice-4800008F");
+        assert_eq!(
+            date_range.scte35_out(),
+            Some(&SPLICE_INSERT.to_string().into())
+        );
+        assert_eq!(
+            date_range.planned_duration,
+            Some(Duration::from_nanos(60_293_566_666))
+        );
+        assert_eq!(
+            SpliceInfoSection::try_from(date_range.scte35_out().unwrap().as_ref()).unwrap(),
+            section
+        );
+
+        let mut section = section;
+        if let SpliceCommand::SpliceInsert(splice_insert) = &mut section.splice_command {
+            splice_insert.is_out_of_network = false;
+            splice_insert.break_duration = None;
+        }
+
+        let date_range = section.date_range_builder().unwrap().build().unwrap();
+        assert!(date_range.scte35_in().is_some());
+        assert!(date_range.scte35_out().is_none());
+        assert_eq!(date_range.planned_duration, None);
+    }
+}
@@ -0,0 +1,249 @@
+use core::convert::TryFrom;
+
+use crate::scte35::bits::BitReader;
+use crate::Error;
+
+/// A Segmentation Unique Program Identifier (`segmentation_upid`), which
+/// identifies the content of a [`SegmentationDescriptor`].
+///
+/// [`SegmentationDescriptor`]: crate::scte35::SegmentationDescriptor
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Upid {
+    /// No UPID is used (`0x00`).
+    NotUsed,
+    /// A user defined UPID (`0x01`, deprecated).
+    UserDefined(Vec<u8>),
+    /// An Industry Standard Commercial Identifier (`0x02`), which consists of
+    /// 8 characters.
+    Isci(String),
+    /// An Advertising Digital Identification (`0x03`), which consists of 12
+    /// characters.
+    AdId(String),
+    /// A SMPTE Unique Material Identifier (`0x04`), which consists of 32
+    /// bytes.
+    Umid(Vec<u8>),
+    /// An International Standard Audiovisual Number (`0x06`), which consists
+    /// of 12 bytes.
+    Isan(Vec<u8>),
+    /// A Tribune Media Systems Program identifier (`0x07`), which consists of
+    /// 12 characters.
+    Tid(String),
+    /// An `AiringID` (`0x08`).
+    AiringId(u64),
+    /// A `CableLabs` metadata identifier (`0x09`).
+    Adi(String),
+    /// An Entertainment Identifier Registry identifier (`0x0A`), which
+    /// consists of 12 bytes.
+    Eidr(Vec<u8>),
+    /// Multiple UPIDs (`0x0D`).
+    Mid(Vec<Self>),
+    /// Advertising information (`0x0E`).
+    AdsInformation(String),
+    /// A Uniform Resource Identifier (`0x0F`).
+    Uri(String),
+    /// A Universally Unique Identifier (`0x10`), which consists of 16 bytes.
+    Uuid(Vec<u8>),
+    /// Any other UPID.
+    Other {
+        /// The `segmentation_upid_type`.
+        upid_type: u8,
+        /// The raw `segmentation_upid`.
+        data: Vec<u8>,
+    },
+}
+
+fn string(data: &[u8]) -> crate::Result<String> {
+    String::from_utf8(data.to_vec()).map_err(Error::scte35)
+}
+
+fn fixed(name: &str, data: &[u8], length: usize) -> crate::Result<Vec<u8>> {
+    if data.len() == length {
+        Ok(data.to_vec())
+    } else {
+        Err(Error::scte35(format!(
+            "expected {} bytes for {}, but found {}",
+            length,
+            name,
+            data.len()
+        )))
+    }
+}
+
+fn fixed_string(name: &str, data: &[u8], length: usize) -> crate::Result<String> {
+    string(&fixed(name, data, length)?)
+}
+
+impl Upid {
+    /// Returns the `segmentation_upid_type`.
+    #[must_use]
+    pub const fn upid_type(&self) -> u8 {
+        match self {
+            Self::NotUsed => 0x00,
+            Self::UserDefined(_) => 0x01,
+            Self::Isci(_) => 0x02,
+            Self::AdId(_) => 0x03,
+            Self::Umid(_) => 0x04,
+            Self::Isan(_) => 0x06,
+            Self::Tid(_) => 0x07,
+            Self::AiringId(_) => 0x08,
+            Self::Adi(_) => 0x09,
+            Self::Eidr(_) => 0x0A,
+            Self::Mid(_) => 0x0D,
+            Self::AdsInformation(_) => 0x0E,
+            Self::Uri(_) => 0x0F,
+            Self::Uuid(_) => 0x10,
+            Self::Other { upid_type, .. } => *upid_type,
+        }
+    }
+
+    pub(crate) fn read(upid_type: u8, data: &[u8]) -> crate::Result<Self> {
+        Ok(match upid_type {
+            0x00 => Self::NotUsed,
+            0x01 => Self::UserDefined(data.to_vec()),
+            0x02 => Self::Isci(fixed_string("ISCI", data, 8)?),
+            0x03 => Self::AdId(fixed_string("Ad-ID", data, 12)?),
+            0x04 => Self::Umid(fixed("UMID", data, 32)?),
+            0x06 => Self::Isan(fixed("ISAN", data, 12)?),
+            0x07 => Self::Tid(fixed_string("TID", data, 12)?),
+            0x08 => {
+                let mut reader = BitReader::new(data);
+                let value = reader.read(64)?;
+
+                if reader.remaining_bits() != 0 {
+                    return Err(Error::scte35("expected 8 bytes for AiringID"));
+                }
+
+                Self::AiringId(value)
+            }
+            0x09 => Self::Adi(string(data)?),
+            0x0A => Self::Eidr(fixed("EIDR", data, 12)?),
+            0x0D => {
+                let mut reader = BitReader::new(data);
+                let mut list = vec![];
+
+                while reader.remaining_bytes() > 0 {
+                    let upid_type = reader.read_u8()?;
+                    let length = reader.read_u8()?;
+
+                    list.push(Self::read(
+                        upid_type,
+                        reader.read_bytes(usize::from(length))?,
+                    )?);
+                }
+
+                Self::Mid(list)
+            }
+            0x0E => Self::AdsInformation(string(data)?),
+            0x0F => Self::Uri(string(data)?),
+            0x10 => Self::Uuid(fixed("UUID", data, 16)?),
+            _ => {
+                Self::Other {
+                    upid_type,
+                    data: data.to_vec(),
+                }
+            }
+        })
+    }
+
+    /// Returns the encoded `segmentation_upid` (without type and length).
+    ///
+    /// The length of the fixed-length identifiers is checked, so that the
+    /// result can be decoded again.
+    pub(crate) fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        Ok(match self {
+            Self::NotUsed => vec![],
+            Self::Isci(value) => fixed("ISCI", value.as_bytes(), 8)?,
+            Self::AdId(value) => fixed("Ad-ID", value.as_bytes(), 12)?,
+            Self::Tid(value) => fixed("TID", value.as_bytes(), 12)?,
+            Self::Umid(value) => fixed("UMID", value, 32)?,
+            Self::Isan(value) => fixed("ISAN", value, 12)?,
+            Self::Eidr(value) => fixed("EIDR", value, 12)?,
+            Self::Uuid(value) => fixed("UUID", value, 16)?,
+            Self::Adi(value) | Self::AdsInformation(value) | Self::Uri(value) => {
+                value.as_bytes().to_vec()
+            }
+            Self::UserDefined(value) | Self::Other { data: value, .. } => value.clone(),
+            Self::AiringId(value) => value.to_be_bytes().to_vec(),
+            Self::Mid(list) => {
+                let mut result = vec![];
+
+                for upid in list {
+                    let bytes = upid.to_bytes()?;
+
+                    result.push(upid.upid_type());
+                    result.push(length(&bytes)?);
+                    result.extend(bytes);
+                }
+
+                result
+            }
+        })
+    }
+}
+
+/// Returns the length of a `segmentation_upid`, which must fit in 8 bits.
+pub(crate) fn length(bytes: &[u8]) -> crate::Result<u8> {
+    u8::try_from(bytes.len()).map_err(|_| Error::scte35("the segmentation_upid is too long"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_read() {
+        assert_eq!(
+            Upid::read(0x08, &[0, 0, 0, 0, 0x2C, 0xA0, 0xA1, 0x8A]).unwrap(),
+            Upid::AiringId(0x2CA0_A18A)
+        );
+        assert_eq!(
+            Upid::read(0x03, b"ABCD01234567").unwrap(),
+            Upid::AdId("ABCD01234567".to_string())
+        );
+        assert_eq!(
+            Upid::read(0x0F, b"urn:uuid:1").unwrap(),
+            Upid::Uri("urn:uuid:1".to_string())
+        );
+        assert_eq!(
+            Upid::read(0x0D, b"\x03\x0cABCD01234567\x0f\x01x").unwrap(),
+            Upid::Mid(vec![
+                Upid::AdId("ABCD01234567".to_string()),
+                Upid::Uri("x".to_string())
+            ])
+        );
+        assert_eq!(
+            Upid::read(0x20, &[1, 2]).unwrap(),
+            Upid::Other {
+                upid_type: 0x20,
+                data: vec![1, 2]
+            }
+        );
+
+        assert!(Upid::read(0x03, b"ABCD").is_err());
+        assert!(Upid::read(0x08, &[0; 9]).is_err());
+        assert!(Upid::read(0x0D, b"\x03\x0cABCD").is_err());
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        for upid in vec![
+            Upid::AiringId(0x2CA0_A18A),
+            Upid::AdId("ABCD01234567".to_string()),
+            Upid::Mid(vec![
+                Upid::AdId("ABCD01234567".to_string()),
+                Upid::Uri("x".to_string()),
+            ]),
+            Upid::Uuid(vec![7; 16]),
+        ] {
+            assert_eq!(
+                Upid::read(upid.upid_type(), &upid.to_bytes().unwrap()).unwrap(),
+                upid
+            );
+        }
+        assert!(Upid::AdId("ABCD".to_string()).to_bytes().is_err());
+        assert!(Upid::Isan(vec![0; 13]).to_bytes().is_err());
+        assert!(Upid::Mid(vec![Upid::Uuid(vec![7; 15])]).to_bytes().is_err());
+    }
+}
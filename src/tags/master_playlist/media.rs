@@ -6,8 +6,8 @@ use derive_builder::Builder;
 use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
-use crate::types::{Channels, InStreamId, MediaType, ProtocolVersion};
-use crate::utils::{parse_yes_or_no, quote, tag, unquote};
+use crate::types::{Channels, InStreamId, MediaType, ProtocolVersion, VideoLayout};
+use crate::utils::{parse_yes_or_no, quote, tag, unquote, validate_stable_id};
 use crate::{Error, RequiredVersion};
 
 /// An [`ExtXMedia`] tag is an alternative rendition of a [`VariantStream`].
@@ -169,7 +169,53 @@ pub struct ExtXMedia<'a> {
     /// [`MasterPlaylist`]: crate::MasterPlaylist
     #[builder(setter(strip_option), default)]
     #[shorthand(enable(skip))]
-    pub channels: Option<Channels>,
+    pub channels: Option<Channels<'a>>,
+    /// The audio bit depth of the rendition (`BIT-DEPTH`).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and must only be specified, if the
+    /// [`ExtXMedia::media_type`] is [`MediaType::Audio`]. It should be
+    /// specified for lossless or high resolution audio.
+    #[builder(setter(strip_option), default)]
+    #[shorthand(enable(skip))]
+    pub bit_depth: Option<u64>,
+    /// The audio sample rate of the rendition in Hz (`SAMPLE-RATE`).
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and must only be specified, if the
+    /// [`ExtXMedia::media_type`] is [`MediaType::Audio`].
+    #[builder(setter(strip_option), default)]
+    #[shorthand(enable(skip))]
+    pub sample_rate: Option<u64>,
+    /// The video layouts of the rendition, ordered by preference
+    /// (`REQ-VIDEO-LAYOUT`), which should match the layouts of the
+    /// [`VariantStream`]s, that refer to the rendition.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional and must only be specified, if the
+    /// [`ExtXMedia::media_type`] is [`MediaType::Video`]. The list must not be
+    /// empty.
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    #[builder(setter(strip_option), default)]
+    video_layout: Option<VideoLayout>,
+    /// An identifier, that stays the same for the rendition across different
+    /// versions of the [`MasterPlaylist`] (`STABLE-RENDITION-ID`).
+    ///
+    /// This allows a client to keep the selected rendition, after the
+    /// [`MasterPlaylist`] has been reloaded.
+    ///
+    /// ### Note
+    ///
+    /// This field is optional. The identifier must only contain the characters
+    /// `[a-z]`, `[A-Z]`, `[0-9]`, `+`, `/`, `=`, `.`, `-` and `_`.
+    ///
+    /// [`MasterPlaylist`]: crate::MasterPlaylist
+    #[builder(setter(strip_option), default)]
+    stable_rendition_id: Option<Cow<'a, str>>,
 }
 
 impl<'a> ExtXMediaBuilder<'a> {
@@ -216,6 +262,39 @@ impl<'a> ExtXMediaBuilder<'a> {
             .to_string());
         }
 
+        if let Some(Some(audio_coding)) = self
+            .channels
+            .as_ref()
+            .map(|v| v.as_ref().and_then(Channels::audio_coding))
+        {
+            audio_coding.validate().map_err(|e| e.to_string())?;
+        }
+
+        if media_type != MediaType::Audio {
+            if let Some(Some(_)) = self.bit_depth {
+                return Err(Error::unexpected_attribute("BIT-DEPTH").to_string());
+            }
+
+            if let Some(Some(_)) = self.sample_rate {
+                return Err(Error::unexpected_attribute("SAMPLE-RATE").to_string());
+            }
+        }
+
+        if let Some(Some(video_layout)) = &self.video_layout {
+            if media_type != MediaType::Video {
+                return Err(Error::unexpected_attribute("REQ-VIDEO-LAYOUT").to_string());
+            }
+
+            if video_layout.is_empty() {
+                return Err(Error::custom("the REQ-VIDEO-LAYOUT must not be empty").to_string());
+            }
+        }
+
+        if let Some(Some(stable_rendition_id)) = &self.stable_rendition_id {
+            validate_stable_id("STABLE-RENDITION-ID", stable_rendition_id)
+                .map_err(|e| e.to_string())?;
+        }
+
         Ok(())
     }
 }
@@ -257,6 +336,10 @@ impl<'a> ExtXMedia<'a> {
             instream_id: None,
             characteristics: None,
             channels: None,
+            bit_depth: None,
+            sample_rate: None,
+            video_layout: None,
+            stable_rendition_id: None,
         }
     }
 
@@ -289,6 +372,37 @@ impl<'a> ExtXMedia<'a> {
     #[inline]
     pub fn builder() -> ExtXMediaBuilder<'a> { ExtXMediaBuilder::default() }
 
+    /// Returns `true`, if a client, that supports audio with up to
+    /// `max_bit_depth` bits and a sample rate of up to `max_sample_rate` Hz,
+    /// is able to play this rendition.
+    ///
+    /// A missing [`ExtXMedia::bit_depth`] or [`ExtXMedia::sample_rate`] is
+    /// assumed to be supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXMedia;
+    /// use hls_m3u8::types::MediaType;
+    ///
+    /// let media = ExtXMedia::builder()
+    ///     .media_type(MediaType::Audio)
+    ///     .group_id("lossless")
+    ///     .name("English (Hi-Res Lossless)")
+    ///     .bit_depth(24_u64)
+    ///     .sample_rate(192_000_u64)
+    ///     .build()?;
+    ///
+    /// assert!(media.is_supported_by(24, 192_000));
+    /// assert!(!media.is_supported_by(16, 48_000));
+    /// # Ok::<(), String>(())
+    /// ```
+    #[must_use]
+    pub fn is_supported_by(&self, max_bit_depth: u64, max_sample_rate: u64) -> bool {
+        self.bit_depth.map_or(true, |v| v <= max_bit_depth)
+            && self.sample_rate.map_or(true, |v| v <= max_sample_rate)
+    }
+
     /// Makes the struct independent of its lifetime, by taking ownership of all
     /// internal [`Cow`]s.
     ///
@@ -309,17 +423,24 @@ impl<'a> ExtXMedia<'a> {
             is_forced: self.is_forced,
             instream_id: self.instream_id,
             characteristics: self.characteristics.map(|v| Cow::Owned(v.into_owned())),
-            channels: self.channels,
+            channels: self.channels.map(Channels::into_owned),
+            bit_depth: self.bit_depth,
+            sample_rate: self.sample_rate,
+            video_layout: self.video_layout,
+            stable_rendition_id: self
+                .stable_rendition_id
+                .map(|v| Cow::Owned(v.into_owned())),
         }
     }
 }
 
 /// This tag requires either `ProtocolVersion::V1` or if there is an
-/// `instream_id` it requires it's version.
+/// `instream_id` or `channels` it requires their version.
 impl<'a> RequiredVersion for ExtXMedia<'a> {
     fn required_version(&self) -> ProtocolVersion {
         self.instream_id
-            .map_or(ProtocolVersion::V1, |i| i.required_version())
+            .required_version()
+            .max(self.channels.required_version())
     }
 }
 
@@ -367,6 +488,23 @@ impl<'a> fmt::Display for ExtXMedia<'a> {
         if let Some(value) = &self.channels {
             write!(f, ",CHANNELS={}", quote(value))?;
         }
+
+        if let Some(value) = &self.bit_depth {
+            write!(f, ",BIT-DEPTH={}", value)?;
+        }
+
+        if let Some(value) = &self.sample_rate {
+            write!(f, ",SAMPLE-RATE={}", value)?;
+        }
+
+        if let Some(value) = self.video_layout.as_ref().filter(|v| !v.is_empty()) {
+            write!(f, ",REQ-VIDEO-LAYOUT={}", quote(value))?;
+        }
+
+        if let Some(value) = &self.stable_rendition_id {
+            write!(f, ",STABLE-RENDITION-ID={}", quote(value))?;
+        }
+
         Ok(())
     }
 }
@@ -415,7 +553,27 @@ impl<'a> TryFrom<&'a str> for ExtXMedia<'a> {
                     builder.characteristics(unquote(value));
                 }
                 "CHANNELS" => {
-                    builder.channels(unquote(value).parse::<Channels>()?);
+                    builder.channels(Channels::try_from(unquote(value))?);
+                }
+                "BIT-DEPTH" => {
+                    builder.bit_depth(
+                        value
+                            .parse::<u64>()
+                            .map_err(|e| Error::parse_int(value, e))?,
+                    );
+                }
+                "SAMPLE-RATE" => {
+                    builder.sample_rate(
+                        value
+                            .parse::<u64>()
+                            .map_err(|e| Error::parse_int(value, e))?,
+                    );
+                }
+                "REQ-VIDEO-LAYOUT" => {
+                    builder.video_layout(unquote(value).parse::<VideoLayout>()?);
+                }
+                "STABLE-RENDITION-ID" => {
+                    builder.stable_rendition_id(unquote(value));
                 }
                 _ => {
                     // [6.3.1. General Client Responsibilities]
@@ -432,6 +590,7 @@ impl<'a> TryFrom<&'a str> for ExtXMedia<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::types::{AudioCoding, VideoChannelLayout};
     use pretty_assertions::assert_eq;
 
     macro_rules! generate_tests {
@@ -687,6 +846,87 @@ mod test {
                 "CHANNELS=\"2\""
             )
         },
+        {
+            ExtXMedia::builder()
+                .media_type(MediaType::Audio)
+                .group_id("atmos")
+                .language("en")
+                .name("English")
+                .channels(Channels::try_from("16/JOC").unwrap())
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-MEDIA:",
+                "TYPE=AUDIO,",
+                "GROUP-ID=\"atmos\",",
+                "LANGUAGE=\"en\",",
+                "NAME=\"English\",",
+                "CHANNELS=\"16/JOC\""
+            )
+        },
+        {
+            ExtXMedia::builder()
+                .media_type(MediaType::Audio)
+                .group_id("alac")
+                .language("en")
+                .name("English (Lossless)")
+                .uri("alac/prog_index.m3u8")
+                .channels(Channels::new(2))
+                .bit_depth(24_u64)
+                .sample_rate(96_000_u64)
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-MEDIA:",
+                "TYPE=AUDIO,",
+                "URI=\"alac/prog_index.m3u8\",",
+                "GROUP-ID=\"alac\",",
+                "LANGUAGE=\"en\",",
+                "NAME=\"English (Lossless)\",",
+                "CHANNELS=\"2\",",
+                "BIT-DEPTH=24,",
+                "SAMPLE-RATE=96000"
+            )
+        },
+        {
+            ExtXMedia::builder()
+                .media_type(MediaType::Video)
+                .group_id("mv-hevc")
+                .name("Main (stereo)")
+                .uri("stereo/prog_index.m3u8")
+                .video_layout(VideoLayout::from(vec![
+                    VideoChannelLayout::Stereo,
+                    VideoChannelLayout::Mono,
+                ]))
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-MEDIA:",
+                "TYPE=VIDEO,",
+                "URI=\"stereo/prog_index.m3u8\",",
+                "GROUP-ID=\"mv-hevc\",",
+                "NAME=\"Main (stereo)\",",
+                "REQ-VIDEO-LAYOUT=\"CH-STEREO,CH-MONO\""
+            )
+        },
+        {
+            ExtXMedia::builder()
+                .media_type(MediaType::Audio)
+                .group_id("binaural")
+                .language("en")
+                .name("English (binaural)")
+                .channels(Channels::try_from("2/-/BINAURAL").unwrap())
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-MEDIA:",
+                "TYPE=AUDIO,",
+                "GROUP-ID=\"binaural\",",
+                "LANGUAGE=\"en\",",
+                "NAME=\"English (binaural)\",",
+                "CHANNELS=\"2/-/BINAURAL\""
+            )
+        },
         {
             ExtXMedia::builder()
                 .media_type(MediaType::Subtitles)
@@ -737,6 +977,24 @@ mod test {
                 "INSTREAM-ID=\"CC2\""
             )
         },
+        {
+            ExtXMedia::builder()
+                .media_type(MediaType::Audio)
+                .group_id("audio")
+                .name("English")
+                .uri("eng/prog_index.m3u8")
+                .stable_rendition_id("audio-en_stereo")
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-MEDIA:",
+                "TYPE=AUDIO,",
+                "URI=\"eng/prog_index.m3u8\",",
+                "GROUP-ID=\"audio\",",
+                "NAME=\"English\",",
+                "STABLE-RENDITION-ID=\"audio-en_stereo\""
+            )
+        },
         {
             ExtXMedia::new(MediaType::Audio, "foo", "bar"),
             "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"foo\",NAME=\"bar\""
@@ -767,6 +1025,61 @@ mod test {
             ExtXMedia::try_from("#EXT-X-MEDIA:TYPE=AUDIO,FORCED=YES").is_err(),
             true
         );
+
+        assert_eq!(
+            ExtXMedia::try_from(concat!(
+                "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"English\",",
+                "STABLE-RENDITION-ID=\"en (stereo)\""
+            ))
+            .is_err(),
+            true
+        );
+
+        assert_eq!(
+            ExtXMedia::try_from(concat!(
+                "#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID=\"video\",NAME=\"Main\",",
+                "SAMPLE-RATE=48000"
+            ))
+            .is_err(),
+            true
+        );
+
+        assert_eq!(
+            ExtXMedia::try_from(concat!(
+                "#EXT-X-MEDIA:TYPE=SUBTITLES,URI=\"subs.m3u8\",GROUP-ID=\"subs\",",
+                "NAME=\"English\",BIT-DEPTH=16"
+            ))
+            .is_err(),
+            true
+        );
+
+        assert_eq!(
+            ExtXMedia::try_from(concat!(
+                "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"English\",",
+                "REQ-VIDEO-LAYOUT=\"CH-MONO\""
+            ))
+            .is_err(),
+            true
+        );
+
+        assert!(ExtXMedia::builder()
+            .media_type(MediaType::Video)
+            .group_id("video")
+            .name("Main")
+            .video_layout(VideoLayout::new())
+            .build()
+            .is_err());
+
+        let mut channels = Channels::new(16);
+        channels.set_audio_coding(Some(AudioCoding::ObjectBased(vec![])));
+
+        assert!(ExtXMedia::builder()
+            .media_type(MediaType::Audio)
+            .group_id("audio")
+            .name("English")
+            .channels(channels)
+            .build()
+            .is_err());
     }
 
     #[test]
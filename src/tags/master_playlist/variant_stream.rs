@@ -423,7 +423,7 @@ impl<'a> PartialEq<&VariantStream<'a>> for VariantStream<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::InStreamId;
+    use crate::types::{InStreamId, VideoChannelLayout};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -442,6 +442,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_video_layout() {
+        let variant_stream = VariantStream::ExtXStreamInf {
+            uri: "stereo/index.m3u8".into(),
+            frame_rate: None,
+            audio: None,
+            subtitles: None,
+            closed_captions: None,
+            stream_data: StreamData::builder()
+                .bandwidth(20_000_000)
+                .codecs(&["hvc1.6.40.L120.B0"])
+                .video_layout(vec![VideoChannelLayout::Stereo, VideoChannelLayout::Mono])
+                .build()
+                .unwrap(),
+        };
+
+        let input = concat!(
+            "#EXT-X-STREAM-INF:",
+            "BANDWIDTH=20000000,",
+            "CODECS=\"hvc1.6.40.L120.B0\",",
+            "REQ-VIDEO-LAYOUT=\"CH-STEREO,CH-MONO\"\n",
+            "stereo/index.m3u8"
+        );
+
+        assert_eq!(variant_stream.to_string(), input.to_string());
+        assert_eq!(VariantStream::try_from(input).unwrap(), variant_stream);
+        assert!(variant_stream.video_layout().unwrap().is_stereo());
+    }
+
     #[test]
     fn test_is_associated() {
         let mut variant_stream = VariantStream::ExtXStreamInf {
@@ -10,14 +10,14 @@ use derive_builder::Builder;
 use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
-use crate::types::{ProtocolVersion, Value};
+use crate::types::{Cue, Decimal, ProtocolVersion, Value};
 use crate::utils::{quote, tag, unquote};
 use crate::{Error, RequiredVersion};
 
 /// The [`ExtXDateRange`] tag associates a date range (i.e., a range of time
 /// defined by a starting and ending date) with a set of attribute/value pairs.
 #[derive(ShortHand, Builder, Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
-#[builder(setter(into))]
+#[builder(setter(into), build_fn(validate = "Self::validate"))]
 #[shorthand(enable(must_use, into))]
 pub struct ExtXDateRange<'a> {
     /// A string that uniquely identifies an [`ExtXDateRange`] in the playlist.
@@ -81,6 +81,18 @@ pub struct ExtXDateRange<'a> {
     #[cfg(not(feature = "chrono"))]
     #[builder(setter(strip_option), default)]
     end_date: Option<Cow<'a, str>>,
+    /// This field indicates when to trigger an action associated with the
+    /// [`ExtXDateRange`] (for example the playback of an interstitial).
+    ///
+    /// ## Note
+    ///
+    /// This field is optional and must not contain both [`CueTrigger::Pre`]
+    /// and [`CueTrigger::Post`].
+    ///
+    /// [`CueTrigger::Pre`]: crate::types::CueTrigger::Pre
+    /// [`CueTrigger::Post`]: crate::types::CueTrigger::Post
+    #[builder(setter(strip_option), default)]
+    cue: Option<Cue>,
     /// The duration of the [`ExtXDateRange`]. A single instant in time (e.g.,
     /// crossing a finish line) should be represented with a duration of 0.
     ///
@@ -196,6 +208,35 @@ impl<'a> ExtXDateRangeBuilder<'a> {
 
         self
     }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(Some(cue)) = &self.cue {
+            cue.validate().map_err(|e| e.to_string())?;
+        }
+
+        if self.end_on_next.unwrap_or(false) {
+            if !matches!(self.class, Some(Some(_))) {
+                return Err(Error::missing_attribute("CLASS").to_string());
+            } else if matches!(self.duration, Some(Some(_))) {
+                return Err(Error::unexpected_attribute("DURATION").to_string());
+            } else if matches!(self.end_date, Some(Some(_))) {
+                return Err(Error::unexpected_attribute("END-DATE").to_string());
+            }
+        }
+
+        #[cfg(feature = "chrono")]
+        {
+            if let (Some(Some(start_date)), Some(Some(end_date))) =
+                (&self.start_date, &self.end_date)
+            {
+                if end_date < start_date {
+                    return Err(Error::custom("END-DATE must not be before START-DATE").to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a> ExtXDateRange<'a> {
@@ -246,6 +287,7 @@ let date_range = ExtXDateRange::new("id", "2010-02-19T14:54:23.031+08:00");
             #[cfg(not(feature = "chrono"))]
             start_date: Some(start_date.into()),
             end_date: None,
+            cue: None,
             duration: None,
             planned_duration: None,
             scte35_cmd: None,
@@ -279,7 +321,6 @@ let date_range = ExtXDateRange::builder()
     .scte35_cmd("0xFC002F0000000000FF2")
     .scte35_out("0xFC002F0000000000FF0")
     .scte35_in("0xFC002F0000000000FF1")
-    .end_on_next(true)
     .build()?;
 # Ok::<(), String>(())
 ```
@@ -304,7 +345,6 @@ let date_range = ExtXDateRange::builder()
     .scte35_cmd("0xFC002F0000000000FF2")
     .scte35_out("0xFC002F0000000000FF0")
     .scte35_in("0xFC002F0000000000FF1")
-    .end_on_next(true)
     .build()?;
 # Ok::<(), String>(())
 ```
@@ -333,6 +373,7 @@ let date_range = ExtXDateRange::builder()
             end_date: self.end_date.map(|v| Cow::Owned(v.into_owned())),
             #[cfg(feature = "chrono")]
             end_date: self.end_date,
+            cue: self.cue,
             scte35_cmd: self.scte35_cmd.map(|v| Cow::Owned(v.into_owned())),
             scte35_out: self.scte35_out.map(|v| Cow::Owned(v.into_owned())),
             scte35_in: self.scte35_in.map(|v| Cow::Owned(v.into_owned())),
@@ -347,6 +388,140 @@ let date_range = ExtXDateRange::builder()
             planned_duration: self.planned_duration,
         }
     }
+
+    /// Combines an update of the same date range (an [`ExtXDateRange`] with
+    /// the same [`ExtXDateRange::id`]) into this one.
+    ///
+    /// Attributes, that are only present in `other`, are added to `self`.
+    /// Attributes, that appear in both tags, must have the same value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtXDateRange;
+    /// use core::convert::TryFrom;
+    /// use std::time::Duration;
+    ///
+    /// let mut date_range = ExtXDateRange::try_from(concat!(
+    ///     "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2014-03-05T11:15:00Z\",",
+    ///     "PLANNED-DURATION=30"
+    /// ))?;
+    ///
+    /// date_range.merge(&ExtXDateRange::try_from(
+    ///     "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2014-03-05T11:15:00Z\",DURATION=29.5",
+    /// )?)?;
+    ///
+    /// assert_eq!(date_range.planned_duration, Some(Duration::from_secs(30)));
+    /// assert_eq!(date_range.duration, Some(Duration::from_secs_f64(29.5)));
+    ///
+    /// // the start date must not change:
+    /// assert!(date_range
+    ///     .merge(&ExtXDateRange::try_from(
+    ///         "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2014-03-05T11:16:00Z\""
+    ///     )?)
+    ///     .is_err());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the ids are different, if an
+    /// attribute has a different value in both tags or if the combined range
+    /// is invalid (for example a range with [`ExtXDateRange::end_on_next`] and
+    /// a [`ExtXDateRange::duration`]). In this case `self` is not modified.
+    pub fn merge(&mut self, other: &Self) -> crate::Result<()> {
+        fn conflict(id: &str, attribute: &str) -> Error {
+            Error::custom(format!(
+                "the attribute {} of the date range {:?} has conflicting values",
+                attribute, id
+            ))
+        }
+
+        fn merge_field<T: PartialEq + Clone>(
+            id: &str,
+            attribute: &str,
+            left: &mut Option<T>,
+            right: Option<&T>,
+        ) -> crate::Result<()> {
+            match (&left, right) {
+                (Some(left), Some(right)) if left != right => Err(conflict(id, attribute)),
+                (None, Some(right)) => {
+                    *left = Some((*right).clone());
+                    Ok(())
+                }
+                _ => Ok(()),
+            }
+        }
+
+        if self.id != other.id {
+            return Err(Error::custom(format!(
+                "can not merge the date ranges {:?} and {:?} with different ids",
+                self.id, other.id
+            )));
+        }
+
+        // the result is built in a copy, so `self` is left untouched on error:
+        let mut result = self.clone();
+        let id = &self.id;
+
+        merge_field(id, "CLASS", &mut result.class, other.class.as_ref())?;
+        merge_field(id, "START-DATE", &mut result.start_date, other.start_date.as_ref())?;
+        merge_field(id, "END-DATE", &mut result.end_date, other.end_date.as_ref())?;
+        merge_field(id, "CUE", &mut result.cue, other.cue.as_ref())?;
+        merge_field(id, "DURATION", &mut result.duration, other.duration.as_ref())?;
+        merge_field(
+            id,
+            "PLANNED-DURATION",
+            &mut result.planned_duration,
+            other.planned_duration.as_ref(),
+        )?;
+        merge_field(id, "SCTE35-CMD", &mut result.scte35_cmd, other.scte35_cmd.as_ref())?;
+        merge_field(id, "SCTE35-OUT", &mut result.scte35_out, other.scte35_out.as_ref())?;
+        merge_field(id, "SCTE35-IN", &mut result.scte35_in, other.scte35_in.as_ref())?;
+
+        for (key, value) in &other.client_attributes {
+            let entry = result
+                .client_attributes
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+
+            if entry != value {
+                return Err(conflict(id, key));
+            }
+        }
+
+        result.end_on_next |= other.end_on_next;
+        result.validate()?;
+
+        *self = result;
+        Ok(())
+    }
+
+    /// Checks the rules, that apply to a single [`ExtXDateRange`], but can
+    /// be violated by merging multiple tags.
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        // https://tools.ietf.org/html/rfc8216#section-4.3.2.7
+        if self.end_on_next {
+            if self.class.is_none() {
+                return Err(Error::missing_attribute("CLASS"));
+            } else if self.duration.is_some() {
+                return Err(Error::unexpected_attribute("DURATION"));
+            } else if self.end_date.is_some() {
+                return Err(Error::unexpected_attribute("END-DATE"));
+            }
+        }
+
+        #[cfg(feature = "chrono")]
+        {
+            if let (Some(start_date), Some(end_date)) = (&self.start_date, &self.end_date) {
+                if end_date < start_date {
+                    return Err(Error::custom("END-DATE must not be before START-DATE"));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// This tag requires [`ProtocolVersion::V1`].
@@ -364,6 +539,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
         let mut class = None;
         let mut start_date = None;
         let mut end_date = None;
+        let mut cue = None;
         let mut duration = None;
         let mut planned_duration = None;
         let mut scte35_cmd = None;
@@ -397,6 +573,7 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
                         end_date = Some(unquote(value))
                     }
                 }
+                "CUE" => cue = Some(unquote(value).parse::<Cue>()?),
                 "DURATION" => {
                     duration = Some(Duration::from_secs_f64(
                         value.parse().map_err(|e| Error::parse_float(value, e))?,
@@ -428,7 +605,16 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
                             ));
                         }
 
-                        client_attributes.insert(Cow::Borrowed(key), Value::try_from(value)?);
+                        // the offsets of an `Interstitial` are kept exact, instead of
+                        // rounding them to a `Float`:
+                        let value = match (key, value.parse::<Decimal>()) {
+                            ("X-RESUME-OFFSET" | "X-PLAYOUT-LIMIT", Ok(decimal)) => {
+                                Value::Decimal(decimal)
+                            }
+                            _ => Value::try_from(value)?,
+                        };
+
+                        client_attributes.insert(Cow::Borrowed(key), value);
                     } else {
                         // [6.3.1. General Client Responsibilities]
                         // > ignore any attribute/value pair with an
@@ -440,14 +626,6 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
 
         let id = id.ok_or_else(|| Error::missing_value("ID"))?;
 
-        if end_on_next && class.is_none() {
-            return Err(Error::missing_attribute("CLASS"));
-        } else if end_on_next && duration.is_some() {
-            return Err(Error::unexpected_attribute("DURATION"));
-        } else if end_on_next && end_date.is_some() {
-            return Err(Error::unexpected_attribute("END-DATE"));
-        }
-
         // TODO: verify this without chrono?
         // https://tools.ietf.org/html/rfc8216#section-4.3.2.7
         #[cfg(feature = "chrono")]
@@ -465,11 +643,12 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
             }
         }
 
-        Ok(Self {
+        let result = Self {
             id,
             class,
             start_date,
             end_date,
+            cue,
             duration,
             planned_duration,
             scte35_cmd,
@@ -477,7 +656,10 @@ impl<'a> TryFrom<&'a str> for ExtXDateRange<'a> {
             scte35_in,
             end_on_next,
             client_attributes,
-        })
+        };
+
+        result.validate()?;
+        Ok(result)
     }
 }
 
@@ -506,6 +688,11 @@ impl<'a> fmt::Display for ExtXDateRange<'a> {
             }
         }
 
+        // an empty list can not be parsed, so it is not written:
+        if let Some(value) = self.cue.as_ref().filter(|v| !v.is_empty()) {
+            write!(f, ",CUE={}", quote(value))?;
+        }
+
         if let Some(value) = &self.end_date {
             #[cfg(feature = "chrono")]
             {
@@ -557,7 +744,7 @@ impl<'a> fmt::Display for ExtXDateRange<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::types::Float;
+    use crate::types::{CueTrigger, Float};
     #[cfg(feature = "chrono")]
     use chrono::offset::TimeZone;
     use pretty_assertions::assert_eq;
@@ -592,6 +779,14 @@ mod test {
                     "END-ON-NEXT=YES"
                 ))
                 .is_err());
+
+                assert!(ExtXDateRange::try_from(concat!(
+                    "#EXT-X-DATERANGE:",
+                    "ID=\"test_id\",",
+                    "START-DATE=\"2014-03-05T11:15:00Z\",",
+                    "CUE=\"PRE,POST\""
+                ))
+                .is_err());
             }
         }
     }
@@ -675,6 +870,184 @@ mod test {
                 "X-CUSTOM=45.3",
             )
         },
+        {
+            ExtXDateRange::builder()
+                .id("preroll")
+                .class("com.apple.hls.interstitial")
+                .start_date({
+                    #[cfg(feature = "chrono")]
+                    {
+                        FixedOffset::east(0).ymd(2014, 3, 5).and_hms(11, 15, 0)
+                    }
+                    #[cfg(not(feature = "chrono"))]
+                    {
+                        "2014-03-05T11:15:00Z"
+                    }
+                })
+                .cue(Cue::from(vec![CueTrigger::Pre, CueTrigger::Once]))
+                .insert_client_attribute("X-ASSET-URI", "http://example.com/ad.m3u8".to_string())
+                .build()
+                .unwrap(),
+            concat!(
+                "#EXT-X-DATERANGE:",
+                "ID=\"preroll\",",
+                "CLASS=\"com.apple.hls.interstitial\",",
+                "START-DATE=\"2014-03-05T11:15:00Z\",",
+                "CUE=\"PRE,ONCE\",",
+                "X-ASSET-URI=\"http://example.com/ad.m3u8\"",
+            )
+        },
+    }
+
+    #[test]
+    fn test_builder_end_on_next() {
+        let mut builder = ExtXDateRange::builder();
+        builder.id("id").end_on_next(true);
+
+        assert!(builder.build().is_err());
+        assert!(builder.class("class").build().is_ok());
+        assert!(builder
+            .clone()
+            .duration(Duration::from_secs(1))
+            .build()
+            .is_err());
+        assert!(builder
+            .end_date({
+                #[cfg(feature = "chrono")]
+                {
+                    FixedOffset::east(0).ymd(2014, 3, 5).and_hms(11, 15, 0)
+                }
+                #[cfg(not(feature = "chrono"))]
+                {
+                    "2014-03-05T11:15:00Z"
+                }
+            })
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_end_date_before_start_date() {
+        assert!(ExtXDateRange::builder()
+            .id("id")
+            .start_date(FixedOffset::east(0).ymd(2014, 3, 5).and_hms(11, 15, 0))
+            .end_date(FixedOffset::east(0).ymd(2014, 3, 5).and_hms(11, 14, 59))
+            .build()
+            .is_err());
+
+        assert!(ExtXDateRange::try_from(concat!(
+            "#EXT-X-DATERANGE:",
+            "ID=\"id\",",
+            "START-DATE=\"2014-03-05T11:15:00Z\",",
+            "END-DATE=\"2014-03-05T11:14:59Z\""
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut date_range = ExtXDateRange::try_from(concat!(
+            "#EXT-X-DATERANGE:",
+            "ID=\"ad\",",
+            "CLASS=\"com.example.ad\",",
+            "START-DATE=\"2014-03-05T11:15:00Z\",",
+            "PLANNED-DURATION=30,",
+            "X-AD-ID=\"1\""
+        ))
+        .unwrap();
+
+        date_range
+            .merge(
+                &ExtXDateRange::try_from(concat!(
+                    "#EXT-X-DATERANGE:",
+                    "ID=\"ad\",",
+                    "START-DATE=\"2014-03-05T11:15:00Z\",",
+                    "DURATION=30,",
+                    "SCTE35-IN=0xFC01,",
+                    "X-AD-ID=\"1\",",
+                    "X-AD-NAME=\"name\""
+                ))
+                .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            date_range,
+            ExtXDateRange::try_from(concat!(
+                "#EXT-X-DATERANGE:",
+                "ID=\"ad\",",
+                "CLASS=\"com.example.ad\",",
+                "START-DATE=\"2014-03-05T11:15:00Z\",",
+                "DURATION=30,",
+                "PLANNED-DURATION=30,",
+                "SCTE35-IN=0xFC01,",
+                "X-AD-ID=\"1\",",
+                "X-AD-NAME=\"name\""
+            ))
+            .unwrap()
+        );
+
+        let copy = date_range.clone();
+
+        // different id
+        assert!(date_range
+            .merge(&ExtXDateRange::try_from("#EXT-X-DATERANGE:ID=\"other\"").unwrap())
+            .is_err());
+        // conflicting attribute
+        assert!(date_range
+            .merge(&ExtXDateRange::try_from("#EXT-X-DATERANGE:ID=\"ad\",DURATION=31").unwrap())
+            .is_err());
+        // conflicting client attribute
+        assert!(date_range
+            .merge(&ExtXDateRange::try_from("#EXT-X-DATERANGE:ID=\"ad\",X-AD-ID=\"2\"").unwrap())
+            .is_err());
+        // END-ON-NEXT is not allowed together with DURATION
+        assert!(date_range
+            .merge(
+                &ExtXDateRange::try_from(
+                    "#EXT-X-DATERANGE:ID=\"ad\",CLASS=\"com.example.ad\",END-ON-NEXT=YES"
+                )
+                .unwrap()
+            )
+            .is_err());
+
+        // the range is not modified by a failed merge
+        assert_eq!(date_range, copy);
+    }
+
+    #[test]
+    fn test_builder_cue() {
+        assert!(ExtXDateRange::builder()
+            .id("invalid")
+            .cue(Cue::from(vec![CueTrigger::Pre, CueTrigger::Post]))
+            .build()
+            .is_err());
+
+        let mut date_range = ExtXDateRange::new("id", {
+            #[cfg(feature = "chrono")]
+            {
+                FixedOffset::east(0).ymd(2014, 3, 5).and_hms(11, 15, 0)
+            }
+            #[cfg(not(feature = "chrono"))]
+            {
+                "2014-03-05T11:15:00Z"
+            }
+        });
+
+        // an empty cue is not written:
+        date_range.set_cue(Some(Cue::new()));
+        assert_eq!(
+            date_range.to_string(),
+            "#EXT-X-DATERANGE:ID=\"id\",START-DATE=\"2014-03-05T11:15:00Z\"".to_string()
+        );
+
+        assert!(ExtXDateRange::builder()
+            .id("id")
+            .start_date(date_range.start_date().unwrap().clone())
+            .cue(Cue::new())
+            .build()
+            .is_err());
     }
 
     #[test]
@@ -5,17 +5,23 @@ use std::time::Duration;
 
 use derive_more::AsRef;
 
-use crate::types::ProtocolVersion;
+use crate::types::{Decimal, ProtocolVersion};
 use crate::utils::tag;
 use crate::{Error, RequiredVersion};
 
 /// Specifies the duration of a [`Media Segment`].
 ///
+/// The duration is stored as an exact [`Decimal`], which keeps the literal
+/// value of the tag (for example `#EXTINF:6.006,` or `#EXTINF:1.920,`), so
+/// it is written back unchanged and can be summed up without rounding
+/// errors.
+///
 /// [`Media Segment`]: crate::media_segment::MediaSegment
 #[derive(AsRef, Default, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ExtInf<'a> {
     #[as_ref]
     duration: Duration,
+    exact_duration: Decimal,
     title: Option<Cow<'a, str>>,
 }
 
@@ -36,6 +42,7 @@ impl<'a> ExtInf<'a> {
     pub const fn new(duration: Duration) -> Self {
         Self {
             duration,
+            exact_duration: Decimal::from_duration(duration),
             title: None,
         }
     }
@@ -54,6 +61,7 @@ impl<'a> ExtInf<'a> {
     pub fn with_title<T: Into<Cow<'a, str>>>(duration: Duration, title: T) -> Self {
         Self {
             duration,
+            exact_duration: Decimal::from_duration(duration),
             title: Some(title.into()),
         }
     }
@@ -89,6 +97,64 @@ impl<'a> ExtInf<'a> {
     /// ```
     pub fn set_duration(&mut self, value: Duration) -> &mut Self {
         self.duration = value;
+        self.exact_duration = Decimal::from_duration(value);
+        self
+    }
+
+    /// Returns the duration rounded to the nearest integer number of seconds,
+    /// which must not exceed the [`ExtXTargetDuration`].
+    ///
+    /// The rounding is done without floating point numbers, so a duration of
+    /// exactly `x.5` seconds is always rounded up.
+    ///
+    /// [`ExtXTargetDuration`]: crate::tags::ExtXTargetDuration
+    // https://tools.ietf.org/html/rfc8216#section-4.3.3.1
+    pub(crate) fn rounded_duration(&self) -> Duration {
+        let duration = self.duration;
+
+        Duration::from_secs(
+            duration.as_secs() + u64::from(duration.subsec_nanos() >= 500_000_000),
+        )
+    }
+
+    /// Returns the exact duration of the associated media segment, as it
+    /// appears in the playlist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtInf;
+    /// use core::convert::TryFrom;
+    /// use hls_m3u8::types::Decimal;
+    ///
+    /// let ext_inf = ExtInf::try_from("#EXTINF:1.920,")?;
+    ///
+    /// assert_eq!(ext_inf.exact_duration(), Decimal::new(1920, 3));
+    /// assert_eq!(ext_inf.to_string(), "#EXTINF:1.920,");
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub const fn exact_duration(&self) -> Decimal { self.exact_duration }
+
+    /// Sets the exact duration of the associated media segment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::tags::ExtInf;
+    /// use hls_m3u8::types::Decimal;
+    /// use std::time::Duration;
+    ///
+    /// let mut ext_inf = ExtInf::new(Duration::from_secs(5));
+    ///
+    /// ext_inf.set_exact_duration(Decimal::new(6006, 3));
+    ///
+    /// assert_eq!(ext_inf.duration(), Duration::from_millis(6006));
+    /// assert_eq!(ext_inf.to_string(), "#EXTINF:6.006,");
+    /// ```
+    pub fn set_exact_duration(&mut self, value: Decimal) -> &mut Self {
+        self.duration = value.as_duration();
+        self.exact_duration = value;
         self
     }
 
@@ -136,16 +202,17 @@ impl<'a> ExtInf<'a> {
     pub fn into_owned(self) -> ExtInf<'static> {
         ExtInf {
             duration: self.duration,
+            exact_duration: self.exact_duration,
             title: self.title.map(|v| Cow::Owned(v.into_owned())),
         }
     }
 }
 
-/// This tag requires [`ProtocolVersion::V1`], if the duration does not have
-/// nanoseconds, otherwise it requires [`ProtocolVersion::V3`].
+/// This tag requires [`ProtocolVersion::V1`], if the duration is an integer,
+/// otherwise it requires [`ProtocolVersion::V3`].
 impl<'a> RequiredVersion for ExtInf<'a> {
     fn required_version(&self) -> ProtocolVersion {
-        if self.duration.subsec_nanos() == 0 {
+        if self.exact_duration.scale() == 0 {
             ProtocolVersion::V1
         } else {
             ProtocolVersion::V3
@@ -156,7 +223,7 @@ impl<'a> RequiredVersion for ExtInf<'a> {
 impl<'a> fmt::Display for ExtInf<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", Self::PREFIX)?;
-        write!(f, "{},", self.duration.as_secs_f64())?;
+        write!(f, "{},", self.exact_duration)?;
 
         if let Some(value) = &self.title {
             write!(f, "{}", value)?;
@@ -171,12 +238,21 @@ impl<'a> TryFrom<&'a str> for ExtInf<'a> {
     fn try_from(input: &'a str) -> Result<Self, Self::Error> {
         let mut input = tag(input, Self::PREFIX)?.splitn(2, ',');
 
-        let duration = input.next().unwrap();
-        let duration = Duration::from_secs_f64(
-            duration
-                .parse()
-                .map_err(|e| Error::parse_float(duration, e))?,
-        );
+        let input_duration = input.next().unwrap();
+
+        // a literal, that is not a plain decimal number (like `1e1`), is
+        // parsed as a float and can not be kept:
+        let exact_duration = {
+            if let Ok(value) = input_duration.parse::<Decimal>() {
+                value
+            } else {
+                Decimal::from_duration(Duration::from_secs_f64(
+                    input_duration
+                        .parse()
+                        .map_err(|e| Error::parse_float(input_duration, e))?,
+                ))
+            }
+        };
 
         let title = input
             .next()
@@ -184,7 +260,11 @@ impl<'a> TryFrom<&'a str> for ExtInf<'a> {
             .filter(|value| !value.is_empty())
             .map(|v| Cow::Borrowed(v));
 
-        Ok(Self { duration, title })
+        Ok(Self {
+            duration: exact_duration.as_duration(),
+            exact_duration,
+            title,
+        })
     }
 }
 
@@ -192,6 +272,16 @@ impl<'a> From<Duration> for ExtInf<'a> {
     fn from(value: Duration) -> Self { Self::new(value) }
 }
 
+impl<'a> From<Decimal> for ExtInf<'a> {
+    fn from(value: Decimal) -> Self {
+        Self {
+            duration: value.as_duration(),
+            exact_duration: value,
+            title: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -245,10 +335,50 @@ mod test {
             ExtInf::with_title(Duration::from_secs(5), "title")
         );
 
+        assert_eq!(
+            ExtInf::try_from("#EXTINF:1e1,").unwrap(),
+            ExtInf::new(Duration::from_secs(10))
+        );
+
         assert!(ExtInf::try_from("#EXTINF:").is_err());
         assert!(ExtInf::try_from("#EXTINF:garbage").is_err());
     }
 
+    #[test]
+    fn test_exact_duration() {
+        for input in &["#EXTINF:6.006,", "#EXTINF:1.920,", "#EXTINF:10.0,title"] {
+            assert_eq!(ExtInf::try_from(*input).unwrap().to_string(), *input);
+        }
+
+        let ext_inf = ExtInf::try_from("#EXTINF:1.920,").unwrap();
+
+        assert_eq!(ext_inf.duration(), Duration::from_millis(1920));
+        assert_eq!(ext_inf.exact_duration(), Decimal::new(1920, 3));
+        assert_ne!(ext_inf, ExtInf::new(Duration::from_millis(1920)));
+        assert_eq!(ext_inf, ExtInf::from(Decimal::new(1920, 3)));
+
+        assert_eq!(
+            ExtInf::try_from("#EXTINF:10.0,").unwrap().required_version(),
+            ProtocolVersion::V3
+        );
+    }
+
+    #[test]
+    fn test_rounded_duration() {
+        assert_eq!(
+            ExtInf::new(Duration::from_millis(10_499)).rounded_duration(),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            ExtInf::new(Duration::from_millis(10_500)).rounded_duration(),
+            Duration::from_secs(11)
+        );
+        assert_eq!(
+            ExtInf::new(Duration::from_secs(0)).rounded_duration(),
+            Duration::from_secs(0)
+        );
+    }
+
     #[test]
     fn test_title() {
         assert_eq!(ExtInf::new(Duration::from_secs(5)).title(), &None);
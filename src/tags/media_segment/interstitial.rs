@@ -0,0 +1,584 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::ops::Deref;
+use std::time::Duration;
+
+use derive_builder::Builder;
+use shorthand::ShortHand;
+
+use crate::tags::ExtXDateRange;
+use crate::types::{Decimal, Value};
+use crate::Error;
+
+/// A typed view of an [`ExtXDateRange`] with the `CLASS`
+/// `"com.apple.hls.interstitial"`, which schedules the playback of an
+/// interstitial asset (for example an ad) in the primary asset.
+///
+/// The attributes, that are not specific to interstitials (`ID`,
+/// `START-DATE`, `DURATION`, `CUE`, ...), are stored in the underlying
+/// [`Interstitial::date_range`], which can be accessed through [`Deref`].
+///
+/// An [`Interstitial`] must have a [`ExtXDateRange::start_date`] and exactly
+/// one of [`Interstitial::asset_uri`] and [`Interstitial::asset_list`].
+///
+/// # Example
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use hls_m3u8::tags::{ExtXDateRange, Interstitial};
+///
+/// let date_range = ExtXDateRange::try_from(concat!(
+///     "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+///     "START-DATE=\"2020-01-02T21:55:44Z\",DURATION=15.0,",
+///     "X-ASSET-URI=\"http://example.com/ad1.m3u8\",X-RESUME-OFFSET=0,",
+///     "X-RESTRICT=\"SKIP,JUMP\""
+/// ))?;
+///
+/// let interstitial = Interstitial::try_from(date_range)?;
+///
+/// assert_eq!(interstitial.id(), "ad1");
+/// assert_eq!(
+///     interstitial.asset_uri(),
+///     Some(&"http://example.com/ad1.m3u8".into())
+/// );
+/// assert!(interstitial.restrict_skip && interstitial.restrict_jump);
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+#[allow(clippy::struct_excessive_bools)]
+#[derive(ShortHand, Builder, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[builder(setter(into), build_fn(validate = "Self::validate"))]
+#[shorthand(enable(must_use, into))]
+pub struct Interstitial<'a> {
+    /// The underlying [`ExtXDateRange`], which contains all attributes, that
+    /// are not specific to interstitials.
+    ///
+    /// ## Note
+    ///
+    /// This field is required.
+    date_range: ExtXDateRange<'a>,
+    /// The URI of the interstitial asset (`X-ASSET-URI`).
+    ///
+    /// ## Note
+    ///
+    /// Either this field or [`Interstitial::asset_list`] is required.
+    #[builder(setter(strip_option), default)]
+    asset_uri: Option<Cow<'a, str>>,
+    /// The URI of a JSON object, that lists the interstitial assets
+    /// (`X-ASSET-LIST`).
+    ///
+    /// ## Note
+    ///
+    /// Either this field or [`Interstitial::asset_uri`] is required.
+    #[builder(setter(strip_option), default)]
+    asset_list: Option<Cow<'a, str>>,
+    /// The offset from the [`ExtXDateRange::start_date`] at which the playback
+    /// of the primary asset resumes after the interstitial
+    /// (`X-RESUME-OFFSET`).
+    ///
+    /// ## Note
+    ///
+    /// This field is optional. If it is missing, the duration of the
+    /// interstitial is used instead.
+    #[builder(setter(strip_option), default)]
+    #[shorthand(enable(skip))]
+    pub resume_offset: Option<Duration>,
+    /// The maximum duration of the interstitial playback (`X-PLAYOUT-LIMIT`).
+    ///
+    /// ## Note
+    ///
+    /// This field is optional.
+    #[builder(setter(strip_option), default)]
+    #[shorthand(enable(skip))]
+    pub playout_limit: Option<Duration>,
+    /// The client should locate the segment boundary closest to the
+    /// [`ExtXDateRange::start_date`] and leave the primary asset there
+    /// (`X-SNAP="OUT"`).
+    #[builder(default)]
+    #[shorthand(enable(skip))]
+    pub snap_out: bool,
+    /// The client should locate the segment boundary closest to the scheduled
+    /// resumption point and return to the primary asset there
+    /// (`X-SNAP="IN"`).
+    #[builder(default)]
+    #[shorthand(enable(skip))]
+    pub snap_in: bool,
+    /// The client must not allow the user to seek forward through the
+    /// interstitial (`X-RESTRICT="SKIP"`).
+    #[builder(default)]
+    #[shorthand(enable(skip))]
+    pub restrict_skip: bool,
+    /// The client must not allow the user to seek past the interstitial
+    /// without playing it (`X-RESTRICT="JUMP"`).
+    #[builder(default)]
+    #[shorthand(enable(skip))]
+    pub restrict_jump: bool,
+}
+
+impl<'a> InterstitialBuilder<'a> {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(date_range) = &self.date_range {
+            Interstitial::validate_parts(
+                date_range,
+                matches!(self.asset_uri, Some(Some(_))),
+                matches!(self.asset_list, Some(Some(_))),
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Interstitial<'a> {
+    /// The `CLASS` of an [`ExtXDateRange`], that describes an interstitial.
+    pub const CLASS: &'static str = "com.apple.hls.interstitial";
+
+    /// Returns a builder for an [`Interstitial`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::convert::TryFrom;
+    /// use hls_m3u8::tags::{ExtXDateRange, Interstitial};
+    /// use std::time::Duration;
+    ///
+    /// let interstitial = Interstitial::builder()
+    ///     .date_range(ExtXDateRange::try_from(
+    ///         "#EXT-X-DATERANGE:ID=\"preroll\",START-DATE=\"2020-01-02T21:55:44Z\"",
+    ///     )?)
+    ///     .asset_list("http://example.com/assets.json")
+    ///     .resume_offset(Duration::from_secs(0))
+    ///     .build()?;
+    ///
+    /// let date_range = ExtXDateRange::from(interstitial);
+    ///
+    /// assert_eq!(
+    ///     date_range.to_string(),
+    ///     concat!(
+    ///         "#EXT-X-DATERANGE:ID=\"preroll\",CLASS=\"com.apple.hls.interstitial\",",
+    ///         "START-DATE=\"2020-01-02T21:55:44Z\",",
+    ///         "X-ASSET-LIST=\"http://example.com/assets.json\",X-RESUME-OFFSET=0"
+    ///     )
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn builder() -> InterstitialBuilder<'a> { InterstitialBuilder::default() }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> Interstitial<'static> {
+        Interstitial {
+            date_range: self.date_range.into_owned(),
+            asset_uri: self.asset_uri.map(|v| Cow::Owned(v.into_owned())),
+            asset_list: self.asset_list.map(|v| Cow::Owned(v.into_owned())),
+            resume_offset: self.resume_offset,
+            playout_limit: self.playout_limit,
+            snap_out: self.snap_out,
+            snap_in: self.snap_in,
+            restrict_skip: self.restrict_skip,
+            restrict_jump: self.restrict_jump,
+        }
+    }
+
+    fn validate_parts(
+        date_range: &ExtXDateRange<'_>,
+        has_asset_uri: bool,
+        has_asset_list: bool,
+    ) -> crate::Result<()> {
+        if let Some(class) = date_range.class() {
+            if class != Self::CLASS {
+                return Err(Error::custom(format!(
+                    "expected the class {:?}, but found {:?}",
+                    Self::CLASS,
+                    class
+                )));
+            }
+        }
+
+        if date_range.start_date().is_none() {
+            return Err(Error::missing_attribute("START-DATE"));
+        }
+
+        if has_asset_uri && has_asset_list {
+            return Err(Error::custom(
+                "`X-ASSET-URI` and `X-ASSET-LIST` must not be specified together",
+            ));
+        } else if !has_asset_uri && !has_asset_list {
+            return Err(Error::missing_attribute("X-ASSET-URI"));
+        }
+
+        if let Some(cue) = date_range.cue() {
+            cue.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Deref for Interstitial<'a> {
+    type Target = ExtXDateRange<'a>;
+
+    fn deref(&self) -> &Self::Target { &self.date_range }
+}
+
+fn parse_duration(key: &str, value: &Value<'_>) -> crate::Result<Duration> {
+    match value {
+        // the float is converted through its string representation, to prevent
+        // rounding errors (like 59.993 -> 59.99300003)
+        Value::Float(float) if float.as_f32() >= 0.0 => {
+            Ok(Duration::from_secs_f64(
+                float
+                    .to_string()
+                    .parse()
+                    .map_err(|e| Error::parse_float(float, e))?,
+            ))
+        }
+        Value::Decimal(decimal) => Ok(decimal.as_duration()),
+        _ => {
+            Err(Error::custom(format!(
+                "expected a positive number for `{}`, but found {}",
+                key, value
+            )))
+        }
+    }
+}
+
+fn parse_list<'b>(key: &str, value: &'b Value<'_>) -> crate::Result<Vec<&'b str>> {
+    match value {
+        Value::String(list) => Ok(list.split(',').map(str::trim).collect()),
+        _ => {
+            Err(Error::custom(format!(
+                "expected a quoted string for `{}`, but found {}",
+                key, value
+            )))
+        }
+    }
+}
+
+fn join(list: &[(bool, &str)]) -> Option<Value<'static>> {
+    let list = list
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, value)| *value)
+        .collect::<Vec<_>>();
+
+    if list.is_empty() {
+        None
+    } else {
+        Some(Value::String(Cow::Owned(list.join(","))))
+    }
+}
+
+impl<'a> TryFrom<ExtXDateRange<'a>> for Interstitial<'a> {
+    type Error = Error;
+
+    fn try_from(mut date_range: ExtXDateRange<'a>) -> Result<Self, Self::Error> {
+        if date_range.class().map(AsRef::as_ref) != Some(Self::CLASS) {
+            return Err(Error::custom(format!(
+                "an interstitial requires the class {:?}",
+                Self::CLASS
+            )));
+        }
+
+        let attributes = &mut date_range.client_attributes;
+
+        let asset_uri = match attributes.remove("X-ASSET-URI") {
+            Some(Value::String(value)) => Some(value),
+            Some(_) => return Err(Error::custom("`X-ASSET-URI` must be a quoted string")),
+            None => None,
+        };
+
+        let asset_list = match attributes.remove("X-ASSET-LIST") {
+            Some(Value::String(value)) => Some(value),
+            Some(_) => return Err(Error::custom("`X-ASSET-LIST` must be a quoted string")),
+            None => None,
+        };
+
+        let resume_offset = attributes
+            .remove("X-RESUME-OFFSET")
+            .map(|value| parse_duration("X-RESUME-OFFSET", &value))
+            .transpose()?;
+
+        let playout_limit = attributes
+            .remove("X-PLAYOUT-LIMIT")
+            .map(|value| parse_duration("X-PLAYOUT-LIMIT", &value))
+            .transpose()?;
+
+        let mut snap_out = false;
+        let mut snap_in = false;
+
+        if let Some(value) = attributes.remove("X-SNAP") {
+            for snap in parse_list("X-SNAP", &value)? {
+                match snap {
+                    "OUT" => snap_out = true,
+                    "IN" => snap_in = true,
+                    _ => return Err(Error::custom(format!("invalid `X-SNAP`: {:?}", snap))),
+                }
+            }
+        }
+
+        let mut restrict_skip = false;
+        let mut restrict_jump = false;
+
+        if let Some(value) = attributes.remove("X-RESTRICT") {
+            for restriction in parse_list("X-RESTRICT", &value)? {
+                match restriction {
+                    "SKIP" => restrict_skip = true,
+                    "JUMP" => restrict_jump = true,
+                    _ => {
+                        return Err(Error::custom(format!(
+                            "invalid `X-RESTRICT`: {:?}",
+                            restriction
+                        )));
+                    }
+                }
+            }
+        }
+
+        Self::validate_parts(&date_range, asset_uri.is_some(), asset_list.is_some())?;
+
+        Ok(Self {
+            date_range,
+            asset_uri,
+            asset_list,
+            resume_offset,
+            playout_limit,
+            snap_out,
+            snap_in,
+            restrict_skip,
+            restrict_jump,
+        })
+    }
+}
+
+impl<'a> From<Interstitial<'a>> for ExtXDateRange<'a> {
+    fn from(value: Interstitial<'a>) -> Self {
+        let mut date_range = value.date_range;
+
+        date_range.set_class(Some(Interstitial::CLASS));
+
+        let attributes = &mut date_range.client_attributes;
+
+        if let Some(uri) = value.asset_uri {
+            attributes.insert("X-ASSET-URI".into(), Value::String(uri));
+        }
+
+        if let Some(uri) = value.asset_list {
+            attributes.insert("X-ASSET-LIST".into(), Value::String(uri));
+        }
+
+        if let Some(offset) = value.resume_offset {
+            attributes.insert(
+                "X-RESUME-OFFSET".into(),
+                Value::Decimal(Decimal::from_duration(offset)),
+            );
+        }
+
+        if let Some(limit) = value.playout_limit {
+            attributes.insert(
+                "X-PLAYOUT-LIMIT".into(),
+                Value::Decimal(Decimal::from_duration(limit)),
+            );
+        }
+
+        if let Some(snap) = join(&[(value.snap_out, "OUT"), (value.snap_in, "IN")]) {
+            attributes.insert("X-SNAP".into(), snap);
+        }
+
+        if let Some(restrict) = join(&[
+            (value.restrict_skip, "SKIP"),
+            (value.restrict_jump, "JUMP"),
+        ]) {
+            attributes.insert("X-RESTRICT".into(), restrict);
+        }
+
+        date_range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Cue, CueTrigger};
+    use pretty_assertions::assert_eq;
+
+    fn date_range(input: &str) -> ExtXDateRange<'_> { ExtXDateRange::try_from(input).unwrap() }
+
+    #[test]
+    fn test_try_from() {
+        let interstitial = Interstitial::try_from(date_range(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-02T21:55:44Z\",CUE=\"POST,ONCE\",",
+            "X-ASSET-URI=\"http://example.com/ad1.m3u8\",X-COM-EXAMPLE-AD-ID=\"XYZ\",",
+            "X-PLAYOUT-LIMIT=59.993,X-SNAP=\"OUT,IN\",X-RESTRICT=\"JUMP\""
+        )))
+        .unwrap();
+
+        assert_eq!(
+            interstitial.asset_uri(),
+            Some(&"http://example.com/ad1.m3u8".into())
+        );
+        assert_eq!(interstitial.asset_list(), None);
+        assert_eq!(interstitial.resume_offset, None);
+        assert_eq!(
+            interstitial.playout_limit,
+            Some(Duration::from_millis(59_993))
+        );
+        assert!(interstitial.snap_out && interstitial.snap_in);
+        assert!(!interstitial.restrict_skip && interstitial.restrict_jump);
+        assert_eq!(
+            interstitial.cue(),
+            Some(&Cue::from(vec![CueTrigger::Post, CueTrigger::Once]))
+        );
+        // unrelated client attributes are kept in the date range:
+        assert_eq!(
+            interstitial
+                .client_attributes
+                .keys()
+                .map(AsRef::as_ref)
+                .collect::<Vec<&str>>(),
+            vec!["X-COM-EXAMPLE-AD-ID"]
+        );
+
+        assert!(Interstitial::try_from(date_range(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",START-DATE=\"2020-01-02T21:55:44Z\",",
+            "X-ASSET-URI=\"http://example.com/ad1.m3u8\""
+        )))
+        .is_err());
+
+        assert!(Interstitial::try_from(date_range(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-02T21:55:44Z\""
+        )))
+        .is_err());
+
+        assert!(Interstitial::try_from(date_range(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-02T21:55:44Z\",",
+            "X-ASSET-URI=\"http://example.com/ad1.m3u8\",",
+            "X-ASSET-LIST=\"http://example.com/ad1.json\""
+        )))
+        .is_err());
+
+        assert!(Interstitial::try_from(date_range(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+            "X-ASSET-URI=\"http://example.com/ad1.m3u8\""
+        )))
+        .is_err());
+
+        assert!(Interstitial::try_from(date_range(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-02T21:55:44Z\",",
+            "X-ASSET-URI=\"http://example.com/ad1.m3u8\",X-SNAP=\"MIDDLE\""
+        )))
+        .is_err());
+
+        assert!(Interstitial::try_from(date_range(concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-02T21:55:44Z\",",
+            "X-ASSET-URI=\"http://example.com/ad1.m3u8\",X-RESUME-OFFSET=\"0\""
+        )))
+        .is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = concat!(
+            "#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-02T21:55:44Z\",CUE=\"PRE\",DURATION=15,",
+            "X-ASSET-URI=\"http://example.com/ad1.m3u8\",X-PLAYOUT-LIMIT=30.5,",
+            "X-RESTRICT=\"SKIP,JUMP\",X-RESUME-OFFSET=0,X-SNAP=\"OUT\""
+        );
+
+        let interstitial = Interstitial::try_from(date_range(input)).unwrap();
+
+        assert_eq!(ExtXDateRange::from(interstitial).to_string(), input);
+    }
+
+    #[test]
+    fn test_builder() {
+        let interstitial = Interstitial::builder()
+            .date_range(date_range(
+                "#EXT-X-DATERANGE:ID=\"ad2\",START-DATE=\"2020-01-02T21:55:44Z\"",
+            ))
+            .asset_uri("http://example.com/ad2.m3u8")
+            .restrict_skip(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            ExtXDateRange::from(interstitial).to_string(),
+            concat!(
+                "#EXT-X-DATERANGE:ID=\"ad2\",CLASS=\"com.apple.hls.interstitial\",",
+                "START-DATE=\"2020-01-02T21:55:44Z\",",
+                "X-ASSET-URI=\"http://example.com/ad2.m3u8\",X-RESTRICT=\"SKIP\""
+            )
+        );
+
+        // long offsets are written without losing precision:
+        let interstitial = Interstitial::builder()
+            .date_range(date_range(
+                "#EXT-X-DATERANGE:ID=\"ad3\",START-DATE=\"2020-01-02T21:55:44Z\"",
+            ))
+            .asset_uri("http://example.com/ad3.m3u8")
+            .resume_offset(Duration::new(86_400, 123_456_789))
+            .build()
+            .unwrap();
+
+        let output = ExtXDateRange::from(interstitial);
+
+        assert_eq!(
+            output.to_string(),
+            concat!(
+                "#EXT-X-DATERANGE:ID=\"ad3\",CLASS=\"com.apple.hls.interstitial\",",
+                "START-DATE=\"2020-01-02T21:55:44Z\",",
+                "X-ASSET-URI=\"http://example.com/ad3.m3u8\",",
+                "X-RESUME-OFFSET=86400.123456789"
+            )
+        );
+        assert_eq!(
+            Interstitial::try_from(output).unwrap().resume_offset,
+            Some(Duration::new(86_400, 123_456_789))
+        );
+
+        // and parsed without losing precision:
+        let input = concat!(
+            "#EXT-X-DATERANGE:ID=\"ad3\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2020-01-02T21:55:44Z\",",
+            "X-ASSET-URI=\"http://example.com/ad3.m3u8\",",
+            "X-PLAYOUT-LIMIT=3600.000000001,X-RESUME-OFFSET=86400.123456789"
+        );
+
+        let interstitial = Interstitial::try_from(date_range(input)).unwrap();
+
+        assert_eq!(
+            interstitial.resume_offset,
+            Some(Duration::new(86_400, 123_456_789))
+        );
+        assert_eq!(interstitial.playout_limit, Some(Duration::new(3600, 1)));
+        assert_eq!(ExtXDateRange::from(interstitial).to_string(), input);
+
+        assert!(Interstitial::builder()
+            .date_range(date_range(
+                "#EXT-X-DATERANGE:ID=\"ad2\",START-DATE=\"2020-01-02T21:55:44Z\"",
+            ))
+            .build()
+            .is_err());
+
+        assert!(Interstitial::builder()
+            .date_range(date_range(concat!(
+                "#EXT-X-DATERANGE:ID=\"ad2\",CLASS=\"com.example.chapter\",",
+                "START-DATE=\"2020-01-02T21:55:44Z\""
+            )))
+            .asset_uri("http://example.com/ad2.m3u8")
+            .build()
+            .is_err());
+    }
+}
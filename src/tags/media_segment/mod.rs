@@ -2,6 +2,7 @@ pub(crate) mod byte_range;
 pub(crate) mod date_range;
 pub(crate) mod discontinuity;
 pub(crate) mod inf;
+pub(crate) mod interstitial;
 pub(crate) mod key;
 pub(crate) mod map;
 pub(crate) mod program_date_time;
@@ -10,6 +11,7 @@ pub use byte_range::*;
 pub use date_range::ExtXDateRange;
 pub(crate) use discontinuity::*;
 pub use inf::*;
+pub use interstitial::Interstitial;
 pub use key::ExtXKey;
 pub use map::*;
 pub use program_date_time::*;
@@ -0,0 +1,1418 @@
+//! Views, that map the contents of a [`MediaPlaylist`] onto a timeline.
+use std::cmp::Ordering;
+#[cfg(feature = "chrono")]
+use std::collections::HashMap;
+#[cfg(feature = "chrono")]
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset};
+#[cfg(feature = "chrono")]
+use stable_vec::StableVec;
+
+#[cfg(feature = "chrono")]
+use crate::tags::{ExtXDateRange, ExtXProgramDateTime};
+use crate::tags::{ExtXKey, ExtXMap};
+use crate::types::Decimal;
+#[cfg(feature = "chrono")]
+use crate::Error;
+use crate::{MediaPlaylist, MediaSegment};
+
+#[cfg(feature = "chrono")]
+fn to_chrono(duration: Duration) -> crate::Result<chrono::Duration> {
+    chrono::Duration::from_std(duration).map_err(Error::custom)
+}
+
+/// A [`MediaSegment`] together with its position on the [`Timeline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelineSegment<'p, 'a> {
+    /// The [`MediaSegment`].
+    pub segment: &'p MediaSegment<'a>,
+    /// The offset of the start of the segment from the start of the playlist.
+    pub offset: Duration,
+    /// The discontinuity sequence number of the segment, which is the
+    /// [`MediaPlaylist::discontinuity_sequence`] plus the number of
+    /// [`MediaSegment::has_discontinuity`] up to and including this segment.
+    pub discontinuity_sequence: usize,
+    /// The wall clock time of the start of the segment, which is either the
+    /// [`MediaSegment::program_date_time`] or interpolated from the closest
+    /// [`ExtXProgramDateTime`] in the same discontinuity sequence.
+    ///
+    /// This is `None`, if there is no [`ExtXProgramDateTime`] between the
+    /// surrounding discontinuities.
+    ///
+    /// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+    #[cfg(feature = "chrono")]
+    pub start_date: Option<DateTime<FixedOffset>>,
+}
+
+impl<'p, 'a> TimelineSegment<'p, 'a> {
+    /// Returns the [`MediaSegment::number`] of the segment.
+    #[must_use]
+    pub fn number(&self) -> usize { self.segment.number() }
+
+    /// Returns the duration of the segment.
+    #[must_use]
+    pub const fn duration(&self) -> Duration { self.segment.duration.duration() }
+
+    /// Returns the offset of the end of the segment from the start of the
+    /// playlist.
+    #[must_use]
+    pub fn end(&self) -> Duration { self.offset + self.duration() }
+
+    /// Returns the wall clock time of the end of the segment.
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn end_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.start_date
+            .and_then(|date| Some(date + chrono::Duration::from_std(self.duration()).ok()?))
+    }
+}
+
+/// A view of the [`MediaPlaylist::segments`], which knows the start offset
+/// and the wall clock time of every [`MediaSegment`] and can find a segment
+/// by its offset, date or number with a binary search.
+///
+/// This can be created with [`MediaPlaylist::timeline`].
+///
+/// # Example
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use hls_m3u8::MediaPlaylist;
+/// use std::time::Duration;
+///
+/// let playlist = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-MEDIA-SEQUENCE:5\n",
+///     "#EXTINF:10,\n",
+///     "a.ts\n",
+///     "#EXTINF:8,\n",
+///     "b.ts\n",
+///     "#EXT-X-DISCONTINUITY\n",
+///     "#EXTINF:10,\n",
+///     "c.ts\n",
+/// ))?;
+///
+/// let timeline = playlist.timeline();
+///
+/// assert_eq!(timeline.segment_at(Duration::from_secs(12)).unwrap().number(), 6);
+/// assert_eq!(timeline.start_of(7), Some(Duration::from_secs(18)));
+/// assert_eq!(timeline.segments()[2].discontinuity_sequence, 1);
+/// assert_eq!(timeline.duration(), Duration::from_secs(28));
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline<'p, 'a> {
+    segments: Vec<TimelineSegment<'p, 'a>>,
+    /// The indices of the segments with a known date.
+    #[cfg(feature = "chrono")]
+    dated: Vec<usize>,
+}
+
+impl<'p, 'a> Timeline<'p, 'a> {
+    pub(crate) fn new(playlist: &'p MediaPlaylist<'a>) -> Self {
+        let mut segments = Vec::with_capacity(playlist.segments.num_elements());
+        let mut offset = Decimal::default();
+        let mut discontinuity_sequence = playlist.discontinuity_sequence;
+
+        for segment in playlist.segments.values() {
+            if segment.has_discontinuity {
+                discontinuity_sequence += 1;
+            }
+
+            segments.push(TimelineSegment {
+                segment,
+                offset: offset.as_duration(),
+                discontinuity_sequence,
+                #[cfg(feature = "chrono")]
+                start_date: None,
+            });
+
+            // the exact durations are summed up to prevent drift:
+            offset += segment.duration.exact_duration();
+        }
+
+        #[cfg(feature = "chrono")]
+        {
+            interpolate_dates(&mut segments);
+
+            let dated = (0..segments.len())
+                .filter(|&i| segments[i].start_date.is_some())
+                .collect();
+
+            Self { segments, dated }
+        }
+
+        #[cfg(not(feature = "chrono"))]
+        {
+            Self { segments }
+        }
+    }
+
+    /// Returns all segments in the order of the playlist.
+    #[must_use]
+    pub fn segments(&self) -> &[TimelineSegment<'p, 'a>] { &self.segments }
+
+    /// Returns the duration of all segments.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.segments
+            .last()
+            .map_or_else(Default::default, TimelineSegment::end)
+    }
+
+    /// Returns the segment, that contains the provided offset from the start
+    /// of the playlist (`offset..end`).
+    ///
+    /// This function returns `None`, if the offset is after the end of the
+    /// playlist.
+    #[must_use]
+    pub fn segment_at(&self, offset: Duration) -> Option<&TimelineSegment<'p, 'a>> {
+        let index = self
+            .segments
+            .binary_search_by(|segment| {
+                if segment.end() <= offset {
+                    Ordering::Less
+                } else if segment.offset > offset {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .ok()?;
+
+        // there might be multiple segments with a duration of 0 at the offset:
+        self.segments[..=index]
+            .iter()
+            .rev()
+            .take_while(|segment| segment.end() > offset)
+            .last()
+    }
+
+    /// Returns the segment with the provided [`MediaSegment::number`].
+    #[must_use]
+    pub fn get(&self, number: usize) -> Option<&TimelineSegment<'p, 'a>> {
+        let index = self
+            .segments
+            .binary_search_by_key(&number, TimelineSegment::number)
+            .ok()?;
+
+        self.segments.get(index)
+    }
+
+    /// Returns the offset of the start of the segment with the provided
+    /// [`MediaSegment::number`] from the start of the playlist.
+    #[must_use]
+    pub fn start_of(&self, number: usize) -> Option<Duration> {
+        self.get(number).map(|segment| segment.offset)
+    }
+
+    /// Returns the segment, whose wall clock time contains the provided date.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::DateTime;
+    /// use core::convert::TryFrom;
+    /// use hls_m3u8::MediaPlaylist;
+    ///
+    /// let playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "a.ts\n",
+    ///     "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:50Z\n",
+    ///     "#EXTINF:10,\n",
+    ///     "b.ts\n",
+    /// ))?;
+    ///
+    /// let timeline = playlist.timeline();
+    /// let date = |value| DateTime::parse_from_rfc3339(value).unwrap();
+    ///
+    /// assert_eq!(timeline.segments()[0].start_date, Some(date("2020-01-02T21:55:40Z")));
+    /// assert_eq!(
+    ///     timeline
+    ///         .segment_at_date(date("2020-01-02T21:55:45Z"))
+    ///         .map(|s| s.number()),
+    ///     Some(0)
+    /// );
+    /// assert!(timeline.segment_at_date(date("2020-01-02T21:56:00Z")).is_none());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn segment_at_date(
+        &self,
+        date: DateTime<FixedOffset>,
+    ) -> Option<&TimelineSegment<'p, 'a>> {
+        // the index of the first segment, that starts after the date:
+        let position = match self.dated.binary_search_by(|&i| {
+            match self.segments[i].start_date {
+                Some(start_date) if start_date <= date => Ordering::Less,
+                _ => Ordering::Greater,
+            }
+        }) {
+            Ok(value) | Err(value) => value,
+        };
+
+        let segment = &self.segments[*self.dated.get(position.checked_sub(1)?)?];
+
+        if segment.end_date()? > date {
+            Some(segment)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the offset from the start of the playlist at the provided wall
+    /// clock time.
+    ///
+    /// This function returns `None`, if no segment contains the date.
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn offset_at_date(&self, date: DateTime<FixedOffset>) -> Option<Duration> {
+        let segment = self.segment_at_date(date)?;
+
+        Some(segment.offset + (date - segment.start_date?).to_std().ok()?)
+    }
+
+    /// Returns the wall clock time at the provided offset from the start of
+    /// the playlist.
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn date_at(&self, offset: Duration) -> Option<DateTime<FixedOffset>> {
+        let segment = self.segment_at(offset)?;
+
+        let elapsed = offset.checked_sub(segment.offset)?;
+
+        Some(segment.start_date? + chrono::Duration::from_std(elapsed).ok()?)
+    }
+}
+
+/// A continuous part of a [`MediaPlaylist`], which is delimited by
+/// [`MediaSegment::has_discontinuity`].
+///
+/// This is returned by the [`Periods`] iterator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Period<'p, 'a> {
+    /// The discontinuity sequence number of the period, which is the
+    /// [`MediaPlaylist::discontinuity_sequence`] plus the number of
+    /// discontinuities in front of the period.
+    pub discontinuity_sequence: usize,
+    /// The offset of the start of the period from the start of the playlist.
+    pub start: Duration,
+    /// The duration of all segments of the period.
+    pub duration: Duration,
+    /// The [`ExtXMap`], that applies to the first segment of the period.
+    ///
+    /// An [`ExtXMap`] applies to every segment until the next [`ExtXMap`], so
+    /// this might have been declared in a previous period.
+    pub map: Option<&'p ExtXMap<'a>>,
+    /// The [`ExtXKey`]s, that apply to the first segment of the period.
+    pub keys: &'p [ExtXKey<'a>],
+    /// All segments of the period.
+    pub segments: Vec<&'p MediaSegment<'a>>,
+}
+
+/// An iterator over the [`Period`]s of a [`MediaPlaylist`].
+///
+/// This can be created with [`MediaPlaylist::periods`].
+///
+/// # Example
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use hls_m3u8::MediaPlaylist;
+/// use std::time::Duration;
+///
+/// let playlist = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-DISCONTINUITY-SEQUENCE:4\n",
+///     "#EXT-X-MAP:URI=\"main.mp4\"\n",
+///     "#EXTINF:10,\n",
+///     "a.m4s\n",
+///     "#EXTINF:10,\n",
+///     "b.m4s\n",
+///     "#EXT-X-DISCONTINUITY\n",
+///     "#EXT-X-MAP:URI=\"ad.mp4\"\n",
+///     "#EXTINF:5,\n",
+///     "ad.m4s\n",
+/// ))?;
+///
+/// let periods = playlist.periods().collect::<Vec<_>>();
+///
+/// assert_eq!(periods.len(), 2);
+/// assert_eq!(periods[1].discontinuity_sequence, 5);
+/// assert_eq!(periods[1].start, Duration::from_secs(20));
+/// assert_eq!(periods[1].duration, Duration::from_secs(5));
+/// assert_eq!(periods[1].map.map(|map| map.uri().as_ref()), Some("ad.mp4"));
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`ExtXMap`]: crate::tags::ExtXMap
+#[derive(Debug, Clone)]
+pub struct Periods<'p, 'a> {
+    segments: Vec<&'p MediaSegment<'a>>,
+    position: usize,
+    discontinuity_sequence: usize,
+    offset: Decimal,
+    map: Option<&'p ExtXMap<'a>>,
+}
+
+impl<'p, 'a> Periods<'p, 'a> {
+    pub(crate) fn new(playlist: &'p MediaPlaylist<'a>) -> Self {
+        Self {
+            segments: playlist.segments.values().collect(),
+            position: 0,
+            discontinuity_sequence: playlist.discontinuity_sequence,
+            offset: Decimal::default(),
+            map: None,
+        }
+    }
+}
+
+impl<'p, 'a> Iterator for Periods<'p, 'a> {
+    type Item = Period<'p, 'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = *self.segments.get(self.position)?;
+
+        if first.has_discontinuity {
+            self.discontinuity_sequence += 1;
+        }
+
+        let end = self.segments[self.position + 1..]
+            .iter()
+            .position(|s| s.has_discontinuity)
+            .map_or(self.segments.len(), |i| self.position + 1 + i);
+
+        let segments = self.segments[self.position..end].to_vec();
+        let duration = segments
+            .iter()
+            .map(|s| s.duration.exact_duration())
+            .sum::<Decimal>();
+
+        let map = first.map.as_ref().or(self.map);
+
+        let result = Period {
+            discontinuity_sequence: self.discontinuity_sequence,
+            start: self.offset.as_duration(),
+            duration: duration.as_duration(),
+            map,
+            keys: &first.keys,
+            segments,
+        };
+
+        // the last map of the period applies to the following segments:
+        self.map = result
+            .segments
+            .iter()
+            .rev()
+            .find_map(|s| s.map.as_ref())
+            .or(map);
+        self.offset += duration;
+        self.position = end;
+
+        Some(result)
+    }
+}
+
+/// Assigns a date to every segment, which is either its own
+/// [`ExtXProgramDateTime`] or interpolated from the closest segment with an
+/// [`ExtXProgramDateTime`] in front of it (or after it, if there is none in
+/// front of it). The interpolation does not cross discontinuities.
+///
+/// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+#[cfg(feature = "chrono")]
+fn interpolate_dates(segments: &mut [TimelineSegment<'_, '_>]) {
+    // the dates are derived from the (exact) offsets of the segments, so the
+    // rounding errors of the single segments do not accumulate:
+    let elapsed = |from: Duration, to: Duration| {
+        to.checked_sub(from)
+            .and_then(|value| chrono::Duration::from_std(value).ok())
+    };
+
+    let mut start = 0;
+
+    while start < segments.len() {
+        // the end of the discontinuity sequence
+        let end = segments[start + 1..]
+            .iter()
+            .position(|s| s.segment.has_discontinuity)
+            .map_or(segments.len(), |i| start + 1 + i);
+
+        let period = &mut segments[start..end];
+
+        let first = period.iter().find_map(|s| {
+            s.segment
+                .program_date_time
+                .as_ref()
+                .map(|value| (s.offset, value.date_time))
+        });
+
+        if let Some(mut anchor) = first {
+            for segment in period {
+                if let Some(value) = &segment.segment.program_date_time {
+                    anchor = (segment.offset, value.date_time);
+                }
+
+                let (offset, date) = anchor;
+
+                // the segments in front of the first anchor are interpolated
+                // backwards:
+                segment.start_date = {
+                    if segment.offset < offset {
+                        elapsed(segment.offset, offset).map(|value| date - value)
+                    } else {
+                        elapsed(offset, segment.offset).map(|value| date + value)
+                    }
+                };
+            }
+        }
+
+        start = end;
+    }
+}
+
+/// Generates the [`ExtXProgramDateTime`] tags of a [`MediaPlaylist`] from a
+/// start date and the durations of the [`MediaSegment`]s.
+///
+/// The first segment and every segment with a discontinuity get a tag (or
+/// every segment, if [`ProgramDateTimeGenerator::every_segment`] is set).
+/// Segments, that already have an [`ExtXProgramDateTime`], keep it and the
+/// following dates are calculated from it.
+///
+/// # Example
+///
+/// ```
+/// use chrono::DateTime;
+/// use hls_m3u8::timeline::ProgramDateTimeGenerator;
+/// use hls_m3u8::{MediaPlaylist, MediaSegment};
+/// use std::time::Duration;
+///
+/// let mut playlist = MediaPlaylist::builder()
+///     .target_duration(Duration::from_secs(10))
+///     .segments(vec![
+///         MediaSegment::builder()
+///             .duration(Duration::from_secs(10))
+///             .uri("a.ts")
+///             .build()?,
+///         MediaSegment::builder()
+///             .duration(Duration::from_secs(10))
+///             .uri("b.ts")
+///             .build()?,
+///         MediaSegment::builder()
+///             .duration(Duration::from_secs(10))
+///             .has_discontinuity(true)
+///             .uri("c.ts")
+///             .build()?,
+///     ])
+///     .build()?;
+///
+/// playlist.generate_program_date_times(ProgramDateTimeGenerator::new(
+///     DateTime::parse_from_rfc3339("2020-01-02T21:55:40Z")?,
+/// ))?;
+///
+/// assert_eq!(
+///     playlist.to_string(),
+///     concat!(
+///         "#EXTM3U\n",
+///         "#EXT-X-TARGETDURATION:10\n",
+///         "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40.000Z\n",
+///         "#EXTINF:10,\n",
+///         "a.ts\n",
+///         "#EXTINF:10,\n",
+///         "b.ts\n",
+///         "#EXT-X-DISCONTINUITY\n",
+///         "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:56:00.000Z\n",
+///         "#EXTINF:10,\n",
+///         "c.ts\n",
+///     )
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramDateTimeGenerator {
+    /// The date of the first segment.
+    pub start: DateTime<FixedOffset>,
+    /// Adds an [`ExtXProgramDateTime`] to every segment, instead of only the
+    /// first segment and the segments after a discontinuity.
+    pub every_segment: bool,
+}
+
+#[cfg(feature = "chrono")]
+impl ProgramDateTimeGenerator {
+    /// Makes a new [`ProgramDateTimeGenerator`], which adds an
+    /// [`ExtXProgramDateTime`] to the first segment and to every segment with a
+    /// discontinuity.
+    #[must_use]
+    pub const fn new(start: DateTime<FixedOffset>) -> Self {
+        Self {
+            start,
+            every_segment: false,
+        }
+    }
+
+    /// Makes a new [`ProgramDateTimeGenerator`], which adds an
+    /// [`ExtXProgramDateTime`] to every segment.
+    #[must_use]
+    pub const fn every_segment(start: DateTime<FixedOffset>) -> Self {
+        Self {
+            start,
+            every_segment: true,
+        }
+    }
+
+    pub(crate) fn apply(&self, segments: &mut StableVec<MediaSegment<'_>>) -> crate::Result<()> {
+        // the date of the last tag and the (exact) time, that has passed since
+        // then:
+        let mut anchor = (self.start, Decimal::default());
+
+        for (i, segment) in segments.values_mut().enumerate() {
+            if let Some(value) = &segment.program_date_time {
+                anchor = (value.date_time, Decimal::default());
+            } else if i == 0 || segment.has_discontinuity || self.every_segment {
+                let date = anchor.0 + to_chrono(anchor.1.as_duration())?;
+
+                segment.program_date_time = Some(ExtXProgramDateTime::new(date));
+            }
+
+            anchor.1 += segment.duration.exact_duration();
+        }
+
+        Ok(())
+    }
+}
+
+/// An inconsistent [`ExtXProgramDateTime`] tag, which has been found by
+/// [`MediaPlaylist::program_date_time_issues`].
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProgramDateTimeIssue {
+    /// The date is before the date of a previous [`ExtXProgramDateTime`].
+    Backwards {
+        /// The [`MediaSegment::number`] of the segment with the tag.
+        number: usize,
+        /// The date of the previous tag.
+        previous: DateTime<FixedOffset>,
+        /// The date of the tag.
+        date: DateTime<FixedOffset>,
+    },
+    /// The date differs from the date, that has been calculated from the
+    /// previous [`ExtXProgramDateTime`] and the durations of the segments in
+    /// between, by more than the tolerance.
+    Drift {
+        /// The [`MediaSegment::number`] of the segment with the tag.
+        number: usize,
+        /// The calculated date.
+        expected: DateTime<FixedOffset>,
+        /// The date of the tag.
+        date: DateTime<FixedOffset>,
+    },
+}
+
+#[cfg(feature = "chrono")]
+impl fmt::Display for ProgramDateTimeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Backwards {
+                number,
+                previous,
+                date,
+            } => write!(
+                f,
+                "the program date time of segment {} ({}) is before the previous one ({})",
+                number,
+                date.to_rfc3339(),
+                previous.to_rfc3339()
+            ),
+            Self::Drift {
+                number,
+                expected,
+                date,
+            } => write!(
+                f,
+                "the program date time of segment {} ({}) does not match the segment durations ({})",
+                number,
+                date.to_rfc3339(),
+                expected.to_rfc3339()
+            ),
+        }
+    }
+}
+
+/// Compares every [`ExtXProgramDateTime`] with the previous one. A date
+/// after a discontinuity is only checked to not go backwards.
+#[cfg(feature = "chrono")]
+pub(crate) fn program_date_time_issues(
+    playlist: &MediaPlaylist<'_>,
+    tolerance: Duration,
+) -> Vec<ProgramDateTimeIssue> {
+    // a tolerance, that can not be represented, allows any drift:
+    let tolerance = chrono::Duration::from_std(tolerance).ok();
+
+    let mut result = vec![];
+    // the previous date, the time that has passed since then and whether
+    // there has been a discontinuity in between:
+    let mut previous: Option<(DateTime<FixedOffset>, Decimal, bool)> = None;
+
+    for segment in playlist.segments.values() {
+        if let Some((_, _, has_discontinuity)) = &mut previous {
+            *has_discontinuity |= segment.has_discontinuity;
+        }
+
+        if let Some(value) = &segment.program_date_time {
+            let date = value.date_time;
+
+            if let Some((previous_date, elapsed, has_discontinuity)) = previous {
+                let expected = to_chrono(elapsed.as_duration())
+                    .ok()
+                    .map(|elapsed| previous_date + elapsed);
+
+                if date < previous_date {
+                    result.push(ProgramDateTimeIssue::Backwards {
+                        number: segment.number(),
+                        previous: previous_date,
+                        date,
+                    });
+                } else if let (Some(expected), false) = (expected, has_discontinuity) {
+                    let difference = {
+                        if date > expected {
+                            date - expected
+                        } else {
+                            expected - date
+                        }
+                    };
+
+                    if tolerance.map_or(false, |tolerance| difference > tolerance) {
+                        result.push(ProgramDateTimeIssue::Drift {
+                            number: segment.number(),
+                            expected,
+                            date,
+                        });
+                    }
+                }
+            }
+
+            previous = Some((date, Decimal::default(), false));
+        }
+
+        if let Some((_, elapsed, _)) = &mut previous {
+            *elapsed += segment.duration.exact_duration();
+        }
+    }
+
+    result
+}
+
+/// The position of a [`MediaSegment`] on the wall clock.
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SegmentSpan {
+    number: usize,
+    offset: Duration,
+    duration: Duration,
+    start_date: DateTime<FixedOffset>,
+}
+
+/// An [`ExtXDateRange`] together with its effective start and end date.
+///
+/// All tags with the same [`ExtXDateRange::id`] are combined into one range
+/// (see [`ExtXDateRange::merge`]).
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledDateRange<'a> {
+    /// The combined [`ExtXDateRange`].
+    pub date_range: ExtXDateRange<'a>,
+    /// The [`ExtXDateRange::start_date`] or, if the range has none, the date
+    /// of the first [`MediaSegment`] in front of which the range appears.
+    ///
+    /// [`MediaSegment`]: crate::MediaSegment
+    pub start_date: DateTime<FixedOffset>,
+    /// The effective end of the range, which is derived from (in this order)
+    ///
+    /// - the [`ExtXDateRange::end_date`],
+    /// - the [`ExtXDateRange::duration`],
+    /// - the start of the next range with the same [`ExtXDateRange::class`],
+    ///   if [`ExtXDateRange::end_on_next`] is set,
+    /// - the [`ExtXDateRange::planned_duration`].
+    ///
+    /// If none of them is present, the end is unknown and the range is assumed
+    /// to last until the end of the playlist.
+    pub end_date: Option<DateTime<FixedOffset>>,
+    /// `true`, if the [`ScheduledDateRange::end_date`] has been derived from
+    /// the [`ExtXDateRange::planned_duration`] and is therefore only an
+    /// estimate.
+    pub is_planned: bool,
+}
+
+#[cfg(feature = "chrono")]
+impl<'a> ScheduledDateRange<'a> {
+    /// Returns `true`, if the range is active at the provided date.
+    ///
+    /// A range with a duration of 0 is only active at its start date.
+    #[must_use]
+    pub fn contains(&self, date: DateTime<FixedOffset>) -> bool {
+        self.start_date <= date
+            && match self.end_date {
+                Some(end_date) if end_date > self.start_date => date < end_date,
+                Some(_) => date == self.start_date,
+                None => true,
+            }
+    }
+
+    /// Returns `true`, if the range overlaps the half-open interval
+    /// `start..end`.
+    #[must_use]
+    pub fn overlaps(&self, start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> bool {
+        self.start_date < end
+            && match self.end_date {
+                Some(end_date) if end_date > self.start_date => start < end_date,
+                Some(_) => start <= self.start_date,
+                None => true,
+            }
+    }
+}
+
+/// All [`ExtXDateRange`]s of a [`MediaPlaylist`] mapped onto the wall clock
+/// with the help of the [`ExtXProgramDateTime`] tags of the playlist.
+///
+/// This can be created with [`MediaPlaylist::date_range_timeline`].
+///
+/// # Example
+///
+/// ```
+/// use chrono::DateTime;
+/// use core::convert::TryFrom;
+/// use hls_m3u8::MediaPlaylist;
+/// use std::time::Duration;
+///
+/// let playlist = MediaPlaylist::try_from(concat!(
+///     "#EXTM3U\n",
+///     "#EXT-X-TARGETDURATION:10\n",
+///     "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+///     "#EXTINF:10,\n",
+///     "a.ts\n",
+///     "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2020-01-02T21:55:50Z\",PLANNED-DURATION=10\n",
+///     "#EXTINF:10,\n",
+///     "b.ts\n",
+///     "#EXTINF:10,\n",
+///     "c.ts\n",
+/// ))?;
+///
+/// let timeline = playlist.date_range_timeline()?;
+///
+/// assert!(timeline.at_offset(Duration::from_secs(5)).is_empty());
+/// assert_eq!(timeline.at_offset(Duration::from_secs(15))[0].date_range.id(), "ad");
+/// assert_eq!(timeline.for_segment(1).len(), 1);
+/// assert!(timeline
+///     .at_date(DateTime::parse_from_rfc3339("2020-01-02T21:56:00Z").unwrap())
+///     .is_empty());
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`ExtXProgramDateTime`]: crate::tags::ExtXProgramDateTime
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateRangeTimeline<'a> {
+    ranges: Vec<ScheduledDateRange<'a>>,
+    segments: Vec<SegmentSpan>,
+}
+
+#[cfg(feature = "chrono")]
+impl<'a> DateRangeTimeline<'a> {
+    pub(crate) fn new(playlist: &MediaPlaylist<'a>) -> crate::Result<Self> {
+        let timeline = playlist.timeline();
+        let date_ranges = playlist.merged_date_ranges()?;
+
+        // only the segments with a known date can be mapped onto the wall clock:
+        let dated = timeline
+            .segments()
+            .iter()
+            .filter_map(|s| s.start_date.map(|start_date| (s, start_date)))
+            .collect::<Vec<_>>();
+
+        if dated.is_empty() && !date_ranges.is_empty() {
+            return Err(Error::custom(
+                "the date ranges can not be mapped onto the timeline without an `EXT-X-PROGRAM-DATE-TIME` tag",
+            ));
+        }
+
+        // the first segment in front of which a date range appears:
+        let mut first_segment = HashMap::new();
+
+        for (segment, start_date) in &dated {
+            for date_range in &segment.segment.date_ranges {
+                first_segment
+                    .entry(date_range.id().clone())
+                    .or_insert(*start_date);
+            }
+        }
+
+        let spans = dated
+            .iter()
+            .map(|(segment, start_date)| SegmentSpan {
+                number: segment.number(),
+                offset: segment.offset,
+                duration: segment.duration(),
+                start_date: *start_date,
+            })
+            .collect();
+
+        let mut ranges = vec![];
+
+        for date_range in date_ranges {
+            let start_date = {
+                if let Some(value) = date_range.start_date() {
+                    value
+                } else {
+                    first_segment
+                        .get(date_range.id())
+                        .copied()
+                        .ok_or_else(Error::invalid_input)?
+                }
+            };
+
+            let mut is_planned = false;
+
+            let end_date = {
+                if let Some(value) = date_range.end_date() {
+                    Some(value)
+                } else if let Some(value) = date_range.duration {
+                    Some(start_date + to_chrono(value)?)
+                } else if date_range.end_on_next {
+                    // resolved below, when all start dates are known
+                    None
+                } else if let Some(value) = date_range.planned_duration {
+                    is_planned = true;
+                    Some(start_date + to_chrono(value)?)
+                } else {
+                    None
+                }
+            };
+
+            ranges.push(ScheduledDateRange {
+                date_range,
+                start_date,
+                end_date,
+                is_planned,
+            });
+        }
+
+        // END-ON-NEXT: the range ends at the start of the following range with
+        // the same class.
+        //
+        // https://tools.ietf.org/html/rfc8216#section-4.3.2.7.1
+        for i in 0..ranges.len() {
+            if !ranges[i].date_range.end_on_next {
+                continue;
+            }
+
+            let class = ranges[i].date_range.class();
+            let start_date = ranges[i].start_date;
+
+            let next = ranges
+                .iter()
+                .filter(|r| r.date_range.class() == class && r.start_date > start_date)
+                .map(|r| r.start_date)
+                .min();
+
+            ranges[i].end_date = next;
+        }
+
+        ranges.sort_by_key(|range| range.start_date);
+
+        Ok(Self {
+            ranges,
+            segments: spans,
+        })
+    }
+
+    /// Returns all ranges ordered by their start date.
+    #[must_use]
+    pub fn ranges(&self) -> &[ScheduledDateRange<'a>] { &self.ranges }
+
+    /// Returns all ranges, that are active at the provided date.
+    #[must_use]
+    pub fn at_date(&self, date: DateTime<FixedOffset>) -> Vec<&ScheduledDateRange<'a>> {
+        self.ranges.iter().filter(|r| r.contains(date)).collect()
+    }
+
+    /// Returns all ranges, that are active at the provided offset from the
+    /// start of the playlist.
+    ///
+    /// The offset is converted into a date with the help of the segment,
+    /// that contains the offset (an offset after the end of the playlist is
+    /// extrapolated from the last segment).
+    #[must_use]
+    pub fn at_offset(&self, offset: Duration) -> Vec<&ScheduledDateRange<'a>> {
+        let index = self
+            .segments
+            .iter()
+            .rposition(|span| span.offset <= offset)
+            .unwrap_or(0);
+
+        let date = self.segments.get(index).and_then(|span| {
+            offset
+                .checked_sub(span.offset)
+                .and_then(|value| chrono::Duration::from_std(value).ok())
+                .map(|value| span.start_date + value)
+        });
+
+        date.map_or_else(Vec::new, |date| self.at_date(date))
+    }
+
+    /// Returns all ranges, that overlap the [`MediaSegment`] with the provided
+    /// [`MediaSegment::number`].
+    ///
+    /// [`MediaSegment`]: crate::MediaSegment
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    #[must_use]
+    pub fn for_segment(&self, number: usize) -> Vec<&ScheduledDateRange<'a>> {
+        let span = self.segments.iter().find(|span| span.number == number);
+
+        span.and_then(|span| {
+            chrono::Duration::from_std(span.duration)
+                .ok()
+                .map(|duration| (span.start_date, span.start_date + duration))
+        })
+        .map_or_else(Vec::new, |(start, end)| {
+            self.ranges
+                .iter()
+                .filter(|r| r.overlaps(start, end))
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+    use pretty_assertions::assert_eq;
+
+    #[cfg(feature = "chrono")]
+    fn date(value: &str) -> DateTime<FixedOffset> { DateTime::parse_from_rfc3339(value).unwrap() }
+
+    #[cfg(feature = "chrono")]
+    fn ids<'a>(ranges: Vec<&'a ScheduledDateRange<'_>>) -> Vec<&'a str> {
+        ranges.iter().map(|r| r.date_range.id().as_ref()).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_date_range_timeline() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:50Z\n",
+            "#EXT-X-DATERANGE:ID=\"chapter-1\",CLASS=\"chapter\",START-DATE=\"2020-01-02T21:55:40Z\",END-ON-NEXT=YES\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2020-01-02T21:55:50Z\",PLANNED-DURATION=15\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXT-X-DATERANGE:ID=\"chapter-2\",CLASS=\"chapter\",START-DATE=\"2020-01-02T21:56:00Z\",END-ON-NEXT=YES\n",
+            "#EXT-X-DATERANGE:ID=\"marker\"\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+        ))
+        .unwrap();
+
+        let timeline = playlist.date_range_timeline().unwrap();
+
+        assert_eq!(
+            timeline
+                .ranges()
+                .iter()
+                .map(|r| (r.date_range.id().as_ref(), r.start_date, r.end_date, r.is_planned))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "chapter-1",
+                    date("2020-01-02T21:55:40Z"),
+                    Some(date("2020-01-02T21:56:00Z")),
+                    false
+                ),
+                (
+                    "ad",
+                    date("2020-01-02T21:55:50Z"),
+                    Some(date("2020-01-02T21:56:05Z")),
+                    true
+                ),
+                ("chapter-2", date("2020-01-02T21:56:00Z"), None, false),
+                // the start date is taken from the segment:
+                ("marker", date("2020-01-02T21:56:00Z"), None, false),
+            ]
+        );
+
+        assert_eq!(ids(timeline.at_offset(Duration::from_secs(5))), vec!["chapter-1"]);
+        assert_eq!(
+            ids(timeline.at_offset(Duration::from_secs(15))),
+            vec!["chapter-1", "ad"]
+        );
+        assert_eq!(
+            ids(timeline.at_date(date("2020-01-02T21:56:02Z"))),
+            vec!["ad", "chapter-2", "marker"]
+        );
+        assert_eq!(
+            ids(timeline.at_date(date("2020-01-02T21:56:05Z"))),
+            vec!["chapter-2", "marker"]
+        );
+        assert!(timeline.at_date(date("2020-01-02T21:55:39Z")).is_empty());
+
+        assert_eq!(ids(timeline.for_segment(10)), vec!["chapter-1"]);
+        assert_eq!(ids(timeline.for_segment(11)), vec!["chapter-1", "ad"]);
+        assert_eq!(
+            ids(timeline.for_segment(12)),
+            vec!["ad", "chapter-2", "marker"]
+        );
+        assert!(timeline.for_segment(13).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_zero_duration() {
+        let range = ScheduledDateRange {
+            date_range: ExtXDateRange::new("id", date("2020-01-02T21:55:40Z")),
+            start_date: date("2020-01-02T21:55:40Z"),
+            end_date: Some(date("2020-01-02T21:55:40Z")),
+            is_planned: false,
+        };
+
+        assert!(range.contains(date("2020-01-02T21:55:40Z")));
+        assert!(!range.contains(date("2020-01-02T21:55:41Z")));
+        assert!(range.overlaps(date("2020-01-02T21:55:40Z"), date("2020-01-02T21:55:50Z")));
+        assert!(!range.overlaps(date("2020-01-02T21:55:30Z"), date("2020-01-02T21:55:40Z")));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_empty() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+        ))
+        .unwrap();
+
+        let timeline = playlist.date_range_timeline().unwrap();
+
+        assert!(timeline.ranges().is_empty());
+        assert!(timeline.at_offset(Duration::from_secs(5)).is_empty());
+        assert!(timeline.for_segment(0).is_empty());
+    }
+
+    #[test]
+    fn test_timeline() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:3\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:2\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXTINF:5.5,\n",
+            "b.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:0,\n",
+            "c.ts\n",
+            "#EXTINF:10,\n",
+            "d.ts\n",
+        ))
+        .unwrap();
+
+        let timeline = playlist.timeline();
+
+        assert_eq!(
+            timeline
+                .segments()
+                .iter()
+                .map(|s| (s.number(), s.offset, s.discontinuity_sequence))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, Duration::from_secs(0), 2),
+                (4, Duration::from_secs(10), 2),
+                (5, Duration::from_millis(15500), 3),
+                (6, Duration::from_millis(15500), 3),
+            ]
+        );
+        assert_eq!(timeline.duration(), Duration::from_millis(25500));
+
+        let number_at = |secs: f64| {
+            timeline
+                .segment_at(Duration::from_secs_f64(secs))
+                .map(TimelineSegment::number)
+        };
+
+        assert_eq!(number_at(0.0), Some(3));
+        assert_eq!(number_at(9.9), Some(3));
+        assert_eq!(number_at(10.0), Some(4));
+        // the segment with a duration of 0 is skipped:
+        assert_eq!(number_at(15.5), Some(6));
+        assert_eq!(number_at(25.4), Some(6));
+        assert_eq!(number_at(25.5), None);
+
+        assert_eq!(timeline.start_of(3), Some(Duration::from_secs(0)));
+        assert_eq!(timeline.start_of(6), Some(Duration::from_millis(15500)));
+        assert_eq!(timeline.start_of(2), None);
+        assert_eq!(timeline.start_of(7), None);
+
+        assert!(MediaPlaylist::try_from("#EXTM3U\n#EXT-X-TARGETDURATION:10\n")
+            .unwrap()
+            .timeline()
+            .segment_at(Duration::from_secs(0))
+            .is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_timeline_dates() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T22:00:00Z\n",
+            "#EXTINF:10,\n",
+            "d.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:10,\n",
+            "e.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:10,\n",
+            "f.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T23:00:00Z\n",
+            "#EXTINF:10,\n",
+            "g.ts\n",
+        ))
+        .unwrap();
+
+        let timeline = playlist.timeline();
+
+        assert_eq!(
+            timeline
+                .segments()
+                .iter()
+                .map(|s| s.start_date)
+                .collect::<Vec<_>>(),
+            vec![
+                Some(date("2020-01-02T21:55:30Z")),
+                Some(date("2020-01-02T21:55:40Z")),
+                Some(date("2020-01-02T21:55:50Z")),
+                Some(date("2020-01-02T22:00:00Z")),
+                // a discontinuity without a program date time:
+                None,
+                Some(date("2020-01-02T22:59:50Z")),
+                Some(date("2020-01-02T23:00:00Z")),
+            ]
+        );
+
+        let number_at = |value| timeline.segment_at_date(date(value)).map(|s| s.number());
+
+        assert_eq!(number_at("2020-01-02T21:55:29Z"), None);
+        assert_eq!(number_at("2020-01-02T21:55:30Z"), Some(0));
+        assert_eq!(number_at("2020-01-02T21:55:55Z"), Some(2));
+        // the gap between c.ts and d.ts:
+        assert_eq!(number_at("2020-01-02T21:56:00Z"), None);
+        assert_eq!(number_at("2020-01-02T22:00:05Z"), Some(3));
+        assert_eq!(number_at("2020-01-02T22:59:55Z"), Some(5));
+        assert_eq!(number_at("2020-01-02T23:00:10Z"), None);
+
+        assert_eq!(
+            timeline.date_at(Duration::from_secs(15)),
+            Some(date("2020-01-02T21:55:45Z"))
+        );
+        assert_eq!(timeline.date_at(Duration::from_secs(45)), None);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_program_date_time_generator() {
+        let segment = |duration: &str, has_discontinuity| {
+            MediaSegment::builder()
+                .duration(duration.parse::<Decimal>().unwrap())
+                .has_discontinuity(has_discontinuity)
+                .uri("a.ts")
+                .build()
+                .unwrap()
+        };
+
+        let mut explicit = segment("1.92", false);
+        explicit.program_date_time = Some(ExtXProgramDateTime::new(date("2020-01-02T22:00:00Z")));
+
+        let mut playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(2))
+            .segments(vec![
+                segment("1.92", false),
+                segment("1.92", false),
+                segment("1.92", true),
+                explicit,
+                segment("1.92", false),
+            ])
+            .build()
+            .unwrap();
+
+        playlist
+            .generate_program_date_times(ProgramDateTimeGenerator::every_segment(date(
+                "2020-01-02T21:55:40Z",
+            )))
+            .unwrap();
+
+        assert_eq!(
+            playlist
+                .segments
+                .values()
+                .map(|s| s.program_date_time.map(|v| v.date_time))
+                .collect::<Vec<_>>(),
+            vec![
+                Some(date("2020-01-02T21:55:40Z")),
+                Some(date("2020-01-02T21:55:41.920Z")),
+                Some(date("2020-01-02T21:55:43.840Z")),
+                Some(date("2020-01-02T22:00:00Z")),
+                Some(date("2020-01-02T22:00:01.920Z")),
+            ]
+        );
+        // the explicit date does not match the durations:
+        assert_eq!(
+            playlist.program_date_time_issues(Duration::from_secs(0)),
+            vec![ProgramDateTimeIssue::Drift {
+                number: 3,
+                expected: date("2020-01-02T21:55:45.760Z"),
+                date: date("2020-01-02T22:00:00Z"),
+            }]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_program_date_time_issues() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:5\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:55:40Z\n",
+            "#EXTINF:10,\n",
+            "a.ts\n",
+            "#EXTINF:10,\n",
+            "b.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:56:00.5Z\n",
+            "#EXTINF:10,\n",
+            "c.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T21:56:05Z\n",
+            "#EXTINF:10,\n",
+            "d.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T23:00:00Z\n",
+            "#EXTINF:10,\n",
+            "e.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2020-01-02T22:00:00Z\n",
+            "#EXTINF:10,\n",
+            "f.ts\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            playlist.program_date_time_issues(Duration::from_millis(500)),
+            vec![
+                ProgramDateTimeIssue::Drift {
+                    number: 8,
+                    expected: date("2020-01-02T21:56:10.5Z"),
+                    date: date("2020-01-02T21:56:05Z"),
+                },
+                ProgramDateTimeIssue::Backwards {
+                    number: 10,
+                    previous: date("2020-01-02T23:00:00Z"),
+                    date: date("2020-01-02T22:00:00Z"),
+                },
+            ]
+        );
+        assert_eq!(
+            playlist.program_date_time_issues(Duration::from_millis(499))[0],
+            ProgramDateTimeIssue::Drift {
+                number: 7,
+                expected: date("2020-01-02T21:56:00Z"),
+                date: date("2020-01-02T21:56:00.5Z"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_periods() {
+        let playlist = MediaPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXT-X-MEDIA-SEQUENCE:7\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:2\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"https://example.com/key\"\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:6.006,\n",
+            "a.m4s\n",
+            "#EXTINF:6.006,\n",
+            "b.m4s\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXT-X-KEY:METHOD=NONE\n",
+            "#EXTINF:1.92,\n",
+            "c.m4s\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:10,\n",
+            "d.m4s\n",
+        ))
+        .unwrap();
+
+        let periods = playlist.periods().collect::<Vec<_>>();
+
+        assert_eq!(
+            periods
+                .iter()
+                .map(|p| (
+                    p.discontinuity_sequence,
+                    p.start,
+                    p.duration,
+                    p.segments.iter().map(|s| s.number()).collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, Duration::from_secs(0), Duration::from_millis(12012), vec![7, 8]),
+                (
+                    3,
+                    Duration::from_millis(12012),
+                    Duration::from_millis(1920),
+                    vec![9]
+                ),
+                (4, Duration::from_millis(13932), Duration::from_secs(10), vec![10]),
+            ]
+        );
+
+        // the map applies to all periods:
+        assert!(periods
+            .iter()
+            .all(|p| p.map.map(|map| map.uri().as_ref()) == Some("init.mp4")));
+
+        assert_eq!(periods[0].keys.len(), 1);
+        assert!(periods[0].keys[0].is_some());
+        assert_eq!(periods[1].keys, &[ExtXKey::empty()][..]);
+        assert_eq!(periods[2].keys, &[ExtXKey::empty()][..]);
+
+        assert_eq!(
+            MediaPlaylist::try_from("#EXTM3U\n#EXT-X-TARGETDURATION:10\n")
+                .unwrap()
+                .periods()
+                .next(),
+            None
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use core::convert::TryFrom;
+use core::fmt;
+use std::borrow::Cow;
+
+use derive_more::{AsMut, AsRef, Deref, DerefMut};
+use shorthand::ShortHand;
+
+use crate::Error;
+
+/// A single entry of [`AllowedCpc`], which maps a `KEYFORMAT` to the Content
+/// Protection Configuration (CPC) labels, that are allowed for it.
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::CpcEntry;
+///
+/// let entry = CpcEntry::new("com.apple.streamingkeydelivery", vec!["AppleMain", "Main"]);
+///
+/// assert_eq!(
+///     entry.to_string(),
+///     "com.apple.streamingkeydelivery:AppleMain/Main".to_string()
+/// );
+/// ```
+#[derive(ShortHand, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[shorthand(enable(must_use, into))]
+pub struct CpcEntry<'a> {
+    /// The `KEYFORMAT` of an [`ExtXKey`], to which the labels apply.
+    ///
+    /// [`ExtXKey`]: crate::tags::ExtXKey
+    key_format: Cow<'a, str>,
+    /// The CPC labels, that are allowed for the [`CpcEntry::key_format`].
+    ///
+    /// The meaning of a label is defined by the owner of the `KEYFORMAT` (for
+    /// example `AppleMain` or `SW_SECURE_CRYPTO`).
+    #[shorthand(disable(into))]
+    labels: Vec<Cow<'a, str>>,
+}
+
+impl<'a> CpcEntry<'a> {
+    /// Makes a new [`CpcEntry`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::CpcEntry;
+    /// let entry = CpcEntry::new("com.microsoft.playready", vec!["SL3000"]);
+    /// ```
+    #[must_use]
+    pub fn new<K, L>(key_format: K, labels: Vec<L>) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        L: Into<Cow<'a, str>>,
+    {
+        Self {
+            key_format: key_format.into(),
+            labels: labels.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> CpcEntry<'static> {
+        CpcEntry {
+            key_format: Cow::Owned(self.key_format.into_owned()),
+            labels: self
+                .labels
+                .into_iter()
+                .map(|v| Cow::Owned(v.into_owned()))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for CpcEntry<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.key_format)?;
+
+        if let Some(label) = self.labels.iter().next() {
+            write!(f, "{}", label)?;
+
+            for label in self.labels.iter().skip(1) {
+                write!(f, "/{}", label)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for CpcEntry<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        // a `KEYFORMAT` might contain a colon (`urn:uuid:...`), but the labels
+        // can not, so the input is split at the last colon:
+        let mut parts = input.rsplitn(2, ':');
+
+        let labels = parts.next().unwrap_or_default();
+        let key_format = parts
+            .next()
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| Error::missing_value("KEYFORMAT"))?;
+
+        if labels.is_empty() {
+            return Err(Error::missing_value("CPC-LABEL"));
+        }
+
+        Ok(Self::new(key_format, labels.split('/').collect()))
+    }
+}
+
+/// The value of the `ALLOWED-CPC` attribute, which is a list of
+/// [`CpcEntry`]s.
+///
+/// It indicates, that a [`VariantStream`] must only be played by a client,
+/// that satisfies one of the listed Content Protection Configurations for the
+/// `KEYFORMAT` of the used [`ExtXKey`]. A `KEYFORMAT`, that is not listed, is
+/// not restricted.
+///
+/// # Example
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use hls_m3u8::types::AllowedCpc;
+///
+/// let allowed_cpc = AllowedCpc::try_from(concat!(
+///     "com.apple.streamingkeydelivery:AppleMain/Main,",
+///     "com.microsoft.playready:SL3000"
+/// ))?;
+///
+/// assert!(allowed_cpc.is_allowed("com.apple.streamingkeydelivery", "Main"));
+/// assert!(!allowed_cpc.is_allowed("com.microsoft.playready", "SL2000"));
+/// assert!(allowed_cpc.is_allowed("identity", "anything"));
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`VariantStream`]: crate::tags::VariantStream
+/// [`ExtXKey`]: crate::tags::ExtXKey
+#[derive(
+    AsMut, AsRef, Deref, DerefMut, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct AllowedCpc<'a> {
+    list: Vec<CpcEntry<'a>>,
+}
+
+impl<'a> AllowedCpc<'a> {
+    /// Makes a new (empty) [`AllowedCpc`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::AllowedCpc;
+    /// let allowed_cpc = AllowedCpc::new();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self { Self { list: Vec::new() } }
+
+    /// Returns the labels, that are allowed for the provided `KEYFORMAT` or
+    /// `None`, if the `KEYFORMAT` is not restricted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::{AllowedCpc, CpcEntry};
+    ///
+    /// let allowed_cpc = AllowedCpc::from(vec![CpcEntry::new(
+    ///     "com.microsoft.playready",
+    ///     vec!["SL3000"],
+    /// )]);
+    ///
+    /// assert_eq!(
+    ///     allowed_cpc.labels("com.microsoft.playready"),
+    ///     Some(&["SL3000".into()][..])
+    /// );
+    /// assert_eq!(allowed_cpc.labels("identity"), None);
+    /// ```
+    #[must_use]
+    pub fn labels<T: AsRef<str>>(&self, key_format: T) -> Option<&[Cow<'a, str>]> {
+        self.list
+            .iter()
+            .find(|entry| entry.key_format() == key_format.as_ref())
+            .map(|entry| entry.labels().as_slice())
+    }
+
+    /// Returns `true`, if a client with the provided CPC `label` is allowed to
+    /// play content, that is protected with the `KEYFORMAT`.
+    #[must_use]
+    pub fn is_allowed<K: AsRef<str>, L: AsRef<str>>(&self, key_format: K, label: L) -> bool {
+        self.labels(key_format)
+            .map_or(true, |labels| labels.iter().any(|l| l == label.as_ref()))
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> AllowedCpc<'static> {
+        AllowedCpc {
+            list: self.list.into_iter().map(CpcEntry::into_owned).collect(),
+        }
+    }
+}
+
+impl<'a> From<Vec<CpcEntry<'a>>> for AllowedCpc<'a> {
+    fn from(list: Vec<CpcEntry<'a>>) -> Self { Self { list } }
+}
+
+impl<'a> fmt::Display for AllowedCpc<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(entry) = self.list.iter().next() {
+            write!(f, "{}", entry)?;
+
+            for entry in self.list.iter().skip(1) {
+                write!(f, ",{}", entry)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for AllowedCpc<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        Ok(Self {
+            list: input
+                .split(',')
+                .map(CpcEntry::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl<'a> TryFrom<Cow<'a, str>> for AllowedCpc<'a> {
+    type Error = Error;
+
+    fn try_from(input: Cow<'a, str>) -> Result<Self, Self::Error> {
+        match input {
+            Cow::Owned(o) => Ok(AllowedCpc::try_from(o.as_str())?.into_owned()),
+            Cow::Borrowed(b) => Self::try_from(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            AllowedCpc::from(vec![
+                CpcEntry::new("com.apple.streamingkeydelivery", vec!["AppleMain", "Main"]),
+                CpcEntry::new("com.microsoft.playready", vec!["SL3000"]),
+            ])
+            .to_string(),
+            concat!(
+                "com.apple.streamingkeydelivery:AppleMain/Main,",
+                "com.microsoft.playready:SL3000"
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            AllowedCpc::try_from(concat!(
+                "com.apple.streamingkeydelivery:AppleMain/Main,",
+                "urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed:HW_SECURE_ALL"
+            ))
+            .unwrap(),
+            AllowedCpc::from(vec![
+                CpcEntry::new("com.apple.streamingkeydelivery", vec!["AppleMain", "Main"]),
+                CpcEntry::new(
+                    "urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed",
+                    vec!["HW_SECURE_ALL"]
+                ),
+            ])
+        );
+
+        assert!(AllowedCpc::try_from("").is_err());
+        assert!(AllowedCpc::try_from("com.microsoft.playready").is_err());
+        assert!(AllowedCpc::try_from("com.microsoft.playready:").is_err());
+        assert!(AllowedCpc::try_from(":SL3000").is_err());
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let allowed_cpc = AllowedCpc::from(vec![CpcEntry::new(
+            "com.apple.streamingkeydelivery",
+            vec!["AppleMain", "Main"],
+        )]);
+
+        assert!(allowed_cpc.is_allowed("com.apple.streamingkeydelivery", "AppleMain"));
+        assert!(allowed_cpc.is_allowed("com.apple.streamingkeydelivery", "Main"));
+        assert!(!allowed_cpc.is_allowed("com.apple.streamingkeydelivery", "Baseline"));
+        assert!(allowed_cpc.is_allowed("com.microsoft.playready", "SL2000"));
+    }
+}
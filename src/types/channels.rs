@@ -1,20 +1,226 @@
+use core::convert::TryFrom;
 use core::fmt;
 use core::str::FromStr;
+use std::borrow::Cow;
 
 use shorthand::ShortHand;
 
-use crate::Error;
+use crate::types::ProtocolVersion;
+use crate::{Error, RequiredVersion};
 
-/// The maximum number of independent, simultaneous audio channels present in
-/// any [`MediaSegment`] in the rendition.
+/// Identifies the encoding of object-based audio, which is the second
+/// parameter of [`Channels`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AudioCoding<'a> {
+    /// The audio is not object-based (serialized as `-`).
+    NotObjectBased,
+    /// A list of codec-specific Audio Object Coding Identifiers, for example
+    /// `JOC` for Dolby Atmos (Joint Object Coding).
+    ///
+    /// The list must not be empty and an identifier must only consist of the
+    /// characters `[A-Z]`, `[0-9]` and `-`, which is checked by
+    /// [`AudioCoding::object_based`].
+    ObjectBased(Vec<Cow<'a, str>>),
+}
+
+impl<'a> AudioCoding<'a> {
+    /// Makes a new [`AudioCoding::ObjectBased`] from a list of Audio Object
+    /// Coding Identifiers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::AudioCoding;
+    ///
+    /// assert_eq!(
+    ///     AudioCoding::object_based(vec!["JOC"])?,
+    ///     AudioCoding::ObjectBased(vec!["JOC".into()])
+    /// );
+    ///
+    /// assert!(AudioCoding::object_based(Vec::<&str>::new()).is_err());
+    /// assert!(AudioCoding::object_based(vec!["joc"]).is_err());
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, if the list is empty or contains an
+    /// invalid identifier.
+    pub fn object_based<I, T>(identifiers: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Cow<'a, str>>,
+    {
+        let result = Self::ObjectBased(identifiers.into_iter().map(Into::into).collect());
+        result.validate()?;
+
+        Ok(result)
+    }
+
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        if let Self::ObjectBased(list) = self {
+            if list.is_empty() {
+                return Err(Error::custom(
+                    "the list of audio object coding identifiers must not be empty",
+                ));
+            }
+
+            for value in list {
+                if !is_identifier(value) {
+                    return Err(Error::custom(format!(
+                        "invalid audio object coding identifier: {:?}",
+                        value
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> AudioCoding<'static> {
+        match self {
+            Self::NotObjectBased => AudioCoding::NotObjectBased,
+            Self::ObjectBased(list) => {
+                AudioCoding::ObjectBased(
+                    list.into_iter()
+                        .map(|v| Cow::Owned(v.into_owned()))
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Display for AudioCoding<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Self::NotObjectBased => write!(f, "-")?,
+            Self::ObjectBased(list) => {
+                if let Some(value) = list.iter().next() {
+                    write!(f, "{}", value)?;
+
+                    for value in list.iter().skip(1) {
+                        write!(f, ",{}", value)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for AudioCoding<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        if input == "-" {
+            return Ok(Self::NotObjectBased);
+        }
+
+        Self::object_based(input.split(','))
+    }
+}
+
+/// Specifies how the audio channels of a rendition are meant to be used, which
+/// is the third parameter of [`Channels`].
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChannelUsage<'a> {
+    /// The audio is binaural (either recorded or synthesized) and should not be
+    /// played over loudspeakers (`BINAURAL`).
+    Binaural,
+    /// The audio is pre-processed content, that should not be downmixed, and is
+    /// intended for loudspeakers in a specific layout (spatial audio)
+    /// (`IMMERSIVE`).
+    Immersive,
+    /// The audio is a downmix of a higher channel-count audio source
+    /// (`DOWNMIX`).
+    Downmix,
+    /// Any other channel usage identifier, which is not known by this crate.
+    Other(Cow<'a, str>),
+}
+
+impl<'a> ChannelUsage<'a> {
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> ChannelUsage<'static> {
+        match self {
+            Self::Binaural => ChannelUsage::Binaural,
+            Self::Immersive => ChannelUsage::Immersive,
+            Self::Downmix => ChannelUsage::Downmix,
+            Self::Other(value) => ChannelUsage::Other(Cow::Owned(value.into_owned())),
+        }
+    }
+}
+
+impl<'a> fmt::Display for ChannelUsage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binaural => write!(f, "BINAURAL"),
+            Self::Immersive => write!(f, "IMMERSIVE"),
+            Self::Downmix => write!(f, "DOWNMIX"),
+            Self::Other(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ChannelUsage<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        match input {
+            "BINAURAL" => Ok(Self::Binaural),
+            "IMMERSIVE" => Ok(Self::Immersive),
+            "DOWNMIX" => Ok(Self::Downmix),
+            other if is_identifier(other) => Ok(Self::Other(Cow::Borrowed(other))),
+            other => {
+                Err(Error::custom(format!(
+                    "invalid channel usage identifier: {:?}",
+                    other
+                )))
+            }
+        }
+    }
+}
+
+/// Returns `true`, if the identifier is not empty and only consists of the
+/// characters `[A-Z]`, `[0-9]` and `-`.
+fn is_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// The `CHANNELS` attribute of an [`ExtXMedia`] tag.
+///
+/// It consists of the maximum number of independent, simultaneous audio
+/// channels present in any [`MediaSegment`] in the rendition and optionally an
+/// [`AudioCoding`] and a list of [`ChannelUsage`]s.
 ///
 /// For example, an `AC-3 5.1` rendition would have a maximum channel number of
-/// 6.
+/// 6 and a Dolby Atmos rendition could be described with `16/JOC`.
 ///
 /// [`MediaSegment`]: crate::MediaSegment
-#[derive(ShortHand, Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// [`ExtXMedia`]: crate::tags::ExtXMedia
+#[derive(ShortHand, Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[shorthand(enable(must_use))]
-pub struct Channels {
+pub struct Channels<'a> {
     /// The maximum number of independent simultaneous audio channels.
     ///
     /// # Example
@@ -28,9 +234,47 @@ pub struct Channels {
     /// assert_eq!(channels.number(), 5);
     /// ```
     number: u64,
+    /// The encoding of object-based audio.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Channels;
+    /// use hls_m3u8::types::AudioCoding;
+    ///
+    /// let mut channels = Channels::new(16);
+    ///
+    /// channels.set_audio_coding(Some(AudioCoding::ObjectBased(vec!["JOC".into()])));
+    /// assert_eq!(channels.to_string(), "16/JOC".to_string());
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional.
+    audio_coding: Option<AudioCoding<'a>>,
+    /// How the audio channels are meant to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Channels;
+    /// use hls_m3u8::types::ChannelUsage;
+    ///
+    /// let mut channels = Channels::new(2);
+    ///
+    /// channels.set_channel_usage(vec![ChannelUsage::Binaural]);
+    /// assert_eq!(channels.to_string(), "2/-/BINAURAL".to_string());
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional. If it is not empty, but no
+    /// [`Channels::audio_coding`] is specified,
+    /// [`AudioCoding::NotObjectBased`] is assumed.
+    channel_usage: Vec<ChannelUsage<'a>>,
 }
 
-impl Channels {
+impl<'a> Channels<'a> {
     /// Makes a new [`Channels`] struct.
     ///
     /// # Example
@@ -44,23 +288,116 @@ impl Channels {
     /// ```
     //#[inline]
     #[must_use]
-    pub const fn new(number: u64) -> Self { Self { number } }
+    pub const fn new(number: u64) -> Self {
+        Self {
+            number,
+            audio_coding: None,
+            channel_usage: Vec::new(),
+        }
+    }
+
+    /// Makes the struct independent of its lifetime, by taking ownership of all
+    /// internal [`Cow`]s.
+    ///
+    /// # Note
+    ///
+    /// This is a relatively expensive operation.
+    #[must_use]
+    pub fn into_owned(self) -> Channels<'static> {
+        Channels {
+            number: self.number,
+            audio_coding: self.audio_coding.map(AudioCoding::into_owned),
+            channel_usage: self
+                .channel_usage
+                .into_iter()
+                .map(ChannelUsage::into_owned)
+                .collect(),
+        }
+    }
+}
+
+/// This struct requires [`ProtocolVersion::V1`], the additional parameters
+/// have been added without a new protocol version.
+impl<'a> RequiredVersion for Channels<'a> {
+    fn required_version(&self) -> ProtocolVersion { ProtocolVersion::V1 }
 }
 
-impl FromStr for Channels {
+impl<'a> TryFrom<&'a str> for Channels<'a> {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let mut parameters = input.split('/');
+
+        // `split` always returns at least one element
+        let number = parameters.next().unwrap_or_default();
+        let mut result = Self::new(number.parse().map_err(|e| Error::parse_int(number, e))?);
+
+        if let Some(value) = parameters.next() {
+            result.audio_coding = Some(AudioCoding::try_from(value)?);
+        }
+
+        if let Some(value) = parameters.next() {
+            result.channel_usage = value
+                .split(',')
+                .map(ChannelUsage::try_from)
+                .collect::<Result<_, _>>()?;
+        }
+
+        if let Some(value) = parameters.next() {
+            return Err(Error::custom(format!(
+                "unexpected CHANNELS parameter: {:?}",
+                value
+            )));
+        }
+
+        Ok(result)
+    }
+}
+
+impl<'a> TryFrom<Cow<'a, str>> for Channels<'a> {
+    type Error = Error;
+
+    fn try_from(input: Cow<'a, str>) -> Result<Self, Self::Error> {
+        match input {
+            Cow::Owned(o) => Ok(Channels::try_from(o.as_str())?.into_owned()),
+            Cow::Borrowed(b) => Self::try_from(b),
+        }
+    }
+}
+
+impl FromStr for Channels<'static> {
     type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        Ok(Self::new(
-            input.parse().map_err(|e| Error::parse_int(input, e))?,
-        ))
+        Ok(Channels::try_from(input)?.into_owned())
     }
 }
 
-impl fmt::Display for Channels {
+impl<'a> fmt::Display for Channels<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.number)?;
 
+        // an empty list of identifiers can not be parsed, so it is not written:
+        if let Some(value) = self
+            .audio_coding
+            .as_ref()
+            .filter(|v| **v != AudioCoding::ObjectBased(vec![]))
+        {
+            write!(f, "/{}", value)?;
+        } else if !self.channel_usage.is_empty() {
+            // the channel usage requires the audio coding parameter in front of
+            // it, `-` is assumed, if there is no audio coding:
+            write!(f, "/{}", AudioCoding::NotObjectBased)?;
+        }
+
+        if let Some(value) = self.channel_usage.first() {
+            write!(f, "/{}", value)?;
+
+            for value in self.channel_usage.iter().skip(1) {
+                write!(f, ",{}", value)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -75,13 +412,76 @@ mod tests {
         assert_eq!(Channels::new(6).to_string(), "6".to_string());
 
         assert_eq!(Channels::new(7).to_string(), "7".to_string());
+
+        let mut channels = Channels::new(16);
+        channels.set_audio_coding(Some(AudioCoding::ObjectBased(vec!["JOC".into()])));
+        assert_eq!(channels.to_string(), "16/JOC".to_string());
+
+        let mut channels = Channels::new(2);
+        channels.set_channel_usage(vec![ChannelUsage::Binaural]);
+        assert_eq!(channels.to_string(), "2/-/BINAURAL".to_string());
+
+        let mut channels = Channels::new(6);
+        channels.set_audio_coding(Some(AudioCoding::NotObjectBased));
+        channels.set_channel_usage(vec![ChannelUsage::Immersive, ChannelUsage::Downmix]);
+        assert_eq!(channels.to_string(), "6/-/IMMERSIVE,DOWNMIX".to_string());
+
+        let mut channels = Channels::new(16);
+        // an empty list of identifiers is not written:
+        channels.set_audio_coding(Some(AudioCoding::ObjectBased(vec![])));
+        assert_eq!(channels.to_string(), "16".to_string());
+
+        channels.set_channel_usage(vec![ChannelUsage::Binaural]);
+        assert_eq!(channels.to_string(), "16/-/BINAURAL".to_string());
     }
 
     #[test]
     fn test_parser() {
         assert_eq!(Channels::new(6), Channels::from_str("6").unwrap());
 
+        let mut channels = Channels::new(16);
+        channels.set_audio_coding(Some(AudioCoding::ObjectBased(vec!["JOC".into()])));
+        assert_eq!(channels, Channels::try_from("16/JOC").unwrap());
+
+        let mut channels = Channels::new(2);
+        channels.set_audio_coding(Some(AudioCoding::NotObjectBased));
+        channels.set_channel_usage(vec![ChannelUsage::Binaural]);
+        assert_eq!(channels, Channels::try_from("2/-/BINAURAL").unwrap());
+
+        let mut channels = Channels::new(12);
+        channels.set_audio_coding(Some(AudioCoding::ObjectBased(vec![
+            "JOC".into(),
+            "AC-4".into(),
+        ])));
+        channels.set_channel_usage(vec![ChannelUsage::Immersive, ChannelUsage::Downmix]);
+        assert_eq!(
+            channels,
+            Channels::try_from("12/JOC,AC-4/IMMERSIVE,DOWNMIX").unwrap()
+        );
+
         assert!(Channels::from_str("garbage").is_err());
         assert!(Channels::from_str("").is_err());
+        assert!(Channels::from_str("16/joc").is_err());
+        assert!(Channels::from_str("16/").is_err());
+        assert!(Channels::from_str("2/-/binaural").is_err());
+        assert!(Channels::from_str("2/-/").is_err());
+
+        // unknown channel usage identifiers are kept:
+        let mut channels = Channels::new(2);
+        channels.set_audio_coding(Some(AudioCoding::NotObjectBased));
+        channels.set_channel_usage(vec![ChannelUsage::Other("STEREO-WIDE".into())]);
+        assert_eq!(channels, Channels::try_from("2/-/STEREO-WIDE").unwrap());
+        assert_eq!(channels.to_string(), "2/-/STEREO-WIDE".to_string());
+        assert!(Channels::from_str("2/-/BINAURAL/X").is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(
+            Channels::try_from("2/-/BINAURAL")
+                .unwrap()
+                .required_version(),
+            ProtocolVersion::V1
+        );
     }
 }
@@ -0,0 +1,167 @@
+use core::fmt;
+use core::iter::FromIterator;
+use core::str::FromStr;
+
+use derive_more::{AsMut, AsRef, Deref, DerefMut};
+use strum::{Display, EnumString};
+
+use crate::Error;
+
+/// A trigger identifier of the `CUE` attribute, which indicates when to
+/// trigger the action associated with an [`ExtXDateRange`].
+///
+/// [`ExtXDateRange`]: crate::tags::ExtXDateRange
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING-KEBAB-CASE")]
+pub enum CueTrigger {
+    /// The action should be triggered before playback of the primary asset
+    /// begins, regardless of where playback begins in the primary asset.
+    Pre,
+    /// The action should be triggered after the primary asset has been played
+    /// to its end without error.
+    Post,
+    /// The action should be triggered no more than once.
+    Once,
+}
+
+/// The value of the `CUE` attribute of an [`ExtXDateRange`], which is a list
+/// of [`CueTrigger`]s.
+///
+/// A [`Cue`] must not contain both [`CueTrigger::Pre`] and
+/// [`CueTrigger::Post`].
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::{Cue, CueTrigger};
+///
+/// let cue = Cue::from(vec![CueTrigger::Pre, CueTrigger::Once]);
+///
+/// assert_eq!(cue.to_string(), "PRE,ONCE".to_string());
+/// assert!(cue.is_pre());
+/// assert!(cue.is_once());
+/// ```
+///
+/// [`ExtXDateRange`]: crate::tags::ExtXDateRange
+#[derive(
+    AsMut, AsRef, Deref, DerefMut, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct Cue {
+    list: Vec<CueTrigger>,
+}
+
+impl Cue {
+    /// Makes a new (empty) [`Cue`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Cue;
+    /// let cue = Cue::new();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self { Self { list: Vec::new() } }
+
+    /// Returns `true`, if the [`Cue`] contains [`CueTrigger::Pre`].
+    #[must_use]
+    pub fn is_pre(&self) -> bool { self.list.contains(&CueTrigger::Pre) }
+
+    /// Returns `true`, if the [`Cue`] contains [`CueTrigger::Post`].
+    #[must_use]
+    pub fn is_post(&self) -> bool { self.list.contains(&CueTrigger::Post) }
+
+    /// Returns `true`, if the [`Cue`] contains [`CueTrigger::Once`].
+    #[must_use]
+    pub fn is_once(&self) -> bool { self.list.contains(&CueTrigger::Once) }
+
+    /// Checks, that the [`Cue`] is not empty and does not contain both
+    /// [`CueTrigger::Pre`] and [`CueTrigger::Post`].
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        if self.list.is_empty() {
+            return Err(Error::custom("`CUE` must not be empty"));
+        }
+
+        if self.is_pre() && self.is_post() {
+            return Err(Error::custom("`CUE` must not contain both `PRE` and `POST`"));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<CueTrigger>> for Cue {
+    fn from(list: Vec<CueTrigger>) -> Self { Self { list } }
+}
+
+impl From<CueTrigger> for Cue {
+    fn from(value: CueTrigger) -> Self { Self { list: vec![value] } }
+}
+
+impl FromIterator<CueTrigger> for Cue {
+    fn from_iter<I: IntoIterator<Item = CueTrigger>>(iter: I) -> Self {
+        Self {
+            list: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for Cue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(trigger) = self.list.iter().next() {
+            write!(f, "{}", trigger)?;
+
+            for trigger in self.list.iter().skip(1) {
+                write!(f, ",{}", trigger)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Cue {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let result = input
+            .split(',')
+            .map(|s| s.trim().parse::<CueTrigger>().map_err(Error::strum))
+            .collect::<Result<Self, _>>()?;
+
+        result.validate()?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Cue::from(vec![CueTrigger::Post, CueTrigger::Once]).to_string(),
+            "POST,ONCE".to_string()
+        );
+        assert_eq!(Cue::from(CueTrigger::Pre).to_string(), "PRE".to_string());
+        assert_eq!(Cue::new().to_string(), "".to_string());
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            Cue::from(vec![CueTrigger::Pre, CueTrigger::Once]),
+            "PRE,ONCE".parse().unwrap()
+        );
+        assert_eq!(Cue::from(CueTrigger::Post), "POST".parse().unwrap());
+
+        assert!("PRE,POST".parse::<Cue>().is_err());
+        assert!("MID".parse::<Cue>().is_err());
+        assert!("".parse::<Cue>().is_err());
+        assert!(Cue::new().validate().is_err());
+    }
+}
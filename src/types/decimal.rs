@@ -0,0 +1,478 @@
+use core::cmp::Ordering;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign};
+use core::str::FromStr;
+use std::fmt;
+use std::time::Duration;
+
+use crate::Error;
+
+/// The largest supported [`Decimal::scale`] (`10^19` is the largest power of
+/// ten, that fits into an [`u64`]).
+const MAX_SCALE: u8 = 19;
+
+const fn pow10(exponent: u8) -> u64 {
+    let mut result = 1_u64;
+    let mut i = 0;
+
+    while i < exponent {
+        result *= 10;
+        i += 1;
+    }
+
+    result
+}
+
+/// An exact, non-negative decimal number (`mantissa / 10^scale`), which
+/// keeps the number of decimal places of the literal it has been parsed from.
+///
+/// Summing up the duration of thousands of [`MediaSegment`]s with a
+/// floating point type accumulates rounding errors, while the sum of
+/// [`Decimal`]s is exact. This type is used by [`ExtInf`] to remember the
+/// literal duration of a segment (for example `6.006` or `1.920`), which
+/// is written back unchanged.
+///
+/// Two decimals with the same value, but a different scale (like `5.5` and
+/// `5.50`) are ordered by their value, but are not equal, because they
+/// represent different literals.
+///
+/// # Example
+///
+/// ```
+/// # use hls_m3u8::types::Decimal;
+/// use std::time::Duration;
+///
+/// let decimal = "6.006".parse::<Decimal>()?;
+///
+/// assert_eq!(decimal, Decimal::new(6006, 3));
+/// assert_eq!(decimal.to_string(), "6.006");
+///
+/// let sum = std::iter::repeat(decimal).take(4000).sum::<Decimal>();
+///
+/// assert_eq!(sum.to_string(), "24024.000");
+/// assert_eq!(sum.as_duration(), Duration::from_secs(24024));
+/// # Ok::<(), hls_m3u8::Error>(())
+/// ```
+///
+/// [`MediaSegment`]: crate::MediaSegment
+/// [`ExtInf`]: crate::tags::ExtInf
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: u64,
+    scale: u8,
+}
+
+impl Decimal {
+    /// Makes a new [`Decimal`] with the value `mantissa / 10^scale`.
+    ///
+    /// # Panics
+    ///
+    /// If the scale is larger than 19.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Decimal;
+    /// let decimal = Decimal::new(1920, 3);
+    ///
+    /// assert_eq!(decimal.to_string(), "1.920");
+    /// ```
+    #[must_use]
+    pub fn new(mantissa: u64, scale: u8) -> Self {
+        if scale > MAX_SCALE {
+            panic!("the scale must not be larger than {}: `{}`", MAX_SCALE, scale);
+        }
+
+        Self { mantissa, scale }
+    }
+
+    /// Returns the value without the decimal point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Decimal;
+    /// assert_eq!(Decimal::new(1920, 3).mantissa(), 1920);
+    /// ```
+    #[must_use]
+    pub const fn mantissa(&self) -> u64 { self.mantissa }
+
+    /// Returns the number of decimal places.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Decimal;
+    /// assert_eq!(Decimal::new(1920, 3).scale(), 3);
+    /// ```
+    #[must_use]
+    pub const fn scale(&self) -> u8 { self.scale }
+
+    /// Makes a new [`Decimal`] from a [`Duration`] with the least number of
+    /// decimal places, that are needed to represent the [`Duration`] exactly.
+    ///
+    /// Durations of more than 584 years lose their sub-second precision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Decimal;
+    /// use std::time::Duration;
+    ///
+    /// assert_eq!(
+    ///     Decimal::from_duration(Duration::from_millis(5500)),
+    ///     Decimal::new(55, 1)
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn from_duration(duration: Duration) -> Self {
+        let mut value =
+            duration.as_secs() as u128 * 1_000_000_000 + duration.subsec_nanos() as u128;
+        let mut scale = 9;
+
+        while scale > 0 && (value % 10 == 0 || value > u64::max_value() as u128) {
+            value /= 10;
+            scale -= 1;
+        }
+
+        if value > u64::max_value() as u128 {
+            value = u64::max_value() as u128;
+        }
+
+        // the value has been limited to `u64::max_value()` above
+        #[allow(clippy::cast_possible_truncation)]
+        Self {
+            mantissa: value as u64,
+            scale,
+        }
+    }
+
+    /// Converts the decimal into a [`Duration`].
+    ///
+    /// Decimal places beyond nanoseconds are truncated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Decimal;
+    /// use std::time::Duration;
+    ///
+    /// assert_eq!(
+    ///     Decimal::new(6006, 3).as_duration(),
+    ///     Duration::from_millis(6006)
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn as_duration(&self) -> Duration {
+        let divisor = pow10(self.scale);
+        let fraction = self.mantissa % divisor;
+
+        let nanos = {
+            if self.scale <= 9 {
+                fraction * pow10(9 - self.scale)
+            } else {
+                fraction / pow10(self.scale - 9)
+            }
+        };
+
+        // the fraction is less than 10^9, so it can not be truncated
+        #[allow(clippy::cast_possible_truncation)]
+        Duration::new(self.mantissa / divisor, nanos as u32)
+    }
+
+    /// Converts the decimal into an [`f64`], which might lose precision.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_f64(&self) -> f64 { self.mantissa as f64 / pow10(self.scale) as f64 }
+
+    /// Adds two decimals. The result has the larger scale of both decimals.
+    ///
+    /// This function returns `None`, if the result does not fit into the
+    /// decimal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Decimal;
+    /// assert_eq!(
+    ///     Decimal::new(55, 1).checked_add(Decimal::new(1920, 3)),
+    ///     Some(Decimal::new(7420, 3))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+
+        let left = self.mantissa.checked_mul(pow10(scale - self.scale))?;
+        let right = other.mantissa.checked_mul(pow10(scale - other.scale))?;
+
+        Some(Self {
+            mantissa: left.checked_add(right)?,
+            scale,
+        })
+    }
+
+    /// Adds two decimals like [`Decimal::checked_add`], but instead of
+    /// overflowing, decimal places are dropped from the result (rounding
+    /// towards zero), until it fits. A result, that does not even fit without
+    /// decimal places, saturates at [`u64::max_value`].
+    ///
+    /// This is the behavior of the [`Add`] implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::Decimal;
+    /// assert_eq!(
+    ///     Decimal::new(10_000_000_000_000_000_001, 19).saturating_add(Decimal::new(10, 0)),
+    ///     Decimal::new(11_000_000_000_000_000_000, 18)
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Decimal::new(u64::max_value(), 0).saturating_add(Decimal::new(1, 0)),
+    ///     Decimal::new(u64::max_value(), 0)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn saturating_add(self, other: Self) -> Self {
+        if let Some(result) = self.checked_add(other) {
+            return result;
+        }
+
+        let mut scale = self.scale.max(other.scale);
+        let (left, right) = self.aligned(&other);
+
+        let mut value = {
+            if let Some(value) = left.checked_add(right) {
+                value
+            } else {
+                // both mantissas fit into an u64 with a scale of 0, so the scale
+                // is at least 1:
+                scale -= 1;
+                left / 10 + right / 10 + (left % 10 + right % 10) / 10
+            }
+        };
+
+        let max = u128::from(u64::max_value());
+
+        while value > max && scale > 0 {
+            value /= 10;
+            scale -= 1;
+        }
+
+        // the value has been limited to `u64::max_value()`
+        #[allow(clippy::cast_possible_truncation)]
+        Self {
+            mantissa: value.min(max) as u64,
+            scale,
+        }
+    }
+
+    /// Returns both mantissas with the same scale.
+    fn aligned(&self, other: &Self) -> (u128, u128) {
+        let scale = self.scale.max(other.scale);
+
+        (
+            u128::from(self.mantissa) * u128::from(pow10(scale - self.scale)),
+            u128::from(other.mantissa) * u128::from(pow10(scale - other.scale)),
+        )
+    }
+}
+
+impl Add for Decimal {
+    type Output = Self;
+
+    /// Adds two decimals with [`Decimal::saturating_add`], which never panics.
+    fn add(self, other: Self) -> Self::Output { self.saturating_add(other) }
+}
+
+impl AddAssign for Decimal {
+    fn add_assign(&mut self, other: Self) { *self = *self + other; }
+}
+
+impl Sum for Decimal {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self { iter.fold(Self::default(), Add::add) }
+}
+
+impl<'a> Sum<&'a Self> for Decimal {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self { iter.copied().sum() }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (left, right) = self.aligned(other);
+
+        left.cmp(&right).then(self.scale.cmp(&other.scale))
+    }
+}
+
+impl From<Duration> for Decimal {
+    fn from(value: Duration) -> Self { Self::from_duration(value) }
+}
+
+impl From<Decimal> for Duration {
+    fn from(value: Decimal) -> Self { value.as_duration() }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.mantissa);
+        }
+
+        let divisor = pow10(self.scale);
+
+        write!(
+            f,
+            "{}.{:0width$}",
+            self.mantissa / divisor,
+            self.mantissa % divisor,
+            width = usize::from(self.scale)
+        )
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.splitn(2, '.');
+
+        let integer = parts.next().unwrap();
+        let fraction = parts.next().unwrap_or("");
+
+        if (integer.is_empty() && fraction.is_empty())
+            || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(Error::custom(format!("invalid decimal: {:?}", input)));
+        }
+
+        if fraction.len() > usize::from(MAX_SCALE) {
+            return Err(Error::custom(format!("too many decimal places: {:?}", input)));
+        }
+
+        let mantissa = format!("{}{}", integer, fraction)
+            .parse()
+            .map_err(|e| Error::parse_int(input, e))?;
+
+        // the length has been checked above
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(Self {
+            mantissa,
+            scale: fraction.len() as u8,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Decimal::new(5, 0).to_string(), "5".to_string());
+        assert_eq!(Decimal::new(550, 2).to_string(), "5.50".to_string());
+        assert_eq!(Decimal::new(6006, 3).to_string(), "6.006".to_string());
+        assert_eq!(Decimal::new(1, 3).to_string(), "0.001".to_string());
+        assert_eq!(Decimal::new(0, 1).to_string(), "0.0".to_string());
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(Decimal::from_str("5").unwrap(), Decimal::new(5, 0));
+        assert_eq!(Decimal::from_str("5.50").unwrap(), Decimal::new(550, 2));
+        assert_eq!(Decimal::from_str("0.001").unwrap(), Decimal::new(1, 3));
+        assert_eq!(Decimal::from_str(".5").unwrap(), Decimal::new(5, 1));
+        assert_eq!(Decimal::from_str("5.").unwrap(), Decimal::new(5, 0));
+        assert_eq!(
+            Decimal::from_str("0.0000000000000000001").unwrap(),
+            Decimal::new(1, 19)
+        );
+
+        assert!(Decimal::from_str("").is_err());
+        assert!(Decimal::from_str(".").is_err());
+        assert!(Decimal::from_str("-1").is_err());
+        assert!(Decimal::from_str("1e3").is_err());
+        assert!(Decimal::from_str("1.2.3").is_err());
+        assert!(Decimal::from_str("0.00000000000000000001").is_err());
+        assert!(Decimal::from_str("18446744073709551616").is_err());
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(
+            Decimal::new(1920, 3).as_duration(),
+            Duration::from_millis(1920)
+        );
+        assert_eq!(Decimal::new(5, 0).as_duration(), Duration::from_secs(5));
+        assert_eq!(
+            Decimal::new(1_234_567_891, 10).as_duration(),
+            Duration::from_nanos(123_456_789)
+        );
+
+        assert_eq!(
+            Decimal::from_duration(Duration::from_secs(5)),
+            Decimal::new(5, 0)
+        );
+        assert_eq!(
+            Decimal::from_duration(Duration::from_nanos(1)),
+            Decimal::new(1, 9)
+        );
+        assert_eq!(
+            Decimal::from_duration(Duration::from_secs(u64::max_value())),
+            Decimal::new(u64::max_value(), 0)
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        let sum = std::iter::repeat(Decimal::new(192, 2))
+            .take(4000)
+            .sum::<Decimal>();
+
+        assert_eq!(sum, Decimal::new(768_000, 2));
+        assert_eq!(sum.as_duration(), Duration::from_secs(7680));
+
+        assert_eq!(
+            Decimal::new(5, 0) + Decimal::new(5, 1),
+            Decimal::new(55, 1)
+        );
+        assert_eq!(
+            Decimal::new(u64::max_value(), 0).checked_add(Decimal::new(1, 1)),
+            None
+        );
+        // the overflow drops decimal places instead of panicking:
+        assert_eq!(
+            Decimal::new(10_000_000_000_000_000_001, 19) + Decimal::new(10, 0),
+            Decimal::new(11_000_000_000_000_000_000, 18)
+        );
+        assert_eq!(
+            Decimal::new(u64::max_value(), 0) + Decimal::new(u64::max_value(), 19),
+            Decimal::new(u64::max_value(), 0)
+        );
+        assert_eq!(
+            Decimal::new(u64::max_value(), 19) + Decimal::new(u64::max_value(), 19),
+            Decimal::new(3_689_348_814_741_910_323, 18)
+        );
+        assert_eq!(
+            Decimal::new(u64::max_value(), 1) + Decimal::new(5, 1),
+            Decimal::new(1_844_674_407_370_955_162, 0)
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        assert!(Decimal::new(55, 1) < Decimal::new(6, 0));
+        assert!(Decimal::new(55, 1) < Decimal::new(550, 2));
+        assert_ne!(Decimal::new(55, 1), Decimal::new(550, 2));
+        assert_eq!(
+            Decimal::new(55, 1).cmp(&Decimal::new(55, 1)),
+            Ordering::Equal
+        );
+    }
+}
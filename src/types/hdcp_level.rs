@@ -16,10 +16,43 @@ pub enum HdcpLevel {
     /// [`HDCP`]: https://www.digital-cp.com/sites/default/files/specifications/HDCP%20on%20HDMI%20Specification%20Rev2_2_Final1.pdf
     #[strum(serialize = "TYPE-0")]
     Type0,
+    /// The associated [`VariantStream`] could fail to play unless the output is
+    /// protected by High-bandwidth Digital Content Protection ([`HDCP`]) Type 1
+    /// or equivalent.
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    /// [`HDCP`]: https://www.digital-cp.com/sites/default/files/specifications/HDCP%20on%20HDMI%20Specification%20Rev2_2_Final1.pdf
+    #[strum(serialize = "TYPE-1")]
+    Type1,
     /// The content does not require output copy protection.
     None,
 }
 
+impl HdcpLevel {
+    /// Returns `true`, if an output, that is protected with the provided
+    /// [`HdcpLevel`], is sufficient to play content, that requires `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::HdcpLevel;
+    ///
+    /// assert!(HdcpLevel::Type0.is_satisfied_by(HdcpLevel::Type1));
+    /// assert!(HdcpLevel::None.is_satisfied_by(HdcpLevel::None));
+    /// assert!(!HdcpLevel::Type1.is_satisfied_by(HdcpLevel::Type0));
+    /// ```
+    #[must_use]
+    pub fn is_satisfied_by(self, capability: Self) -> bool { self.rank() <= capability.rank() }
+
+    const fn rank(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Type0 => 1,
+            Self::Type1 => 2,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,14 +61,31 @@ mod tests {
     #[test]
     fn test_display() {
         assert_eq!(HdcpLevel::Type0.to_string(), "TYPE-0".to_string());
+        assert_eq!(HdcpLevel::Type1.to_string(), "TYPE-1".to_string());
         assert_eq!(HdcpLevel::None.to_string(), "NONE".to_string());
     }
 
     #[test]
     fn test_parser() {
         assert_eq!(HdcpLevel::Type0, "TYPE-0".parse().unwrap());
+        assert_eq!(HdcpLevel::Type1, "TYPE-1".parse().unwrap());
         assert_eq!(HdcpLevel::None, "NONE".parse().unwrap());
 
         assert!("unk".parse::<HdcpLevel>().is_err());
     }
+
+    #[test]
+    fn test_is_satisfied_by() {
+        assert!(HdcpLevel::None.is_satisfied_by(HdcpLevel::None));
+        assert!(HdcpLevel::None.is_satisfied_by(HdcpLevel::Type0));
+        assert!(HdcpLevel::None.is_satisfied_by(HdcpLevel::Type1));
+
+        assert!(!HdcpLevel::Type0.is_satisfied_by(HdcpLevel::None));
+        assert!(HdcpLevel::Type0.is_satisfied_by(HdcpLevel::Type0));
+        assert!(HdcpLevel::Type0.is_satisfied_by(HdcpLevel::Type1));
+
+        assert!(!HdcpLevel::Type1.is_satisfied_by(HdcpLevel::None));
+        assert!(!HdcpLevel::Type1.is_satisfied_by(HdcpLevel::Type0));
+        assert!(HdcpLevel::Type1.is_satisfied_by(HdcpLevel::Type1));
+    }
 }
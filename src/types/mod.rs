@@ -1,8 +1,10 @@
 //! Miscellaneous types.
+pub(crate) mod allowed_cpc;
 pub(crate) mod byte_range;
 pub(crate) mod channels;
 pub(crate) mod closed_captions;
 pub(crate) mod codecs;
+pub(crate) mod cue;
 pub(crate) mod decryption_key;
 pub(crate) mod encryption_method;
 pub(crate) mod hdcp_level;
@@ -16,14 +18,18 @@ pub(crate) mod protocol_version;
 pub(crate) mod resolution;
 pub(crate) mod stream_data;
 pub(crate) mod value;
+pub(crate) mod video_layout;
 
+pub(crate) mod decimal;
 pub(crate) mod float;
 pub(crate) mod ufloat;
 
+pub use allowed_cpc::*;
 pub use byte_range::*;
 pub use channels::*;
 pub use closed_captions::*;
 pub use codecs::*;
+pub use cue::*;
 pub use decryption_key::DecryptionKey;
 pub use encryption_method::*;
 pub use hdcp_level::*;
@@ -37,6 +43,8 @@ pub use protocol_version::*;
 pub use resolution::*;
 pub use stream_data::StreamData;
 pub use value::*;
+pub use video_layout::*;
 
+pub use decimal::Decimal;
 pub use float::Float;
 pub use ufloat::UFloat;
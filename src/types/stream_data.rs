@@ -6,8 +6,10 @@ use derive_builder::Builder;
 use shorthand::ShortHand;
 
 use crate::attribute::AttributePairs;
-use crate::types::{Codecs, HdcpLevel, ProtocolVersion, Resolution};
-use crate::utils::{quote, unquote};
+use crate::types::{
+    AllowedCpc, Codecs, HdcpLevel, ProtocolVersion, Resolution, UFloat, VideoLayout,
+};
+use crate::utils::{quote, unquote, validate_stable_id};
 use crate::{Error, RequiredVersion};
 
 /// The [`StreamData`] struct contains the data that is shared between both
@@ -17,6 +19,7 @@ use crate::{Error, RequiredVersion};
 #[derive(ShortHand, Builder, PartialOrd, Debug, Clone, PartialEq, Eq, Hash, Ord)]
 #[builder(setter(strip_option))]
 #[builder(derive(Debug, PartialEq, PartialOrd, Ord, Eq, Hash))]
+#[builder(build_fn(validate = "Self::validate"))]
 #[shorthand(enable(must_use, into))]
 pub struct StreamData<'a> {
     /// The peak segment bitrate of the [`VariantStream`] in bits per second.
@@ -94,6 +97,34 @@ pub struct StreamData<'a> {
     #[builder(default)]
     #[shorthand(enable(copy), disable(into, option_as_ref))]
     average_bandwidth: Option<u64>,
+    /// An abstract, relative measure of the playback quality-of-experience of
+    /// the [`VariantStream`] (`SCORE`).
+    ///
+    /// A [`VariantStream`] with a higher score should be preferred over one
+    /// with a lower score within the same [`MasterPlaylist`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// use hls_m3u8::types::UFloat;
+    ///
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_score(Some(UFloat::new(1.5)));
+    /// assert_eq!(stream.score(), Some(UFloat::new(1.5)));
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional, but if any [`VariantStream`] has a score, all
+    /// of them should have one.
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    /// [`MasterPlaylist`]: crate::MasterPlaylist
+    #[builder(default)]
+    #[shorthand(enable(copy), disable(into))]
+    score: Option<UFloat>,
     /// A list of formats, where each format specifies a media sample type that
     /// is present in one or more renditions specified by the [`VariantStream`].
     ///
@@ -183,6 +214,38 @@ pub struct StreamData<'a> {
     #[builder(default)]
     #[shorthand(enable(copy), disable(into))]
     hdcp_level: Option<HdcpLevel>,
+    /// The Content Protection Configurations, that a client must satisfy to
+    /// play the [`VariantStream`] (`ALLOWED-CPC`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// use hls_m3u8::types::{AllowedCpc, CpcEntry};
+    ///
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_allowed_cpc(Some(vec![CpcEntry::new(
+    ///     "com.apple.streamingkeydelivery",
+    ///     vec!["AppleMain", "Main"],
+    /// )]));
+    ///
+    /// assert_eq!(
+    ///     stream.allowed_cpc(),
+    ///     Some(&AllowedCpc::from(vec![CpcEntry::new(
+    ///         "com.apple.streamingkeydelivery",
+    ///         vec!["AppleMain", "Main"]
+    ///     )]))
+    /// );
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional.
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    #[builder(default, setter(into))]
+    allowed_cpc: Option<AllowedCpc<'a>>,
     /// It indicates the set of video renditions, that should be used when
     /// playing the presentation.
     ///
@@ -212,6 +275,94 @@ pub struct StreamData<'a> {
     /// [`ExtXMedia::media_type`]: crate::tags::ExtXMedia::media_type
     #[builder(default, setter(into))]
     video: Option<Cow<'a, str>>,
+    /// The video layouts, that are required to play the [`VariantStream`],
+    /// ordered by preference (`REQ-VIDEO-LAYOUT`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// use hls_m3u8::types::{VideoChannelLayout, VideoLayout};
+    ///
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_video_layout(Some(vec![
+    ///     VideoChannelLayout::Stereo,
+    ///     VideoChannelLayout::Mono,
+    /// ]));
+    /// assert_eq!(
+    ///     stream.video_layout(),
+    ///     Some(&VideoLayout::from(vec![
+    ///         VideoChannelLayout::Stereo,
+    ///         VideoChannelLayout::Mono
+    ///     ]))
+    /// );
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional. The builder only allows a
+    /// [`VideoChannelLayout::Stereo`] layout, if the [`StreamData::codecs`]
+    /// contain a codec, that is able to carry multiple views (for example
+    /// MV-HEVC). A parsed playlist is not rejected, instead the [`Validator`]
+    /// reports [`Rule::VideoLayoutWithoutMultiviewCodec`].
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    /// [`Validator`]: crate::validate::Validator
+    /// [`Rule::VideoLayoutWithoutMultiviewCodec`]: crate::validate::Rule::VideoLayoutWithoutMultiviewCodec
+    /// [`VideoChannelLayout::Stereo`]: crate::types::VideoChannelLayout::Stereo
+    #[builder(default, setter(into))]
+    video_layout: Option<VideoLayout>,
+    /// An identifier, that stays the same for the [`VariantStream`] across
+    /// different versions of the [`MasterPlaylist`] (`STABLE-VARIANT-ID`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::StreamData;
+    /// #
+    /// let mut stream = StreamData::new(20);
+    ///
+    /// stream.set_stable_variant_id(Some("1080p-hevc"));
+    /// assert_eq!(stream.stable_variant_id(), Some(&"1080p-hevc".into()));
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This field is optional. The identifier must only contain the characters
+    /// `[a-z]`, `[A-Z]`, `[0-9]`, `+`, `/`, `=`, `.`, `-` and `_`.
+    ///
+    /// [`VariantStream`]: crate::tags::VariantStream
+    /// [`MasterPlaylist`]: crate::MasterPlaylist
+    #[builder(default, setter(into))]
+    stable_variant_id: Option<Cow<'a, str>>,
+}
+
+impl<'a> StreamDataBuilder<'a> {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(Some(allowed_cpc)) = &self.allowed_cpc {
+            if allowed_cpc.is_empty() {
+                return Err(Error::custom("the ALLOWED-CPC must not be empty").to_string());
+            }
+        }
+
+        if let Some(Some(video_layout)) = &self.video_layout {
+            if video_layout.is_empty() {
+                return Err(Error::custom("the REQ-VIDEO-LAYOUT must not be empty").to_string());
+            }
+        }
+
+        if let (Some(Some(codecs)), Some(Some(video_layout))) = (&self.codecs, &self.video_layout) {
+            video_layout.validate(codecs).map_err(|e| e.to_string())?;
+        }
+
+        if let Some(Some(stable_variant_id)) = &self.stable_variant_id {
+            validate_stable_id("STABLE-VARIANT-ID", stable_variant_id)
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a> StreamData<'a> {
@@ -229,10 +380,14 @@ impl<'a> StreamData<'a> {
         Self {
             bandwidth,
             average_bandwidth: None,
+            score: None,
             codecs: None,
             resolution: None,
             hdcp_level: None,
+            allowed_cpc: None,
             video: None,
+            video_layout: None,
+            stable_variant_id: None,
         }
     }
 
@@ -241,7 +396,7 @@ impl<'a> StreamData<'a> {
     /// # Example
     ///
     /// ```
-    /// use hls_m3u8::types::{HdcpLevel, StreamData};
+    /// use hls_m3u8::types::{HdcpLevel, StreamData, VideoChannelLayout};
     ///
     /// StreamData::builder()
     ///     .bandwidth(200)
@@ -250,6 +405,7 @@ impl<'a> StreamData<'a> {
     ///     .resolution((1920, 1080))
     ///     .hdcp_level(HdcpLevel::Type0)
     ///     .video("video_01")
+    ///     .video_layout(VideoChannelLayout::Mono)
     ///     .build()?;
     /// # Ok::<(), Box<dyn ::std::error::Error>>(())
     /// ```
@@ -267,10 +423,16 @@ impl<'a> StreamData<'a> {
         StreamData {
             bandwidth: self.bandwidth,
             average_bandwidth: self.average_bandwidth,
+            score: self.score,
             codecs: self.codecs.map(|v| v.into_owned()),
             resolution: self.resolution,
             hdcp_level: self.hdcp_level,
+            allowed_cpc: self.allowed_cpc.map(AllowedCpc::into_owned),
             video: self.video.map(|v| Cow::Owned(v.into_owned())),
+            video_layout: self.video_layout,
+            stable_variant_id: self
+                .stable_variant_id
+                .map(|v| Cow::Owned(v.into_owned())),
         }
     }
 }
@@ -282,6 +444,9 @@ impl<'a> fmt::Display for StreamData<'a> {
         if let Some(value) = &self.average_bandwidth {
             write!(f, ",AVERAGE-BANDWIDTH={}", value)?;
         }
+        if let Some(value) = &self.score {
+            write!(f, ",SCORE={}", value)?;
+        }
         if let Some(value) = &self.codecs {
             write!(f, ",CODECS={}", quote(value))?;
         }
@@ -291,9 +456,20 @@ impl<'a> fmt::Display for StreamData<'a> {
         if let Some(value) = &self.hdcp_level {
             write!(f, ",HDCP-LEVEL={}", value)?;
         }
+        // an empty list can not be parsed, so it is not written:
+        if let Some(value) = self.allowed_cpc.as_ref().filter(|v| !v.is_empty()) {
+            write!(f, ",ALLOWED-CPC={}", quote(value))?;
+        }
         if let Some(value) = &self.video {
             write!(f, ",VIDEO={}", quote(value))?;
         }
+        // an empty list can not be parsed, so it is not written:
+        if let Some(value) = self.video_layout.as_ref().filter(|v| !v.is_empty()) {
+            write!(f, ",REQ-VIDEO-LAYOUT={}", quote(value))?;
+        }
+        if let Some(value) = &self.stable_variant_id {
+            write!(f, ",STABLE-VARIANT-ID={}", quote(value))?;
+        }
         Ok(())
     }
 }
@@ -304,10 +480,14 @@ impl<'a> TryFrom<&'a str> for StreamData<'a> {
     fn try_from(input: &'a str) -> Result<Self, Self::Error> {
         let mut bandwidth = None;
         let mut average_bandwidth = None;
+        let mut score = None;
         let mut codecs = None;
         let mut resolution = None;
         let mut hdcp_level = None;
+        let mut allowed_cpc = None;
         let mut video = None;
+        let mut video_layout = None;
+        let mut stable_variant_id = None;
 
         for (key, value) in AttributePairs::new(input) {
             match key {
@@ -325,12 +505,20 @@ impl<'a> TryFrom<&'a str> for StreamData<'a> {
                             .map_err(|e| Error::parse_int(value, e))?,
                     )
                 }
+                "SCORE" => score = Some(value.parse()?),
                 "CODECS" => codecs = Some(TryFrom::try_from(unquote(value))?),
                 "RESOLUTION" => resolution = Some(value.parse()?),
                 "HDCP-LEVEL" => {
                     hdcp_level = Some(value.parse::<HdcpLevel>().map_err(Error::strum)?)
                 }
+                "ALLOWED-CPC" => allowed_cpc = Some(AllowedCpc::try_from(unquote(value))?),
                 "VIDEO" => video = Some(unquote(value)),
+                "REQ-VIDEO-LAYOUT" => video_layout = Some(unquote(value).parse::<VideoLayout>()?),
+                "STABLE-VARIANT-ID" => {
+                    let value = unquote(value);
+                    validate_stable_id(key, &value)?;
+                    stable_variant_id = Some(value);
+                }
                 _ => {
                     // [6.3.1. General Client Responsibilities]
                     // > ignore any attribute/value pair with an unrecognized
@@ -341,13 +529,21 @@ impl<'a> TryFrom<&'a str> for StreamData<'a> {
 
         let bandwidth = bandwidth.ok_or_else(|| Error::missing_value("BANDWIDTH"))?;
 
+        // a stereo layout without a multiview codec is not rejected here, but
+        // reported by the `Validator` (`Rule::VideoLayoutWithoutMultiviewCodec`)
+
+
         Ok(Self {
             bandwidth,
             average_bandwidth,
+            score,
             codecs,
             resolution,
             hdcp_level,
+            allowed_cpc,
             video,
+            video_layout,
+            stable_variant_id,
         })
     }
 }
@@ -368,6 +564,7 @@ impl<'a> RequiredVersion for StreamData<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{CpcEntry, VideoChannelLayout};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -417,4 +614,129 @@ mod tests {
 
         assert!(StreamData::try_from("garbage").is_err());
     }
+
+    #[test]
+    fn test_score_and_stable_variant_id() {
+        let mut stream_data = StreamData::new(200);
+        stream_data.set_score(Some(UFloat::new(12.5)));
+        stream_data.set_stable_variant_id(Some("hd/1080p+hevc"));
+
+        let input = concat!(
+            "BANDWIDTH=200,",
+            "SCORE=12.5,",
+            "STABLE-VARIANT-ID=\"hd/1080p+hevc\""
+        );
+
+        assert_eq!(stream_data.to_string(), input.to_string());
+        assert_eq!(stream_data, StreamData::try_from(input).unwrap());
+
+        assert!(StreamData::try_from("BANDWIDTH=200,SCORE=-1").is_err());
+        assert!(StreamData::try_from("BANDWIDTH=200,STABLE-VARIANT-ID=\"a b\"").is_err());
+        assert!(StreamData::builder()
+            .bandwidth(200)
+            .stable_variant_id("a#b")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_allowed_cpc() {
+        let mut stream_data = StreamData::new(200);
+        stream_data.set_hdcp_level(Some(HdcpLevel::Type1));
+        stream_data.set_allowed_cpc(Some(vec![
+            CpcEntry::new("com.apple.streamingkeydelivery", vec!["AppleMain", "Main"]),
+            CpcEntry::new("com.microsoft.playready", vec!["SL3000"]),
+        ]));
+
+        let input = concat!(
+            "BANDWIDTH=200,",
+            "HDCP-LEVEL=TYPE-1,",
+            "ALLOWED-CPC=\"",
+            "com.apple.streamingkeydelivery:AppleMain/Main,",
+            "com.microsoft.playready:SL3000",
+            "\""
+        );
+
+        assert_eq!(stream_data.to_string(), input.to_string());
+        assert_eq!(stream_data, StreamData::try_from(input).unwrap());
+
+        assert!(StreamData::try_from("BANDWIDTH=200,ALLOWED-CPC=\"SL3000\"").is_err());
+
+        assert!(StreamData::builder()
+            .bandwidth(200)
+            .allowed_cpc(AllowedCpc::new())
+            .build()
+            .is_err());
+
+        stream_data.set_allowed_cpc(Some(AllowedCpc::new()));
+        assert_eq!(
+            stream_data.to_string(),
+            "BANDWIDTH=200,HDCP-LEVEL=TYPE-1".to_string()
+        );
+    }
+
+    #[test]
+    fn test_video_layout() {
+        let mut stream_data = StreamData::new(200);
+        stream_data.set_codecs(Some(&["hvc1.6.40.L120.B0", "mp4a.40.2"]));
+        stream_data.set_video_layout(Some(vec![
+            VideoChannelLayout::Stereo,
+            VideoChannelLayout::Mono,
+        ]));
+
+        assert_eq!(
+            stream_data.to_string(),
+            concat!(
+                "BANDWIDTH=200,",
+                "CODECS=\"hvc1.6.40.L120.B0,mp4a.40.2\",",
+                "REQ-VIDEO-LAYOUT=\"CH-STEREO,CH-MONO\""
+            )
+            .to_string()
+        );
+
+        assert_eq!(
+            stream_data,
+            StreamData::try_from(concat!(
+                "BANDWIDTH=200,",
+                "CODECS=\"hvc1.6.40.L120.B0,mp4a.40.2\",",
+                "REQ-VIDEO-LAYOUT=\"CH-STEREO,CH-MONO\""
+            ))
+            .unwrap()
+        );
+
+        // a stereo layout requires a multiview codec, which is only checked by
+        // the builder:
+        assert!(StreamData::try_from(concat!(
+            "BANDWIDTH=200,",
+            "CODECS=\"avc1.4d401e,mp4a.40.2\",",
+            "REQ-VIDEO-LAYOUT=\"CH-STEREO\""
+        ))
+        .is_ok());
+
+        assert!(StreamData::builder()
+            .bandwidth(200)
+            .codecs(&["avc1.4d401e", "mp4a.40.2"])
+            .video_layout(VideoChannelLayout::Stereo)
+            .build()
+            .is_err());
+
+        assert!(StreamData::builder()
+            .bandwidth(200)
+            .codecs(&["avc1.4d401e", "mp4a.40.2"])
+            .video_layout(VideoChannelLayout::Mono)
+            .build()
+            .is_ok());
+
+        assert!(StreamData::builder()
+            .bandwidth(200)
+            .video_layout(VideoLayout::new())
+            .build()
+            .is_err());
+
+        stream_data.set_video_layout(Some(VideoLayout::new()));
+        assert_eq!(
+            stream_data.to_string(),
+            "BANDWIDTH=200,CODECS=\"hvc1.6.40.L120.B0,mp4a.40.2\"".to_string()
+        );
+    }
 }
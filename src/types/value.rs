@@ -2,7 +2,7 @@ use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt;
 
-use crate::types::Float;
+use crate::types::{Decimal, Float};
 use crate::utils::{quote, unquote};
 use crate::Error;
 
@@ -16,6 +16,14 @@ pub enum Value<'a> {
     Hex(Vec<u8>),
     /// A floating point number, that's neither NaN nor infinite.
     Float(Float),
+    /// An exact, non-negative decimal number.
+    ///
+    /// A number is parsed as a [`Value::Float`], except for the offsets of an
+    /// [`Interstitial`], this variant allows to write a number without losing
+    /// precision.
+    ///
+    /// [`Interstitial`]: crate::tags::Interstitial
+    Decimal(Decimal),
 }
 
 impl<'a> Value<'a> {
@@ -31,6 +39,7 @@ impl<'a> Value<'a> {
             Self::String(value) => Value::String(Cow::Owned(value.into_owned())),
             Self::Hex(value) => Value::Hex(value),
             Self::Float(value) => Value::Float(value),
+            Self::Decimal(value) => Value::Decimal(value),
         }
     }
 }
@@ -41,6 +50,7 @@ impl<'a> fmt::Display for Value<'a> {
             Self::String(value) => write!(f, "{}", quote(value)),
             Self::Hex(value) => write!(f, "0x{}", hex::encode_upper(value)),
             Self::Float(value) => write!(f, "{}", value),
+            Self::Decimal(value) => write!(f, "{}", value),
         }
     }
 }
@@ -0,0 +1,267 @@
+use core::fmt;
+use core::iter::FromIterator;
+use core::str::FromStr;
+
+use derive_more::{AsMut, AsRef, Deref, DerefMut};
+
+use crate::types::Codecs;
+use crate::Error;
+
+/// A video layout specifier, that describes which views are present in the
+/// video.
+///
+/// Specifiers, that are not known by this crate (for example of the `PROJ`
+/// domain), are kept as [`VideoChannelLayout::Other`].
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VideoChannelLayout {
+    /// The video contains stereoscopic content (a left and a right view), for
+    /// example MV-HEVC (`CH-STEREO`).
+    Stereo,
+    /// The video contains monoscopic content (a single view) (`CH-MONO`).
+    Mono,
+    /// Any other specifier.
+    Other(String),
+}
+
+impl fmt::Display for VideoChannelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stereo => write!(f, "CH-STEREO"),
+            Self::Mono => write!(f, "CH-MONO"),
+            Self::Other(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl FromStr for VideoChannelLayout {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "CH-STEREO" => Ok(Self::Stereo),
+            "CH-MONO" => Ok(Self::Mono),
+            "" => Err(Error::custom("the video layout specifier is empty")),
+            other => Ok(Self::Other(other.to_string())),
+        }
+    }
+}
+
+/// The value of the `REQ-VIDEO-LAYOUT` attribute, which is a list of video
+/// layouts, that are required to play a [`VariantStream`].
+///
+/// The layouts are ordered by preference, so the first entry is the preferred
+/// layout of the [`VariantStream`].
+///
+/// # Example
+///
+/// ```
+/// use hls_m3u8::types::{VideoChannelLayout, VideoLayout};
+///
+/// let layout = VideoLayout::from(vec![VideoChannelLayout::Stereo, VideoChannelLayout::Mono]);
+///
+/// assert_eq!(layout.to_string(), "CH-STEREO,CH-MONO".to_string());
+/// assert!(layout.is_stereo());
+/// ```
+///
+/// [`VariantStream`]: crate::tags::VariantStream
+#[derive(
+    AsMut, AsRef, Deref, DerefMut, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct VideoLayout {
+    list: Vec<VideoChannelLayout>,
+}
+
+impl VideoLayout {
+    /// Makes a new (empty) [`VideoLayout`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hls_m3u8::types::VideoLayout;
+    /// let layout = VideoLayout::new();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self { Self { list: Vec::new() } }
+
+    /// Returns `true`, if the [`VideoLayout`] contains
+    /// [`VideoChannelLayout::Stereo`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hls_m3u8::types::{VideoChannelLayout, VideoLayout};
+    ///
+    /// assert!(VideoLayout::from(vec![VideoChannelLayout::Stereo]).is_stereo());
+    /// assert!(!VideoLayout::from(vec![VideoChannelLayout::Mono]).is_stereo());
+    /// ```
+    #[must_use]
+    pub fn is_stereo(&self) -> bool { self.list.contains(&VideoChannelLayout::Stereo) }
+
+    /// Checks, that the [`VideoLayout`] can be carried by one of the provided
+    /// [`Codecs`].
+    ///
+    /// Stereoscopic video requires a codec, that can carry more than a single
+    /// view, which are the multiview extensions of AVC (`mvc1`, `mvc2`) and
+    /// HEVC (`lhv1`, `lhe1` or `hvc1`/`hev1` with the Multiview Main profile,
+    /// like `hvc1.6.40.L120.B0`).
+    ///
+    /// # Error
+    ///
+    /// This function returns an error, if the layout is stereoscopic and none
+    /// of the codecs is able to carry multiple views.
+    pub(crate) fn validate(&self, codecs: &Codecs<'_>) -> crate::Result<()> {
+        if !self.is_stereo() {
+            return Ok(());
+        }
+
+        let has_multiview_codec = codecs.iter().any(|codec| is_multiview_codec(codec));
+
+        if has_multiview_codec {
+            Ok(())
+        } else {
+            Err(Error::custom(format!(
+                "the video layout {:?} requires a multiview codec, but found: {:?}",
+                VideoChannelLayout::Stereo.to_string(),
+                codecs.to_string()
+            )))
+        }
+    }
+}
+
+/// Returns `true`, if the codec (in the format of the `CODECS` attribute) can
+/// carry multiple views.
+fn is_multiview_codec(codec: &str) -> bool {
+    // the general_profile_idc of the HEVC Multiview Main profile
+    // (ITU-T H.265, Annex G.11.1.1):
+    const MULTIVIEW_MAIN: u8 = 6;
+
+    let mut parts = codec.split('.');
+
+    match parts.next().unwrap_or_default() {
+        "mvc1" | "mvc2" | "lhv1" | "lhe1" => true,
+        "hvc1" | "hev1" => {
+            // the profile can be prefixed with the general_profile_space
+            // (`A`, `B` or `C`):
+            parts
+                .next()
+                .map(|profile| profile.trim_start_matches(&['A', 'B', 'C'][..]))
+                .and_then(|profile| profile.parse::<u8>().ok())
+                == Some(MULTIVIEW_MAIN)
+        }
+        _ => false,
+    }
+}
+
+impl From<Vec<VideoChannelLayout>> for VideoLayout {
+    fn from(list: Vec<VideoChannelLayout>) -> Self { Self { list } }
+}
+
+impl From<VideoChannelLayout> for VideoLayout {
+    fn from(value: VideoChannelLayout) -> Self { Self { list: vec![value] } }
+}
+
+impl FromIterator<VideoChannelLayout> for VideoLayout {
+    fn from_iter<I: IntoIterator<Item = VideoChannelLayout>>(iter: I) -> Self {
+        Self {
+            list: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for VideoLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(layout) = self.list.iter().next() {
+            write!(f, "{}", layout)?;
+
+            for layout in self.list.iter().skip(1) {
+                write!(f, ",{}", layout)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for VideoLayout {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .split(',')
+            .map(|s| s.trim().parse::<VideoChannelLayout>())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            VideoLayout::from(vec![VideoChannelLayout::Stereo, VideoChannelLayout::Mono])
+                .to_string(),
+            "CH-STEREO,CH-MONO".to_string()
+        );
+        assert_eq!(
+            VideoLayout::from(VideoChannelLayout::Mono).to_string(),
+            "CH-MONO".to_string()
+        );
+        assert_eq!(VideoLayout::new().to_string(), "".to_string());
+    }
+
+    #[test]
+    fn test_parser() {
+        assert_eq!(
+            VideoLayout::from(vec![VideoChannelLayout::Stereo, VideoChannelLayout::Mono]),
+            "CH-STEREO,CH-MONO".parse().unwrap()
+        );
+        assert_eq!(
+            VideoLayout::from(VideoChannelLayout::Mono),
+            "CH-MONO".parse().unwrap()
+        );
+
+        // unknown specifiers are kept:
+        assert_eq!(
+            VideoLayout::from(vec![
+                VideoChannelLayout::Other("PROJ-EQUI".into()),
+                VideoChannelLayout::Mono
+            ]),
+            "PROJ-EQUI,CH-MONO".parse().unwrap()
+        );
+        assert_eq!(
+            "PROJ-EQUI".parse::<VideoLayout>().unwrap().to_string(),
+            "PROJ-EQUI".to_string()
+        );
+
+        assert!("".parse::<VideoLayout>().is_err());
+        assert!("CH-MONO,".parse::<VideoLayout>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let stereo = VideoLayout::from(vec![VideoChannelLayout::Stereo, VideoChannelLayout::Mono]);
+        let mono = VideoLayout::from(VideoChannelLayout::Mono);
+
+        assert!(stereo
+            .validate(&Codecs::from(["hvc1.6.40.L120.B0", "mp4a.40.2"]))
+            .is_ok());
+        assert!(stereo.validate(&Codecs::from(["hev1.A6.40.L120.B0"])).is_ok());
+        assert!(stereo.validate(&Codecs::from(["mvc1.800030"])).is_ok());
+        assert!(stereo.validate(&Codecs::from(["lhv1.6.40.L120.B0"])).is_ok());
+        // a single view HEVC profile (Main 10):
+        assert!(stereo
+            .validate(&Codecs::from(["hvc1.2.20000000.H123.B0", "mp4a.40.2"]))
+            .is_err());
+        assert!(stereo.validate(&Codecs::from(["hvc1"])).is_err());
+        assert!(stereo
+            .validate(&Codecs::from(["avc1.4d401e", "mp4a.40.2"]))
+            .is_err());
+
+        assert!(mono.validate(&Codecs::from(["avc1.4d401e"])).is_ok());
+    }
+}
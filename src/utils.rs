@@ -61,6 +61,33 @@ pub(crate) fn parse_yes_or_no<T: AsRef<str>>(s: T) -> crate::Result<bool> {
     }
 }
 
+/// Checks, that a stable identifier (e.g. `STABLE-VARIANT-ID`) only consists of
+/// the characters `[a-z]`, `[A-Z]`, `[0-9]`, `+`, `/`, `=`, `.`, `-` and `_`.
+///
+/// # Error
+///
+/// This function returns an error, if the identifier is empty or contains a
+/// character, that is not allowed.
+pub(crate) fn validate_stable_id<T: AsRef<str>>(attribute: &str, value: T) -> crate::Result<()> {
+    let value = value.as_ref();
+
+    if value.is_empty() {
+        return Err(Error::missing_value(attribute));
+    }
+
+    if let Some(c) = value
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || "+/=.-_".contains(*c)))
+    {
+        return Err(Error::custom(format!(
+            "invalid character {:?} in {}: {:?}",
+            c, attribute, value
+        )));
+    }
+
+    Ok(())
+}
+
 /// According to the documentation the following characters are forbidden
 /// inside a quoted string:
 /// - carriage return (`\r`)
@@ -119,6 +146,145 @@ where
     Ok(input.trim().split_at(tag.as_ref().len()).1)
 }
 
+/// The components of an URI reference.
+///
+/// [rfc3986#section-3](https://tools.ietf.org/html/rfc3986#section-3)
+struct UriParts<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+}
+
+impl<'a> UriParts<'a> {
+    fn new(uri: &'a str) -> Self {
+        // the fragment is not needed to resolve a reference
+        let uri = uri.split('#').next().unwrap();
+
+        let (uri, query) = {
+            let mut parts = uri.splitn(2, '?');
+            (parts.next().unwrap(), parts.next())
+        };
+
+        let scheme = uri.find(':').map(|i| &uri[..i]).filter(|scheme| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        });
+
+        let rest = scheme.map_or(uri, |scheme| &uri[scheme.len() + 1..]);
+
+        let (authority, path) = {
+            if let Some(rest) = rest.strip_prefix("//") {
+                let end = rest.find('/').unwrap_or_else(|| rest.len());
+                (Some(&rest[..end]), &rest[end..])
+            } else {
+                (None, rest)
+            }
+        };
+
+        Self {
+            scheme,
+            authority,
+            path,
+            query,
+        }
+    }
+}
+
+/// Removes the `.` and `..` segments from a path.
+///
+/// [rfc3986#section-5.2.4](https://tools.ietf.org/html/rfc3986#section-5.2.4)
+fn remove_dot_segments(path: &str) -> String {
+    let mut output: Vec<&str> = vec![];
+    let segments = path.split('/').collect::<Vec<_>>();
+
+    for (i, segment) in segments.iter().enumerate() {
+        let is_last = i == segments.len() - 1;
+
+        match *segment {
+            "." => {
+                if is_last {
+                    output.push("");
+                }
+            }
+            ".." => {
+                // the root can not be removed
+                if output.len() > 1 || output.first().map_or(false, |s| !s.is_empty()) {
+                    output.pop();
+                }
+
+                if is_last {
+                    output.push("");
+                }
+            }
+            _ => output.push(segment),
+        }
+    }
+
+    output.join("/")
+}
+
+/// Resolves an URI reference (for example the URI of a [`MediaSegment`])
+/// against the URI of the playlist, that contains it.
+///
+/// An absolute reference or an empty base are returned unchanged.
+///
+/// [rfc3986#section-5.2](https://tools.ietf.org/html/rfc3986#section-5.2)
+///
+/// [`MediaSegment`]: crate::MediaSegment
+pub(crate) fn resolve_uri<'a>(base: &str, reference: Cow<'a, str>) -> Cow<'a, str> {
+    let fragment = reference.find('#').map_or("", |i| &reference[i..]);
+    let target = UriParts::new(&reference);
+
+    if base.is_empty() || target.scheme.is_some() {
+        return reference;
+    }
+
+    let base = UriParts::new(base);
+    let mut result = String::new();
+
+    if let Some(scheme) = base.scheme {
+        result.push_str(scheme);
+        result.push(':');
+    }
+
+    if let Some(authority) = target.authority {
+        result.push_str("//");
+        result.push_str(authority);
+        result.push_str(&remove_dot_segments(target.path));
+    } else {
+        if let Some(authority) = base.authority {
+            result.push_str("//");
+            result.push_str(authority);
+        }
+
+        if target.path.is_empty() {
+            result.push_str(base.path);
+        } else if target.path.starts_with('/') {
+            result.push_str(&remove_dot_segments(target.path));
+        } else if base.authority.is_some() && base.path.is_empty() {
+            result.push_str(&remove_dot_segments(&format!("/{}", target.path)));
+        } else {
+            let directory = base.path.rfind('/').map_or("", |i| &base.path[..=i]);
+            result.push_str(&remove_dot_segments(&format!("{}{}", directory, target.path)));
+        }
+    }
+
+    if let Some(query) = target
+        .query
+        .or_else(|| base.query.filter(|_| target.path.is_empty() && target.authority.is_none()))
+    {
+        result.push('?');
+        result.push_str(query);
+    }
+
+    result.push_str(fragment);
+
+    Cow::Owned(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,6 +297,15 @@ mod tests {
         assert!(parse_yes_or_no("garbage").is_err());
     }
 
+    #[test]
+    fn test_validate_stable_id() {
+        assert!(validate_stable_id("STABLE-VARIANT-ID", "1080p_hevc-v2.0").is_ok());
+        assert!(validate_stable_id("STABLE-VARIANT-ID", "aGVsbG8+/w==").is_ok());
+        assert!(validate_stable_id("STABLE-VARIANT-ID", "").is_err());
+        assert!(validate_stable_id("STABLE-VARIANT-ID", "with space").is_err());
+        assert!(validate_stable_id("STABLE-RENDITION-ID", "ünicode").is_err());
+    }
+
     #[test]
     fn test_unquote() {
         assert_eq!(unquote("\"TestValue\""), "TestValue".to_string());
@@ -187,4 +362,48 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_resolve_uri() {
+        // https://tools.ietf.org/html/rfc3986#section-5.4
+        let base = "http://a/b/c/d;p?q";
+
+        for (reference, expected) in &[
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            ("./../g", "http://a/b/g"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+        ] {
+            assert_eq!(resolve_uri(base, Cow::Borrowed(reference)), *expected);
+        }
+
+        assert_eq!(
+            resolve_uri("https://example.com/vod/main.m3u8", "segment/1.ts".into()),
+            "https://example.com/vod/segment/1.ts"
+        );
+        assert_eq!(resolve_uri("", "segment/1.ts".into()), "segment/1.ts");
+        assert_eq!(resolve_uri("ads/pre.m3u8", "1.ts".into()), "ads/1.ts");
+    }
 }
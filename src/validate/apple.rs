@@ -0,0 +1,377 @@
+//! The opt-in rules of the Apple HLS authoring specification, which are
+//! checked over a [`MasterPlaylist`] and its resolved [`MediaPlaylist`]s.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+
+use strum::IntoEnumIterator;
+
+use super::{Finding, Location, Rule, Validator};
+use crate::tags::VariantStream;
+use crate::types::MediaType;
+use crate::{MasterPlaylist, MediaPlaylist};
+
+/// The target duration, that is required by the specification.
+const TARGET_DURATION: Duration = Duration::from_secs(6);
+
+/// The sample entries of the video formats, that are supported by Apple
+/// devices.
+const VIDEO_FORMATS: &[&str] = &[
+    "avc1", "avc3", "hvc1", "hev1", "dvh1", "dvhe", "dva1", "dvav", "av01", "vp09",
+];
+
+impl Validator {
+    /// Enables all rules of the Apple HLS authoring specification (those with
+    /// a [`Rule::apple_section`]) with their [`Rule::default_severity`].
+    pub fn enable_apple_authoring(&mut self) -> &mut Self {
+        for rule in Rule::iter().filter(|rule| rule.apple_section().is_some()) {
+            self.enable(rule);
+        }
+
+        self
+    }
+
+    /// Checks a [`MasterPlaylist`] and the [`MediaPlaylist`]s, that it refers
+    /// to, against the enabled rules of the Apple HLS authoring specification.
+    ///
+    /// The media playlists are provided with the uri, that is used in the
+    /// master playlist. Media playlists, that are not provided, are not
+    /// checked.
+    ///
+    /// The findings are located at the variant stream or the rendition, that
+    /// refers to the media playlist, and returned in the order of their
+    /// location.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::convert::TryFrom;
+    /// use hls_m3u8::validate::{Location, Rule, Validator};
+    /// use hls_m3u8::{MasterPlaylist, MediaPlaylist};
+    ///
+    /// let master_playlist = MasterPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-INDEPENDENT-SEGMENTS\n",
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=1000000,AVERAGE-BANDWIDTH=900000,",
+    ///     "CODECS=\"avc1.4d401f,mp4a.40.2\",RESOLUTION=1280x720,FRAME-RATE=30\n",
+    ///     "video.m3u8\n",
+    ///     "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100000,CODECS=\"avc1.4d401f\",",
+    ///     "RESOLUTION=1280x720,URI=\"iframes.m3u8\"\n",
+    /// ))?;
+    ///
+    /// let media_playlist = MediaPlaylist::try_from(concat!(
+    ///     "#EXTM3U\n",
+    ///     "#EXT-X-TARGETDURATION:10\n",
+    ///     "#EXTINF:10,\n",
+    ///     "segment.ts\n",
+    /// ))?;
+    ///
+    /// let findings = Validator::empty()
+    ///     .enable_apple_authoring()
+    ///     .validate_apple_authoring(&master_playlist, vec![("video.m3u8", &media_playlist)]);
+    ///
+    /// assert_eq!(findings.len(), 1);
+    /// assert_eq!(findings[0].rule, Rule::AppleTargetDuration);
+    /// assert_eq!(findings[0].location, Location::VariantStream(0));
+    /// assert_eq!(findings[0].rule.apple_section(), Some("6.1"));
+    /// assert_eq!(
+    ///     findings[0].to_string(),
+    ///     "warning[apple-target-duration] variant stream 0: the target duration 10s is not 6s (section 6.1)"
+    /// );
+    /// # Ok::<(), hls_m3u8::Error>(())
+    /// ```
+    #[must_use]
+    pub fn validate_apple_authoring<'b, 'c, I>(
+        &self,
+        master_playlist: &MasterPlaylist<'_>,
+        media_playlists: I,
+    ) -> Vec<Finding>
+    where
+        I: IntoIterator<Item = (&'b str, &'b MediaPlaylist<'c>)>,
+        'c: 'b,
+    {
+        let media_playlists = media_playlists.into_iter().collect::<HashMap<_, _>>();
+        let mut findings = vec![];
+
+        let has_video = master_playlist.variant_streams.iter().any(|stream| {
+            matches!(stream, VariantStream::ExtXStreamInf { .. }) && is_video(stream)
+        });
+
+        let has_i_frames = master_playlist
+            .variant_streams
+            .iter()
+            .any(|stream| matches!(stream, VariantStream::ExtXIFrame { .. }));
+
+        if has_video && !has_i_frames {
+            self.report(
+                &mut findings,
+                Rule::AppleMissingIFramePlaylists,
+                Location::Playlist,
+                "the master playlist has video variant streams, but no EXT-X-I-FRAME-STREAM-INF",
+            );
+        }
+
+        self.check_audio_groups(&mut findings, master_playlist);
+
+        for (i, stream) in master_playlist.variant_streams.iter().enumerate() {
+            let location = Location::VariantStream(i);
+            let uri = match stream {
+                VariantStream::ExtXStreamInf { uri, .. } | VariantStream::ExtXIFrame { uri, .. } => uri,
+            };
+            let media_playlist = media_playlists.get(uri.as_ref());
+
+            if stream.codecs().is_none() {
+                self.report(
+                    &mut findings,
+                    Rule::AppleMissingCodecs,
+                    location,
+                    "the variant stream has no CODECS attribute",
+                );
+            }
+
+            if is_video(stream) && stream.resolution().is_none() {
+                self.report(
+                    &mut findings,
+                    Rule::AppleMissingResolution,
+                    location,
+                    "the video variant stream has no RESOLUTION attribute",
+                );
+            }
+
+            if let VariantStream::ExtXStreamInf { frame_rate, .. } = stream {
+                if is_video(stream) && frame_rate.is_none() {
+                    self.report(
+                        &mut findings,
+                        Rule::AppleMissingFrameRate,
+                        location,
+                        "the video variant stream has no FRAME-RATE attribute",
+                    );
+                }
+
+                if stream.average_bandwidth().is_none() {
+                    self.report(
+                        &mut findings,
+                        Rule::AppleMissingAverageBandwidth,
+                        location,
+                        "the variant stream has no AVERAGE-BANDWIDTH attribute",
+                    );
+                }
+
+                if let Some(media_playlist) = media_playlist {
+                    if is_video(stream)
+                        && !master_playlist.has_independent_segments
+                        && !media_playlist.has_independent_segments
+                    {
+                        self.report(
+                            &mut findings,
+                            Rule::AppleIndependentSegments,
+                            location,
+                            "neither the master playlist nor the media playlist has an EXT-X-INDEPENDENT-SEGMENTS tag",
+                        );
+                    }
+                }
+            }
+
+            if let Some(media_playlist) = media_playlist {
+                self.check_target_duration(&mut findings, location, media_playlist);
+            }
+        }
+
+        for (i, media) in master_playlist.media.iter().enumerate() {
+            let media_playlist = media
+                .uri()
+                .and_then(|uri| media_playlists.get(uri.as_ref()));
+
+            if let Some(media_playlist) = media_playlist {
+                self.check_target_duration(&mut findings, Location::Rendition(i), media_playlist);
+            }
+        }
+
+        findings.sort_by_key(|finding| finding.location);
+        findings
+    }
+
+    fn check_target_duration(
+        &self,
+        findings: &mut Vec<Finding>,
+        location: Location,
+        media_playlist: &MediaPlaylist<'_>,
+    ) {
+        if media_playlist.target_duration != TARGET_DURATION {
+            self.report(
+                findings,
+                Rule::AppleTargetDuration,
+                location,
+                format!(
+                    "the target duration {:?} is not {:?}",
+                    media_playlist.target_duration, TARGET_DURATION
+                ),
+            );
+        }
+    }
+
+    /// Every audio group has to offer the same renditions, so that a client
+    /// can switch between variant streams without changing the audio
+    /// language.
+    fn check_audio_groups(&self, findings: &mut Vec<Finding>, master_playlist: &MasterPlaylist<'_>) {
+        let mut groups = BTreeMap::new();
+
+        for (i, media) in master_playlist.media.iter().enumerate() {
+            if media.media_type == MediaType::Audio {
+                groups
+                    .entry(media.group_id().as_ref())
+                    .or_insert_with(|| (i, BTreeSet::new()))
+                    .1
+                    .insert((
+                        media.name().as_ref(),
+                        media.language().map(AsRef::as_ref),
+                    ));
+            }
+        }
+
+        // the groups are compared with the first group of the master playlist:
+        let mut groups = groups
+            .into_iter()
+            .map(|(id, (i, renditions))| (i, id, renditions))
+            .collect::<Vec<_>>();
+
+        groups.sort_by_key(|(i, _, _)| *i);
+
+        let mut groups = groups.into_iter();
+
+        if let Some((_, first_id, first)) = groups.next() {
+            for (i, id, renditions) in groups {
+                if renditions != first {
+                    self.report(
+                        findings,
+                        Rule::AppleAudioGroups,
+                        Location::Rendition(i),
+                        format!(
+                            "the audio group {:?} does not offer the same renditions as the audio group {:?}",
+                            id, first_id
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true`, if the stream contains video. A stream without a `CODECS`
+/// attribute is assumed to contain video.
+fn is_video(stream: &VariantStream<'_>) -> bool {
+    stream.codecs().map_or(true, |codecs| {
+        codecs.iter().any(|codec| {
+            let format = codec.split('.').next().unwrap_or_default();
+            VIDEO_FORMATS.contains(&format)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+    use pretty_assertions::assert_eq;
+
+    fn rules(findings: &[Finding]) -> Vec<(Rule, Location)> {
+        findings.iter().map(|f| (f.rule, f.location)).collect()
+    }
+
+    fn media_playlist(target_duration: u64, has_independent_segments: bool) -> MediaPlaylist<'static> {
+        MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(target_duration))
+            .has_independent_segments(has_independent_segments)
+            .segments(vec![])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_opt_in() {
+        let validator = Validator::new();
+
+        assert!(!validator.is_enabled(Rule::AppleMissingCodecs));
+        assert!(validator.is_enabled(Rule::MissingCodecs));
+
+        let playlist = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000\n",
+            "low.m3u8\n",
+        ))
+        .unwrap();
+
+        assert!(validator
+            .validate_apple_authoring(&playlist, vec![])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_validate_apple_authoring() {
+        let master_playlist = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",LANGUAGE=\"en\",URI=\"aac/en.m3u8\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"Deutsch\",LANGUAGE=\"de\",URI=\"aac/de.m3u8\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"ac3\",NAME=\"English\",LANGUAGE=\"en\",URI=\"ac3/en.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000,AVERAGE-BANDWIDTH=900000,",
+            "CODECS=\"avc1.4d401f,mp4a.40.2\",RESOLUTION=1280x720,FRAME-RATE=30,AUDIO=\"aac\"\n",
+            "video.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2000000,CODECS=\"hvc1.2.4.L123.B0,ac-3\",AUDIO=\"ac3\"\n",
+            "hevc.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=64000,AVERAGE-BANDWIDTH=64000,CODECS=\"mp4a.40.2\"\n",
+            "audio.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=64000\n",
+            "unknown.m3u8\n",
+        ))
+        .unwrap();
+
+        let six_seconds = media_playlist(6, false);
+        let independent = media_playlist(6, true);
+        let ten_seconds = media_playlist(10, true);
+
+        let mut validator = Validator::empty();
+        validator.enable_apple_authoring();
+
+        let findings = validator.validate_apple_authoring(
+            &master_playlist,
+            vec![
+                ("video.m3u8", &six_seconds),
+                ("hevc.m3u8", &independent),
+                ("audio.m3u8", &six_seconds),
+                ("aac/de.m3u8", &ten_seconds),
+            ],
+        );
+
+        assert_eq!(
+            rules(&findings),
+            vec![
+                (Rule::AppleMissingIFramePlaylists, Location::Playlist),
+                (Rule::AppleIndependentSegments, Location::VariantStream(0)),
+                (Rule::AppleMissingResolution, Location::VariantStream(1)),
+                (Rule::AppleMissingFrameRate, Location::VariantStream(1)),
+                (Rule::AppleMissingAverageBandwidth, Location::VariantStream(1)),
+                (Rule::AppleMissingCodecs, Location::VariantStream(3)),
+                (Rule::AppleMissingResolution, Location::VariantStream(3)),
+                (Rule::AppleMissingFrameRate, Location::VariantStream(3)),
+                (Rule::AppleMissingAverageBandwidth, Location::VariantStream(3)),
+                (Rule::AppleTargetDuration, Location::Rendition(1)),
+                (Rule::AppleAudioGroups, Location::Rendition(2)),
+            ]
+        );
+
+        assert_eq!(
+            findings.last().unwrap().to_string(),
+            "error[apple-audio-groups] rendition 2: the audio group \"ac3\" does not offer the same renditions as the audio group \"aac\" (section 9.13)"
+        );
+
+        validator.disable(Rule::AppleMissingFrameRate);
+
+        assert_eq!(
+            validator
+                .validate_apple_authoring(&master_playlist, vec![])
+                .iter()
+                .filter(|finding| finding.rule == Rule::AppleMissingFrameRate)
+                .count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,557 @@
+//! A linter, which checks playlists against a set of [`Rule`]s.
+//!
+//! Unlike the checks, that are done while building or parsing a playlist,
+//! the rules of this module do not reject a playlist, but return a list of
+//! [`Finding`]s, each with the id of the [`Rule`], a [`Severity`], the
+//! [`Location`] in the playlist and a message. Every rule can be switched off
+//! or reported with a different severity.
+//!
+//! # Example
+//!
+//! ```
+//! use core::convert::TryFrom;
+//! use hls_m3u8::validate::{Location, Rule, Severity, Validator};
+//! use hls_m3u8::MasterPlaylist;
+//!
+//! let playlist = MasterPlaylist::try_from(concat!(
+//!     "#EXTM3U\n",
+//!     "#EXT-X-STREAM-INF:BANDWIDTH=1000000,AVERAGE-BANDWIDTH=1200000\n",
+//!     "low.m3u8\n",
+//! ))?;
+//!
+//! let mut validator = Validator::new();
+//! let findings = validator.validate_master_playlist(&playlist);
+//!
+//! assert_eq!(findings.len(), 2);
+//! assert_eq!(findings[0].rule, Rule::MissingCodecs);
+//! assert_eq!(findings[0].severity, Severity::Warning);
+//! assert_eq!(findings[0].location, Location::VariantStream(0));
+//! assert_eq!(
+//!     findings[1].to_string(),
+//!     "error[average-bandwidth-exceeds-bandwidth] variant stream 0: the AVERAGE-BANDWIDTH 1200000 exceeds the BANDWIDTH 1000000"
+//! );
+//!
+//! validator.disable(Rule::MissingCodecs);
+//! assert_eq!(validator.validate_master_playlist(&playlist).len(), 1);
+//! # Ok::<(), hls_m3u8::Error>(())
+//! ```
+use std::collections::BTreeMap;
+use std::fmt;
+
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+use crate::tags::ExtXKey;
+use crate::types::PlaylistType;
+use crate::{MasterPlaylist, MediaPlaylist};
+
+mod apple;
+
+/// How severe a [`Finding`] is.
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// The playlist could be improved.
+    Info,
+    /// The playlist does not follow a recommendation.
+    Warning,
+    /// The playlist is invalid.
+    Error,
+}
+
+/// A rule, that is checked by the [`Validator`].
+///
+/// The id of a rule is the kebab-case name of the variant (for example
+/// `target-duration-exceeded`), which is returned by the [`Display`]
+/// implementation and can be parsed with [`FromStr`].
+///
+/// [`Display`]: std::fmt::Display
+/// [`FromStr`]: std::str::FromStr
+#[non_exhaustive]
+#[derive(
+    Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Rule {
+    /// The duration of a segment, rounded to the nearest integer, exceeds the
+    /// target duration.
+    ///
+    /// [rfc8216#section-4.3.3.1](https://tools.ietf.org/html/rfc8216#section-4.3.3.1)
+    TargetDurationExceeded,
+    /// A variant stream does not have a `CODECS` attribute.
+    ///
+    /// [rfc8216#section-4.3.4.2](https://tools.ietf.org/html/rfc8216#section-4.3.4.2)
+    MissingCodecs,
+    /// The `AVERAGE-BANDWIDTH` of a variant stream exceeds its `BANDWIDTH`,
+    /// which is the peak bandwidth.
+    ///
+    /// [rfc8216#section-4.3.4.2](https://tools.ietf.org/html/rfc8216#section-4.3.4.2)
+    AverageBandwidthExceedsBandwidth,
+    /// An unencrypted segment follows an encrypted segment, but does not have
+    /// an [`ExtXKey::empty`], so a client would assume, that it is encrypted.
+    MissingEmptyKey,
+    /// A segment of an I-frames only playlist does not have a byte range.
+    ///
+    /// [rfc8216#section-4.3.3.6](https://tools.ietf.org/html/rfc8216#section-4.3.3.6)
+    IFramesOnlyWithoutByteRange,
+    /// A [`PlaylistType::Event`] playlist has an `EXT-X-ENDLIST` tag, so it
+    /// will no longer change and could be a [`PlaylistType::Vod`] playlist.
+    EventWithEndList,
+    /// A variant stream requires a stereoscopic `REQ-VIDEO-LAYOUT`, but none
+    /// of its `CODECS` is able to carry multiple views.
+    VideoLayoutWithoutMultiviewCodec,
+    /// The target duration of a media playlist is not 6 seconds.
+    ///
+    /// Apple HLS authoring specification, section 6.1
+    AppleTargetDuration,
+    /// A variant stream does not have a `CODECS` attribute.
+    ///
+    /// Apple HLS authoring specification, section 9.15
+    AppleMissingCodecs,
+    /// A video variant stream does not have a `RESOLUTION` attribute.
+    ///
+    /// Apple HLS authoring specification, section 9.16
+    AppleMissingResolution,
+    /// A video variant stream does not have a `FRAME-RATE` attribute.
+    ///
+    /// Apple HLS authoring specification, section 9.17
+    AppleMissingFrameRate,
+    /// The master playlist has video variant streams, but no I-frame
+    /// playlists.
+    ///
+    /// Apple HLS authoring specification, section 6.16
+    AppleMissingIFramePlaylists,
+    /// A variant stream does not have an `AVERAGE-BANDWIDTH` attribute.
+    ///
+    /// Apple HLS authoring specification, section 9.12
+    AppleMissingAverageBandwidth,
+    /// The audio groups do not offer the same renditions.
+    ///
+    /// Apple HLS authoring specification, section 9.13
+    AppleAudioGroups,
+    /// Neither the master playlist nor the media playlist of a video variant
+    /// stream has an `EXT-X-INDEPENDENT-SEGMENTS` tag.
+    ///
+    /// Apple HLS authoring specification, section 8.4
+    AppleIndependentSegments,
+}
+
+impl Rule {
+    /// Returns the [`Severity`], with which the rule is reported by default.
+    #[must_use]
+    pub const fn default_severity(self) -> Severity {
+        match self {
+            Self::TargetDurationExceeded
+            | Self::AverageBandwidthExceedsBandwidth
+            | Self::MissingEmptyKey
+            | Self::VideoLayoutWithoutMultiviewCodec
+            | Self::AppleMissingCodecs
+            | Self::AppleMissingResolution
+            | Self::AppleMissingIFramePlaylists
+            | Self::AppleAudioGroups => Severity::Error,
+            Self::MissingCodecs
+            | Self::IFramesOnlyWithoutByteRange
+            | Self::AppleTargetDuration
+            | Self::AppleMissingFrameRate
+            | Self::AppleMissingAverageBandwidth
+            | Self::AppleIndependentSegments => Severity::Warning,
+            Self::EventWithEndList => Severity::Info,
+        }
+    }
+
+    /// Returns the section of the Apple HLS authoring specification, that
+    /// requires the rule, or `None` if it is not an Apple rule.
+    ///
+    /// The Apple rules are not enabled by default, see
+    /// [`Validator::enable_apple_authoring`].
+    #[must_use]
+    pub const fn apple_section(self) -> Option<&'static str> {
+        match self {
+            Self::AppleTargetDuration => Some("6.1"),
+            Self::AppleMissingIFramePlaylists => Some("6.16"),
+            Self::AppleIndependentSegments => Some("8.4"),
+            Self::AppleMissingAverageBandwidth => Some("9.12"),
+            Self::AppleAudioGroups => Some("9.13"),
+            Self::AppleMissingCodecs => Some("9.15"),
+            Self::AppleMissingResolution => Some("9.16"),
+            Self::AppleMissingFrameRate => Some("9.17"),
+            _ => None,
+        }
+    }
+}
+
+/// The location of a [`Finding`] in a playlist.
+#[non_exhaustive]
+#[derive(Ord, PartialOrd, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// The finding applies to the entire playlist.
+    Playlist,
+    /// The index of a [`MasterPlaylist::variant_streams`].
+    VariantStream(usize),
+    /// The index of a [`MasterPlaylist::media`].
+    Rendition(usize),
+    /// The [`MediaSegment::number`] of a segment.
+    ///
+    /// [`MediaSegment::number`]: crate::MediaSegment::number
+    Segment(usize),
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Playlist => write!(f, "playlist"),
+            Self::VariantStream(index) => write!(f, "variant stream {}", index),
+            Self::Rendition(index) => write!(f, "rendition {}", index),
+            Self::Segment(number) => write!(f, "segment {}", number),
+        }
+    }
+}
+
+/// A violation of a [`Rule`], that has been found by the [`Validator`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Finding {
+    /// The violated rule.
+    pub rule: Rule,
+    /// The severity of the violation.
+    pub severity: Severity,
+    /// Where the rule has been violated.
+    pub location: Location,
+    /// A description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity, self.rule, self.location, self.message
+        )?;
+
+        if let Some(section) = self.rule.apple_section() {
+            write!(f, " (section {})", section)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks playlists against the enabled [`Rule`]s.
+///
+/// By default, all rules, except for the opt-in rules of the Apple HLS
+/// authoring specification, are enabled with their
+/// [`Rule::default_severity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validator {
+    rules: BTreeMap<Rule, Severity>,
+}
+
+impl Validator {
+    /// Makes a new [`Validator`] with all rules, except for the Apple rules,
+    /// enabled.
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Makes a new [`Validator`] without any enabled rules.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// Enables a rule with its [`Rule::default_severity`].
+    pub fn enable(&mut self, rule: Rule) -> &mut Self {
+        self.rules.insert(rule, rule.default_severity());
+        self
+    }
+
+    /// Disables a rule.
+    pub fn disable(&mut self, rule: Rule) -> &mut Self {
+        self.rules.remove(&rule);
+        self
+    }
+
+    /// Enables a rule with the provided [`Severity`].
+    pub fn set_severity(&mut self, rule: Rule, severity: Severity) -> &mut Self {
+        self.rules.insert(rule, severity);
+        self
+    }
+
+    /// Returns `true`, if the rule is enabled.
+    #[must_use]
+    pub fn is_enabled(&self, rule: Rule) -> bool { self.rules.contains_key(&rule) }
+
+    /// Checks a [`MasterPlaylist`] and returns the findings in the order of
+    /// their location.
+    #[must_use]
+    pub fn validate_master_playlist(&self, playlist: &MasterPlaylist<'_>) -> Vec<Finding> {
+        let mut findings = vec![];
+
+        for (i, stream) in playlist.variant_streams.iter().enumerate() {
+            let location = Location::VariantStream(i);
+
+            if stream.codecs().is_none() {
+                self.report(
+                    &mut findings,
+                    Rule::MissingCodecs,
+                    location,
+                    "the variant stream has no CODECS attribute",
+                );
+            }
+
+            if let (Some(codecs), Some(video_layout)) = (stream.codecs(), stream.video_layout()) {
+                if let Err(error) = video_layout.validate(codecs) {
+                    self.report(
+                        &mut findings,
+                        Rule::VideoLayoutWithoutMultiviewCodec,
+                        location,
+                        error.to_string(),
+                    );
+                }
+            }
+
+            if let Some(average_bandwidth) = stream.average_bandwidth() {
+                if average_bandwidth > stream.bandwidth() {
+                    self.report(
+                        &mut findings,
+                        Rule::AverageBandwidthExceedsBandwidth,
+                        location,
+                        format!(
+                            "the AVERAGE-BANDWIDTH {} exceeds the BANDWIDTH {}",
+                            average_bandwidth,
+                            stream.bandwidth()
+                        ),
+                    );
+                }
+            }
+        }
+
+        findings
+    }
+
+    /// Checks a [`MediaPlaylist`] and returns the findings in the order of
+    /// their location.
+    #[must_use]
+    pub fn validate_media_playlist(&self, playlist: &MediaPlaylist<'_>) -> Vec<Finding> {
+        let mut findings = vec![];
+
+        if playlist.playlist_type == Some(PlaylistType::Event) && playlist.has_end_list {
+            self.report(
+                &mut findings,
+                Rule::EventWithEndList,
+                Location::Playlist,
+                "the EVENT playlist has ended and could be a VOD playlist",
+            );
+        }
+
+        let mut is_encrypted = false;
+
+        for segment in playlist.segments.values() {
+            let location = Location::Segment(segment.number());
+            let duration = segment.duration.duration();
+
+            // the duration is rounded to the nearest integer:
+            if segment.duration.rounded_duration() > playlist.target_duration {
+                self.report(
+                    &mut findings,
+                    Rule::TargetDurationExceeded,
+                    location,
+                    format!(
+                        "the duration {:?} exceeds the target duration {:?}",
+                        duration, playlist.target_duration
+                    ),
+                );
+            }
+
+            if segment.keys.is_empty() {
+                if is_encrypted {
+                    self.report(
+                        &mut findings,
+                        Rule::MissingEmptyKey,
+                        location,
+                        "the segment follows an encrypted segment, but has no EXT-X-KEY with METHOD=NONE",
+                    );
+                }
+            } else {
+                is_encrypted = segment.keys.iter().any(ExtXKey::is_some);
+            }
+
+            if playlist.has_i_frames_only && segment.byte_range.is_none() {
+                self.report(
+                    &mut findings,
+                    Rule::IFramesOnlyWithoutByteRange,
+                    location,
+                    "the segment of the I-frames only playlist has no EXT-X-BYTERANGE",
+                );
+            }
+        }
+
+        findings
+    }
+
+    fn report<T: Into<String>>(
+        &self,
+        findings: &mut Vec<Finding>,
+        rule: Rule,
+        location: Location,
+        message: T,
+    ) {
+        if let Some(severity) = self.rules.get(&rule) {
+            findings.push(Finding {
+                rule,
+                severity: *severity,
+                location,
+                message: message.into(),
+            });
+        }
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self {
+            rules: Rule::iter()
+                .filter(|rule| rule.apple_section().is_none())
+                .map(|rule| (rule, rule.default_severity()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tags::{ExtInf, ExtXByteRange};
+    use crate::types::{DecryptionKey, EncryptionMethod};
+    use crate::MediaSegment;
+    use core::convert::TryFrom;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn rules(findings: &[Finding]) -> Vec<(Rule, Location)> {
+        findings.iter().map(|f| (f.rule, f.location)).collect()
+    }
+
+    #[test]
+    fn test_rule_id() {
+        assert_eq!(
+            Rule::IFramesOnlyWithoutByteRange.to_string(),
+            "i-frames-only-without-byte-range"
+        );
+        assert_eq!(
+            "target-duration-exceeded".parse::<Rule>().unwrap(),
+            Rule::TargetDurationExceeded
+        );
+        assert_eq!(Severity::Warning.to_string(), "warning");
+    }
+
+    #[test]
+    fn test_validate_media_playlist() {
+        let key = ExtXKey::new(DecryptionKey::new(
+            EncryptionMethod::Aes128,
+            "https://example.com/key",
+        ));
+
+        let segment = |seconds: u64, keys: Vec<ExtXKey<'static>>| {
+            MediaSegment::builder()
+                .duration(ExtInf::new(Duration::from_millis(seconds)))
+                .keys(keys)
+                .uri("a.ts")
+                .build()
+                .unwrap()
+        };
+
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .allowable_excess_duration(Duration::from_secs(1))
+            .playlist_type(PlaylistType::Event)
+            .has_end_list(true)
+            .segments(vec![
+                segment(10_000, vec![key.clone()]),
+                segment(10_600, vec![]),
+                segment(9_000, vec![ExtXKey::empty()]),
+                segment(9_000, vec![]),
+                segment(9_000, vec![key]),
+                segment(9_000, vec![]),
+            ])
+            .build()
+            .unwrap();
+
+        let mut validator = Validator::new();
+
+        assert_eq!(
+            rules(&validator.validate_media_playlist(&playlist)),
+            vec![
+                (Rule::EventWithEndList, Location::Playlist),
+                (Rule::TargetDurationExceeded, Location::Segment(1)),
+                (Rule::MissingEmptyKey, Location::Segment(1)),
+                (Rule::MissingEmptyKey, Location::Segment(5)),
+            ]
+        );
+
+        validator
+            .disable(Rule::MissingEmptyKey)
+            .set_severity(Rule::EventWithEndList, Severity::Error);
+
+        let findings = validator.validate_media_playlist(&playlist);
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert!(!validator.is_enabled(Rule::MissingEmptyKey));
+        assert!(Validator::empty().validate_media_playlist(&playlist).is_empty());
+    }
+
+    #[test]
+    fn test_i_frames_only() {
+        let playlist = MediaPlaylist::builder()
+            .target_duration(Duration::from_secs(10))
+            .has_i_frames_only(true)
+            .segments(vec![
+                MediaSegment::builder()
+                    .duration(ExtInf::new(Duration::from_secs(4)))
+                    .byte_range(ExtXByteRange::from(0..100))
+                    .uri("a.ts")
+                    .build()
+                    .unwrap(),
+                MediaSegment::builder()
+                    .duration(ExtInf::new(Duration::from_secs(4)))
+                    .uri("a.ts")
+                    .build()
+                    .unwrap(),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            rules(&Validator::new().validate_media_playlist(&playlist)),
+            vec![(Rule::IFramesOnlyWithoutByteRange, Location::Segment(1))]
+        );
+    }
+
+    #[test]
+    fn test_validate_master_playlist() {
+        let playlist = MasterPlaylist::try_from(concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000,CODECS=\"avc1.4d401f\"\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2000000,AVERAGE-BANDWIDTH=2000001\n",
+            "high.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=3000000,CODECS=\"avc1.4d401f\",",
+            "REQ-VIDEO-LAYOUT=\"CH-STEREO\"\n",
+            "stereo.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=3000000,CODECS=\"hvc1.6.40.L120.B0\",",
+            "REQ-VIDEO-LAYOUT=\"CH-STEREO\"\n",
+            "mv-hevc.m3u8\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            rules(&Validator::new().validate_master_playlist(&playlist)),
+            vec![
+                (Rule::MissingCodecs, Location::VariantStream(1)),
+                (Rule::AverageBandwidthExceedsBandwidth, Location::VariantStream(1)),
+                (Rule::VideoLayoutWithoutMultiviewCodec, Location::VariantStream(2)),
+            ]
+        );
+    }
+}